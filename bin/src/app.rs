@@ -1,5 +1,6 @@
 use commonx::web_info;
 use infrastructurex::cache::CacheManager;
+use infrastructurex::container::oper_log_writer::close_oper_log_writer;
 use infrastructurex::persistence::init::init_db;
 use infrastructurex::processor::init::init_worker;
 use interfacesx::init::start_server;
@@ -27,5 +28,8 @@ impl App {
         init_worker().await.unwrap();
         web_info!("{MODULE_NAME}: 3. 启动web服务 ...");
         start_server().await.unwrap();
+        // 服务停止后写完队列中的操作日志
+        close_oper_log_writer().await;
+        web_info!("{MODULE_NAME}: 应用退出");
     }
 }
@@ -4,6 +4,20 @@ mod m20220101_000001_create_table;
 mod m20260206_020910_sys_job;
 mod m20260207_124414_init;
 mod m20260211_014728_corn_job;
+mod m20261019_000001_user_login_lock;
+mod m20261019_000002_user_totp;
+mod m20261019_000003_password_policy;
+mod m20261019_000004_user_identity;
+mod m20261019_000005_user_auth_source;
+mod m20261019_000006_user_api_keys;
+mod m20261019_000007_user_sessions;
+mod m20261019_000008_tenant;
+mod m20261019_000009_dept_data_scope;
+mod m20261019_000010_oper_log_query;
+mod m20261019_000011_oper_log_detail;
+mod m20261019_000012_oper_log_business;
+mod m20261019_000013_login_log;
+mod m20261019_000014_user_totp_step;
 
 pub struct Migrator;
 
@@ -15,6 +29,20 @@ impl MigratorTrait for Migrator {
             Box::new(m20260206_020910_sys_job::Migration),
             Box::new(m20260207_124414_init::Migration),
             Box::new(m20260211_014728_corn_job::Migration),
+            Box::new(m20261019_000001_user_login_lock::Migration),
+            Box::new(m20261019_000002_user_totp::Migration),
+            Box::new(m20261019_000003_password_policy::Migration),
+            Box::new(m20261019_000004_user_identity::Migration),
+            Box::new(m20261019_000005_user_auth_source::Migration),
+            Box::new(m20261019_000006_user_api_keys::Migration),
+            Box::new(m20261019_000007_user_sessions::Migration),
+            Box::new(m20261019_000008_tenant::Migration),
+            Box::new(m20261019_000009_dept_data_scope::Migration),
+            Box::new(m20261019_000010_oper_log_query::Migration),
+            Box::new(m20261019_000011_oper_log_detail::Migration),
+            Box::new(m20261019_000012_oper_log_business::Migration),
+            Box::new(m20261019_000013_login_log::Migration),
+            Box::new(m20261019_000014_user_totp_step::Migration),
         ]
     }
 }
@@ -0,0 +1,49 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 用户表增加TOTP双因素认证字段
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Users::Table)
+                    .add_column_if_not_exists(ColumnDef::new(Users::TotpSecret).string().null())
+                    .add_column_if_not_exists(
+                        ColumnDef::new(Users::TotpEnabled)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .add_column_if_not_exists(
+                        ColumnDef::new(Users::TotpRecoveryCodes).text().null(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Users::Table)
+                    .drop_column(Users::TotpSecret)
+                    .drop_column(Users::TotpEnabled)
+                    .drop_column(Users::TotpRecoveryCodes)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    TotpSecret,
+    TotpEnabled,
+    TotpRecoveryCodes,
+}
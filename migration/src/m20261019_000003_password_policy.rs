@@ -0,0 +1,99 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 用户表增加密码修改时间
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Users::Table)
+                    .add_column_if_not_exists(
+                        ColumnDef::new(Users::PasswordChangedAt).timestamp().null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // 密码历史表
+        manager
+            .create_table(
+                Table::create()
+                    .table(UserPasswordHistory::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(UserPasswordHistory::Id)
+                            .big_integer()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(UserPasswordHistory::UserId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(UserPasswordHistory::Password)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(UserPasswordHistory::CreatedAt)
+                            .timestamp()
+                            .default(Expr::current_timestamp())
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_user_password_history_user_id")
+                    .table(UserPasswordHistory::Table)
+                    .col(UserPasswordHistory::UserId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(UserPasswordHistory::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Users::Table)
+                    .drop_column(Users::PasswordChangedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    PasswordChangedAt,
+}
+
+#[derive(DeriveIden)]
+enum UserPasswordHistory {
+    Table,
+    Id,
+    UserId,
+    Password,
+    CreatedAt,
+}
@@ -0,0 +1,89 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 外部身份(OIDC等)与本地用户的绑定关系
+        manager
+            .create_table(
+                Table::create()
+                    .table(UserIdentities::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(UserIdentities::Id)
+                            .big_integer()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(UserIdentities::UserId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(UserIdentities::Provider)
+                            .string_len(64)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(UserIdentities::Subject).string().not_null())
+                    .col(ColumnDef::new(UserIdentities::Email).string().null())
+                    .col(
+                        ColumnDef::new(UserIdentities::CreatedAt)
+                            .timestamp()
+                            .default(Expr::current_timestamp())
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("uk_user_identities_provider_subject")
+                    .table(UserIdentities::Table)
+                    .col(UserIdentities::Provider)
+                    .col(UserIdentities::Subject)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_user_identities_user_id")
+                    .table(UserIdentities::Table)
+                    .col(UserIdentities::UserId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(UserIdentities::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum UserIdentities {
+    Table,
+    Id,
+    UserId,
+    Provider,
+    Subject,
+    Email,
+    CreatedAt,
+}
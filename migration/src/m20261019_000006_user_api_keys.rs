@@ -0,0 +1,97 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // API Key表, 只保存key的摘要
+        manager
+            .create_table(
+                Table::create()
+                    .table(UserApiKeys::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(UserApiKeys::Id)
+                            .big_integer()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(UserApiKeys::UserId).big_integer().not_null())
+                    .col(ColumnDef::new(UserApiKeys::Name).string_len(64).not_null())
+                    .col(
+                        ColumnDef::new(UserApiKeys::Prefix)
+                            .string_len(16)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(UserApiKeys::KeyHash)
+                            .string_len(64)
+                            .not_null(),
+                    )
+                    // JSON数组
+                    .col(ColumnDef::new(UserApiKeys::Scopes).text().not_null())
+                    .col(ColumnDef::new(UserApiKeys::ExpiresAt).timestamp().null())
+                    .col(ColumnDef::new(UserApiKeys::LastUsedAt).timestamp().null())
+                    .col(ColumnDef::new(UserApiKeys::RevokedAt).timestamp().null())
+                    .col(
+                        ColumnDef::new(UserApiKeys::CreatedAt)
+                            .timestamp()
+                            .default(Expr::current_timestamp())
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("uk_user_api_keys_prefix")
+                    .table(UserApiKeys::Table)
+                    .col(UserApiKeys::Prefix)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_user_api_keys_user_id")
+                    .table(UserApiKeys::Table)
+                    .col(UserApiKeys::UserId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(UserApiKeys::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum UserApiKeys {
+    Table,
+    Id,
+    UserId,
+    Name,
+    Prefix,
+    KeyHash,
+    Scopes,
+    ExpiresAt,
+    LastUsedAt,
+    RevokedAt,
+    CreatedAt,
+}
@@ -0,0 +1,90 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 登录会话表, 主键为token中的token_id
+        manager
+            .create_table(
+                Table::create()
+                    .table(UserSessions::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(UserSessions::TokenId)
+                            .big_integer()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(UserSessions::UserId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(UserSessions::Username)
+                            .string_len(64)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(UserSessions::Ip).string_len(64).not_null())
+                    .col(
+                        ColumnDef::new(UserSessions::UserAgent)
+                            .string_len(512)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(UserSessions::CreatedAt)
+                            .timestamp()
+                            .default(Expr::current_timestamp())
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(UserSessions::LastSeenAt).timestamp().null())
+                    .col(
+                        ColumnDef::new(UserSessions::ExpiresAt)
+                            .timestamp()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(UserSessions::RevokedAt).timestamp().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_user_sessions_user_id")
+                    .table(UserSessions::Table)
+                    .col(UserSessions::UserId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(UserSessions::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum UserSessions {
+    Table,
+    TokenId,
+    UserId,
+    Username,
+    Ip,
+    UserAgent,
+    CreatedAt,
+    LastSeenAt,
+    ExpiresAt,
+    RevokedAt,
+}
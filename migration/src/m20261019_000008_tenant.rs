@@ -0,0 +1,78 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 用户、会话、定时任务、操作日志增加租户ID, 已有数据归属默认租户0
+        for table in [
+            Tenancy::Users,
+            Tenancy::UserSessions,
+            Tenancy::CornJob,
+            Tenancy::SysOperLog,
+        ] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(table)
+                        .add_column_if_not_exists(
+                            ColumnDef::new(Tenancy::TenantId)
+                                .big_integer()
+                                .not_null()
+                                .default(0),
+                        )
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        for (name, table) in [
+            ("idx_users_tenant_id", Tenancy::Users),
+            ("idx_user_sessions_tenant_id", Tenancy::UserSessions),
+            ("idx_corn_job_tenant_id", Tenancy::CornJob),
+            ("idx_sys_oper_log_tenant_id", Tenancy::SysOperLog),
+        ] {
+            manager
+                .create_index(
+                    Index::create()
+                        .if_not_exists()
+                        .name(name)
+                        .table(table)
+                        .col(Tenancy::TenantId)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for table in [
+            Tenancy::Users,
+            Tenancy::UserSessions,
+            Tenancy::CornJob,
+            Tenancy::SysOperLog,
+        ] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(table)
+                        .drop_column(Tenancy::TenantId)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Tenancy {
+    Users,
+    UserSessions,
+    CornJob,
+    SysOperLog,
+    TenantId,
+}
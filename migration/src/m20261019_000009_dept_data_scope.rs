@@ -0,0 +1,161 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 部门/组织树
+        manager
+            .create_table(
+                Table::create()
+                    .table(SysDept::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(SysDept::Id)
+                            .big_integer()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(SysDept::ParentId)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(ColumnDef::new(SysDept::Name).string_len(64).not_null())
+                    .col(
+                        ColumnDef::new(SysDept::OrderNum)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(ColumnDef::new(SysDept::Status).string_len(8).null())
+                    .col(
+                        ColumnDef::new(SysDept::TenantId)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(SysDept::CreatedAt)
+                            .timestamp()
+                            .default(Expr::current_timestamp())
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(SysDept::UpdatedAt)
+                            .timestamp()
+                            .default(Expr::current_timestamp())
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_sys_dept_parent_id")
+                    .table(SysDept::Table)
+                    .col(SysDept::ParentId)
+                    .to_owned(),
+            )
+            .await?;
+
+        // 用户、定时任务、操作日志归属部门
+        for table in [DataScope::Users, DataScope::CornJob, DataScope::SysOperLog] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(table)
+                        .add_column_if_not_exists(
+                            ColumnDef::new(DataScope::DeptId).big_integer().null(),
+                        )
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        // 定时任务记录创建人, 用于"仅本人"数据范围
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(DataScope::CornJob)
+                    .add_column_if_not_exists(
+                        ColumnDef::new(DataScope::CreateBy)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        for (name, table) in [
+            ("idx_users_dept_id", DataScope::Users),
+            ("idx_corn_job_dept_id", DataScope::CornJob),
+            ("idx_sys_oper_log_dept_id", DataScope::SysOperLog),
+        ] {
+            manager
+                .create_index(
+                    Index::create()
+                        .if_not_exists()
+                        .name(name)
+                        .table(table)
+                        .col(DataScope::DeptId)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(DataScope::CornJob)
+                    .drop_column(DataScope::CreateBy)
+                    .to_owned(),
+            )
+            .await?;
+        for table in [DataScope::Users, DataScope::CornJob, DataScope::SysOperLog] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(table)
+                        .drop_column(DataScope::DeptId)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        manager
+            .drop_table(Table::drop().table(SysDept::Table).if_exists().to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum SysDept {
+    Table,
+    Id,
+    ParentId,
+    Name,
+    OrderNum,
+    Status,
+    TenantId,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum DataScope {
+    Users,
+    CornJob,
+    SysOperLog,
+    DeptId,
+    CreateBy,
+}
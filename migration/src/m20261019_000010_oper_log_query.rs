@@ -0,0 +1,63 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 操作状态: 0成功 1失败
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(SysOperLog::Table)
+                    .add_column_if_not_exists(
+                        ColumnDef::new(SysOperLog::Status)
+                            .small_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // 按时间范围查询及过期清理
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_sys_oper_log_oper_time")
+                    .table(SysOperLog::Table)
+                    .col(SysOperLog::OperTime)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .if_exists()
+                    .name("idx_sys_oper_log_oper_time")
+                    .table(SysOperLog::Table)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(SysOperLog::Table)
+                    .drop_column(SysOperLog::Status)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum SysOperLog {
+    Table,
+    Status,
+    OperTime,
+}
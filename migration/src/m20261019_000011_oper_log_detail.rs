@@ -0,0 +1,91 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 查询参数、响应状态、错误信息及请求标识, oper_param改为记录请求体
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(SysOperLog::Table)
+                    .add_column_if_not_exists(
+                        ColumnDef::new(SysOperLog::OperQuery)
+                            .text()
+                            .not_null()
+                            .default(""),
+                    )
+                    .add_column_if_not_exists(
+                        ColumnDef::new(SysOperLog::HttpStatus)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .add_column_if_not_exists(ColumnDef::new(SysOperLog::BizCode).integer().null())
+                    .add_column_if_not_exists(ColumnDef::new(SysOperLog::ErrorMsg).text().null())
+                    .add_column_if_not_exists(
+                        ColumnDef::new(SysOperLog::UserAgent)
+                            .string_len(512)
+                            .not_null()
+                            .default(""),
+                    )
+                    .add_column_if_not_exists(
+                        ColumnDef::new(SysOperLog::RequestId)
+                            .string_len(64)
+                            .not_null()
+                            .default(""),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_sys_oper_log_request_id")
+                    .table(SysOperLog::Table)
+                    .col(SysOperLog::RequestId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .if_exists()
+                    .name("idx_sys_oper_log_request_id")
+                    .table(SysOperLog::Table)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(SysOperLog::Table)
+                    .drop_column(SysOperLog::OperQuery)
+                    .drop_column(SysOperLog::HttpStatus)
+                    .drop_column(SysOperLog::BizCode)
+                    .drop_column(SysOperLog::ErrorMsg)
+                    .drop_column(SysOperLog::UserAgent)
+                    .drop_column(SysOperLog::RequestId)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum SysOperLog {
+    Table,
+    OperQuery,
+    HttpStatus,
+    BizCode,
+    ErrorMsg,
+    UserAgent,
+    RequestId,
+}
@@ -0,0 +1,49 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 路由声明的操作名称及业务类型
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(SysOperLog::Table)
+                    .add_column_if_not_exists(
+                        ColumnDef::new(SysOperLog::Title)
+                            .string_len(128)
+                            .not_null()
+                            .default(""),
+                    )
+                    .add_column_if_not_exists(
+                        ColumnDef::new(SysOperLog::BusinessType)
+                            .small_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(SysOperLog::Table)
+                    .drop_column(SysOperLog::Title)
+                    .drop_column(SysOperLog::BusinessType)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum SysOperLog {
+    Table,
+    Title,
+    BusinessType,
+}
@@ -0,0 +1,169 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 登录审计日志, 成功及失败的登录都会记录
+        manager
+            .create_table(
+                Table::create()
+                    .table(SysLoginLog::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(SysLoginLog::Id)
+                            .big_integer()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(SysLoginLog::UserId).big_integer().null())
+                    .col(
+                        ColumnDef::new(SysLoginLog::Username)
+                            .string_len(64)
+                            .not_null()
+                            .default(""),
+                    )
+                    .col(
+                        ColumnDef::new(SysLoginLog::LoginType)
+                            .string_len(32)
+                            .not_null()
+                            .default(""),
+                    )
+                    .col(
+                        ColumnDef::new(SysLoginLog::Ip)
+                            .string_len(64)
+                            .not_null()
+                            .default(""),
+                    )
+                    .col(
+                        ColumnDef::new(SysLoginLog::UserAgent)
+                            .string_len(512)
+                            .not_null()
+                            .default(""),
+                    )
+                    .col(
+                        ColumnDef::new(SysLoginLog::Status)
+                            .small_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(SysLoginLog::FailReason)
+                            .string_len(512)
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(SysLoginLog::CaptchaUsed)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .col(
+                        ColumnDef::new(SysLoginLog::TwoFactorUsed)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .col(
+                        ColumnDef::new(SysLoginLog::RequestId)
+                            .string_len(64)
+                            .not_null()
+                            .default(""),
+                    )
+                    .col(
+                        ColumnDef::new(SysLoginLog::TenantId)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(ColumnDef::new(SysLoginLog::DeptId).big_integer().null())
+                    .col(
+                        ColumnDef::new(SysLoginLog::LoginTime)
+                            .timestamp()
+                            .default(Expr::current_timestamp())
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        for (name, col) in [
+            ("idx_sys_login_log_login_time", SysLoginLog::LoginTime),
+            ("idx_sys_login_log_username", SysLoginLog::Username),
+        ] {
+            manager
+                .create_index(
+                    Index::create()
+                        .if_not_exists()
+                        .name(name)
+                        .table(SysLoginLog::Table)
+                        .col(col)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        // 用户最近一次登录
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Users::Table)
+                    .add_column_if_not_exists(
+                        ColumnDef::new(Users::LastLoginIp).string_len(64).null(),
+                    )
+                    .add_column_if_not_exists(
+                        ColumnDef::new(Users::LastLoginTime).timestamp().null(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Users::Table)
+                    .drop_column(Users::LastLoginIp)
+                    .drop_column(Users::LastLoginTime)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(SysLoginLog::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum SysLoginLog {
+    Table,
+    Id,
+    UserId,
+    Username,
+    LoginType,
+    Ip,
+    UserAgent,
+    Status,
+    FailReason,
+    CaptchaUsed,
+    TwoFactorUsed,
+    RequestId,
+    TenantId,
+    DeptId,
+    LoginTime,
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    LastLoginIp,
+    LastLoginTime,
+}
@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 use serde_variant::to_variant_name;
 use serde_yaml::from_str;
-use std::{env, fs};
+use std::{collections::HashMap, env, fs};
 
 use crate::error::AppError;
 
@@ -16,6 +16,8 @@ pub struct Config {
     pub auth: Auth,
     #[serde(default)]
     pub workers: Workers,
+    #[serde(default)]
+    pub oper_log: OperLogConfig,
 }
 
 impl Config {
@@ -23,6 +25,11 @@ impl Config {
         let env: String = env::var("environment").unwrap_or_else(|_| "dev".to_string());
         Self::load_config(format!("config.{}", env)).unwrap()
     }
+    /// 重新读取配置文件, 用于运行期间可热更新的配置项
+    pub fn reload() -> Result<Self, AppError> {
+        let env: String = env::var("environment").unwrap_or_else(|_| "dev".to_string());
+        Self::load_config(format!("config.{}", env))
+    }
     fn load_config(env: String) -> Result<Self, AppError> {
         // 这里应该继续实现加载配置文件的逻辑
         let file_path = env::current_dir()
@@ -50,6 +57,9 @@ pub struct Server {
     pub upload_dir: String,
     pub ssl: Ssl,
     pub middlewares: Middlewares,
+    /// 受信任的代理地址, 支持单个IP或CIDR, 只有来自这些地址的转发请求头才会被采用
+    #[serde(default)]
+    pub trusted_proxies: Vec<String>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -59,6 +69,18 @@ pub struct Middlewares {
     pub timeout_request: Option<TimeoutMiddleware>,
     pub limit_payload: Option<String>,
     pub compression: Option<EnableMiddleware>,
+    pub cors: Option<CorsConfig>,
+    #[serde(default)]
+    pub rate_limit: RateLimitConfig,
+    #[serde(default)]
+    pub ip_filter: IpFilterConfig,
+}
+
+impl Middlewares {
+    /// 未配置的开关视为关闭
+    pub fn is_enabled(middleware: &Option<EnableMiddleware>) -> bool {
+        middleware.as_ref().is_some_and(|m| m.enable)
+    }
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -66,6 +88,118 @@ pub struct EnableMiddleware {
     pub enable: bool,
 }
 
+/// 跨域配置, 列表为空或包含`*`时表示允许任意值
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct CorsConfig {
+    pub enable: bool,
+    pub allow_origins: Vec<String>,
+    pub allow_methods: Vec<String>,
+    pub allow_headers: Vec<String>,
+    /// 允许前端读取的响应头
+    pub expose_headers: Vec<String>,
+    /// 允许携带cookie, 需明确配置allow_origins, 此时方法及请求头的任意值改为回显请求中的值
+    pub allow_credentials: bool,
+    /// 预检请求缓存时长(秒)
+    pub max_age: Option<u64>,
+}
+
+impl Default for CorsConfig {
+    fn default() -> Self {
+        Self {
+            enable: true,
+            allow_origins: vec!["*".to_string()],
+            allow_methods: vec!["*".to_string()],
+            allow_headers: vec!["*".to_string()],
+            expose_headers: [
+                "x-request-id",
+                "ratelimit-limit",
+                "ratelimit-remaining",
+                "ratelimit-reset",
+                "retry-after",
+            ]
+            .map(String::from)
+            .to_vec(),
+            allow_credentials: false,
+            max_age: None,
+        }
+    }
+}
+
+/// IP访问控制, 按客户端IP匹配, 支持单个IP或CIDR; 可通过接口重新加载
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct IpFilterConfig {
+    pub enable: bool,
+    /// 全局规则, 作用于所有请求
+    #[serde(flatten)]
+    pub global: IpRuleConfig,
+    /// 分组规则, 由RouterGroup按名称引用
+    pub groups: HashMap<String, IpRuleConfig>,
+}
+
+/// 先匹配deny, 命中即拒绝; allow不为空时只放行其中的地址
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct IpRuleConfig {
+    pub allow: Vec<String>,
+    pub deny: Vec<String>,
+}
+
+/// 限流配置, 计数存放在缓存中, 使用redis时多实例共享限额
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct RateLimitConfig {
+    /// 是否对所有接口启用默认规则, 路由单独配置的规则不受此开关影响
+    pub enable: bool,
+    #[serde(flatten)]
+    pub rule: RateLimitRule,
+}
+
+/// 限流规则, 滑动窗口计数: 窗口内请求数超过limit时拒绝
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct RateLimitRule {
+    pub key_by: RateLimitKey,
+    /// 窗口内允许的请求数
+    pub limit: u64,
+    /// 窗口时长(秒)
+    pub window_seconds: u64,
+}
+
+impl Default for RateLimitRule {
+    fn default() -> Self {
+        Self {
+            key_by: RateLimitKey::Ip,
+            limit: 100,
+            window_seconds: 60,
+        }
+    }
+}
+
+impl RateLimitRule {
+    pub fn new(key_by: RateLimitKey, limit: u64, window_seconds: u64) -> Self {
+        Self {
+            key_by,
+            limit,
+            window_seconds,
+        }
+    }
+}
+
+/// 限流维度
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RateLimitKey {
+    /// 按客户端IP
+    #[default]
+    Ip,
+    /// 按登录用户, 未登录时按IP
+    User,
+    /// 按路由, 所有客户端共享限额
+    Route,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct TimeoutMiddleware {
     pub enable: bool,
@@ -87,6 +221,43 @@ pub struct Logger {
     pub log_dir: String,
     pub web_file_name: String,
     pub api_file_name: String,
+    #[serde(default)]
+    pub redact: RedactConfig,
+}
+
+/// 日志脱敏配置, 作用于请求日志、操作日志及web日志输出
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct RedactConfig {
+    pub enable: bool,
+    /// 敏感字段名, 忽略大小写, 字段名包含其中任一项即脱敏
+    pub fields: Vec<String>,
+    /// 敏感请求头, 忽略大小写
+    pub headers: Vec<String>,
+    /// 脱敏后的替换值
+    pub mask: String,
+}
+
+impl Default for RedactConfig {
+    fn default() -> Self {
+        Self {
+            enable: true,
+            fields: [
+                "password",
+                "pwd",
+                "captcha",
+                "token",
+                "secret",
+                "credential",
+            ]
+            .map(String::from)
+            .to_vec(),
+            headers: ["authorization", "cookie", "set-cookie", "x-api-key"]
+                .map(String::from)
+                .to_vec(),
+            mask: "******".to_string(),
+        }
+    }
 }
 
 #[derive(Debug, Default, Clone, Deserialize, Serialize)]
@@ -157,18 +328,530 @@ pub struct DatabaseConfig {
 pub struct Auth {
     /// JWT authentication config
     pub jwt: JWT,
+    /// 登录失败限制
+    #[serde(default)]
+    pub login_limit: LoginLimit,
+    /// TOTP双因素认证
+    #[serde(default)]
+    pub totp: TotpConfig,
+    /// 密码策略
+    #[serde(default)]
+    pub password_policy: PasswordPolicy,
+    /// 找回密码
+    #[serde(default)]
+    pub password_reset: PasswordReset,
+    /// 自助注册
+    #[serde(default)]
+    pub register: RegisterConfig,
+    /// 图形验证码
+    #[serde(default)]
+    pub captcha: CaptchaConfig,
+    /// OIDC单点登录
+    #[serde(default)]
+    pub oidc: OidcConfig,
+    /// LDAP认证
+    #[serde(default)]
+    pub ldap: LdapConfig,
+    /// API Key
+    #[serde(default)]
+    pub api_key: ApiKeyConfig,
+    /// 登录会话
+    #[serde(default)]
+    pub session: SessionConfig,
+    /// 数据权限
+    #[serde(default)]
+    pub data_scope: DataScopeConfig,
+}
+
+/// 数据权限范围
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DataScopeKind {
+    /// 全部数据
+    #[default]
+    All,
+    /// 本部门及下级部门
+    DeptAndChildren,
+    /// 仅本部门
+    Dept,
+    /// 仅本人创建的数据
+    Own,
+}
+
+/// 数据权限配置, 按角色指定可见的数据范围
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct DataScopeConfig {
+    /// 未配置的角色使用的数据范围
+    pub default_scope: DataScopeKind,
+    pub roles: Vec<RoleDataScope>,
+}
+
+impl DataScopeConfig {
+    pub fn scope_of(&self, role_id: i64) -> DataScopeKind {
+        self.roles
+            .iter()
+            .find(|r| r.role_id == role_id)
+            .map(|r| r.scope)
+            .unwrap_or(self.default_scope)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RoleDataScope {
+    pub role_id: i64,
+    pub scope: DataScopeKind,
+}
+
+/// 登录会话配置, 每次登录记录一条会话, 吊销后对应token立即失效
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct SessionConfig {
+    pub enable: bool,
+    /// 最近活跃时间的更新间隔(秒), 同时也是重启后从数据库同步吊销状态的间隔
+    pub touch_interval: u64,
+    /// 可查看、吊销所有用户会话的角色
+    pub admin_role_ids: Vec<i64>,
+}
+
+impl Default for SessionConfig {
+    fn default() -> Self {
+        Self {
+            enable: true,
+            touch_interval: 60,
+            admin_role_ids: vec![],
+        }
+    }
+}
+
+/// API Key(个人访问令牌)配置, 供脚本、CI等机器客户端使用
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ApiKeyConfig {
+    pub enable: bool,
+    /// 携带API Key的请求头
+    pub header: String,
+    /// 每个用户最多可创建的有效API Key数量
+    pub max_per_user: u64,
+    /// 最长有效期(天), 0表示允许永不过期
+    pub max_ttl_days: i64,
+    /// 最近使用时间的更新间隔(秒), 避免每次请求都写库
+    pub touch_interval: i64,
+}
+
+impl Default for ApiKeyConfig {
+    fn default() -> Self {
+        Self {
+            enable: true,
+            header: "X-API-Key".to_string(),
+            max_per_user: 10,
+            max_ttl_days: 365,
+            touch_interval: 60,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LdapMode {
+    /// 认证来源为ldap的账号及本地不存在的账号使用LDAP认证, 本地账号(如内置管理员)仍使用本地密码
+    #[default]
+    Global,
+    /// 仅认证来源为ldap的账号使用LDAP认证
+    PerUser,
+}
+
+/// LDAP/AD 认证配置
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct LdapConfig {
+    pub enable: bool,
+    pub mode: LdapMode,
+    /// 例如 ldap://127.0.0.1:389 或 ldaps://ldap.example.com:636
+    pub url: String,
+    pub starttls: bool,
+    /// 连接超时(秒)
+    pub timeout: u64,
+    /// 查询用户的服务账号, 为空时使用 user_dn_template 直接以用户身份bind
+    pub bind_dn: Option<String>,
+    pub bind_password: Option<String>,
+    /// 用户查询的起始DN
+    pub base_dn: String,
+    /// 用户查询条件, `{username}` 会被替换为转义后的用户名
+    pub user_filter: String,
+    /// 用户DN模板, 例如 `uid={username},ou=people,dc=example,dc=org`
+    pub user_dn_template: Option<String>,
+    pub attr_name: String,
+    pub attr_email: String,
+    pub attr_phone: String,
+    /// 用户所属组的属性, 值为组DN
+    pub attr_groups: String,
+    /// 登录时将姓名、邮箱、电话同步到本地用户
+    pub sync_profile: bool,
+    /// 本地不存在的账号认证通过后自动创建
+    pub auto_provision: bool,
+    /// 自动创建用户且未匹配到组映射时使用的角色
+    pub default_role_id: i64,
+    /// LDAP组到角色的映射, 按顺序取第一个匹配项
+    pub group_roles: Vec<LdapGroupRole>,
+}
+
+impl Default for LdapConfig {
+    fn default() -> Self {
+        Self {
+            enable: false,
+            mode: LdapMode::Global,
+            url: "ldap://127.0.0.1:389".to_string(),
+            starttls: false,
+            timeout: 5,
+            bind_dn: None,
+            bind_password: None,
+            base_dn: String::new(),
+            user_filter: "(uid={username})".to_string(),
+            user_dn_template: None,
+            attr_name: "cn".to_string(),
+            attr_email: "mail".to_string(),
+            attr_phone: "telephoneNumber".to_string(),
+            attr_groups: "memberOf".to_string(),
+            sync_profile: true,
+            auto_provision: false,
+            default_role_id: 0,
+            group_roles: vec![],
+        }
+    }
+}
+
+/// LDAP组与角色的映射, group 可以是组DN或组的cn
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct LdapGroupRole {
+    pub group: String,
+    pub role_id: i64,
+}
+
+/// OIDC单点登录配置
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct OidcConfig {
+    pub enable: bool,
+    /// 授权请求(state)有效期(秒)
+    pub state_ttl: u64,
+    /// 请求提供方的超时时间(秒)
+    pub http_timeout: u64,
+    pub providers: Vec<OidcProvider>,
+}
+
+impl Default for OidcConfig {
+    fn default() -> Self {
+        Self {
+            enable: false,
+            state_ttl: 600,
+            http_timeout: 10,
+            providers: vec![],
+        }
+    }
+}
+
+/// OIDC提供方
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct OidcProvider {
+    /// 提供方标识, 登录时通过该标识选择提供方, 同时用于记录外部身份绑定
+    pub name: String,
+    /// issuer地址, 通过 `{issuer}/.well-known/openid-configuration` 获取端点信息
+    pub issuer: String,
+    pub client_id: String,
+    /// 机密客户端的密钥, 公共客户端只使用PKCE时可不配置
+    pub client_secret: Option<String>,
+    /// 授权完成后的回调地址, 需与提供方登记的一致
+    pub redirect_uri: String,
+    #[serde(default = "default_oidc_scopes")]
+    pub scopes: Vec<String>,
+    /// 外部账号未绑定本地用户时自动创建
+    #[serde(default = "default_false")]
+    pub auto_provision: bool,
+    /// 按已验证的邮箱绑定已有的本地用户
+    #[serde(default = "default_false")]
+    pub link_by_email: bool,
+    /// 自动创建用户的角色
+    #[serde(default)]
+    pub default_role_id: i64,
+    /// 允许的ID Token签名算法, 为空时使用discovery中声明的非对称算法, 未声明时为RS256;
+    /// HS256等对称算法使用client_secret验签, 只有在此显式配置时才允许
+    #[serde(default)]
+    pub id_token_signing_algs: Vec<String>,
+    /// 提供方已完成多因素认证时跳过本地两步验证, 默认启用了TOTP的账号仍需输入动态码
+    #[serde(default = "default_false")]
+    pub skip_local_2fa: bool,
+}
+
+fn default_oidc_scopes() -> Vec<String> {
+    vec![
+        "openid".to_string(),
+        "profile".to_string(),
+        "email".to_string(),
+    ]
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CaptchaKind {
+    /// 随机字符
+    #[default]
+    Text,
+    /// 算术题, 答案为计算结果
+    Arithmetic,
+}
+
+/// 图形验证码配置
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct CaptchaConfig {
+    pub kind: CaptchaKind,
+    /// 字符验证码长度
+    pub length: usize,
+    /// 验证码有效期(秒)
+    pub ttl: u64,
+    /// 复杂度(1-10), 影响干扰线数量、字符扭曲程度以及算术题的运算范围
+    pub complexity: u8,
+    /// 生成频率的统计窗口(秒)
+    pub rate_window_seconds: u64,
+    /// 同一client_id在统计窗口内最多生成次数, 0表示不限制
+    pub client_max_per_window: u64,
+    /// 同一IP在统计窗口内最多生成次数, 0表示不限制
+    pub ip_max_per_window: u64,
+}
+
+impl CaptchaConfig {
+    /// 字符验证码长度, 配置为0时按1处理
+    pub fn text_length(&self) -> usize {
+        self.length.max(1)
+    }
+
+    /// 答案允许的长度范围, 登录/注册请求的验证码校验与此保持一致
+    pub fn answer_length(&self) -> (usize, usize) {
+        match self.kind {
+            CaptchaKind::Text => (self.text_length(), self.text_length()),
+            // 运算结果最大为两位数乘积
+            CaptchaKind::Arithmetic => (1, 2),
+        }
+    }
+}
+
+impl Default for CaptchaConfig {
+    fn default() -> Self {
+        Self {
+            kind: CaptchaKind::Text,
+            length: 5,
+            ttl: 300,
+            complexity: 3,
+            rate_window_seconds: 60,
+            client_max_per_window: 10,
+            ip_max_per_window: 30,
+        }
+    }
+}
+
+/// 自助注册配置
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct RegisterConfig {
+    pub enable: bool,
+    /// 新注册用户的角色
+    pub default_role_id: i64,
+    /// 邮箱验证token有效期(秒)
+    pub verify_ttl: u64,
+    /// 邮件中的验证链接, `{token}` 会被替换为验证token
+    pub verify_url: String,
+    /// 发件人, 为空时使用邮件服务默认发件人
+    pub mail_from: Option<String>,
+}
+
+impl Default for RegisterConfig {
+    fn default() -> Self {
+        Self {
+            enable: false,
+            default_role_id: 0,
+            verify_ttl: 86400,
+            verify_url: "http://localhost:8080/verify_email?token={token}".to_string(),
+            mail_from: None,
+        }
+    }
+}
+
+/// 找回密码配置
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct PasswordReset {
+    /// 重置token有效期(秒)
+    pub token_ttl: u64,
+    /// 邮件中的重置链接, `{token}` 会被替换为重置token
+    pub reset_url: String,
+    /// 发件人, 为空时使用邮件服务默认发件人
+    pub mail_from: Option<String>,
+}
+
+impl Default for PasswordReset {
+    fn default() -> Self {
+        Self {
+            token_ttl: 1800,
+            reset_url: "http://localhost:8080/reset_password?token={token}".to_string(),
+            mail_from: None,
+        }
+    }
+}
+
+/// 密码策略配置
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct PasswordPolicy {
+    pub min_length: usize,
+    pub max_length: usize,
+    pub require_uppercase: bool,
+    pub require_lowercase: bool,
+    pub require_digit: bool,
+    pub require_special: bool,
+    /// 密码不能与用户名相同
+    pub disallow_username: bool,
+    /// 不能与最近N次使用过的密码相同, 0表示不限制
+    pub history: u64,
+    /// 密码有效期(天), 过期后登录必须先修改密码, 0表示永不过期
+    pub max_age_days: i64,
+}
+
+impl Default for PasswordPolicy {
+    fn default() -> Self {
+        Self {
+            min_length: 8,
+            max_length: 64,
+            require_uppercase: false,
+            require_lowercase: true,
+            require_digit: true,
+            require_special: false,
+            disallow_username: true,
+            history: 5,
+            max_age_days: 0,
+        }
+    }
+}
+
+/// TOTP双因素认证配置
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct TotpConfig {
+    /// otpauth URI 中显示的签发者
+    pub issuer: String,
+    /// 加密TOTP密钥的口令, 数据库中只保存密文
+    pub encrypt_key: String,
+    /// 两步登录挑战token有效期(秒)
+    pub challenge_ttl: u64,
+    /// 允许的时间偏移窗口数(每个窗口30秒)
+    pub skew: u8,
+    /// 恢复码数量
+    pub recovery_codes: usize,
+}
+
+impl Default for TotpConfig {
+    fn default() -> Self {
+        Self {
+            issuer: "vela".to_string(),
+            encrypt_key: String::new(),
+            challenge_ttl: 300,
+            skew: 1,
+            recovery_codes: 10,
+        }
+    }
+}
+
+/// 登录防暴力破解配置
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct LoginLimit {
+    pub enable: bool,
+    /// 失败次数的统计窗口(秒)
+    pub window_seconds: u64,
+    /// 同一账号失败N次后锁定
+    pub lock_after: u64,
+    /// 账号锁定时长(秒)
+    pub lock_seconds: u64,
+    /// 同一账号失败M次后, 必须使用验证码登录
+    pub captcha_after: u64,
+    /// 同一IP在统计窗口内允许的最大失败次数
+    pub ip_max_failures: u64,
+    /// 每次失败递增的响应延迟(毫秒)
+    pub delay_step_ms: u64,
+    /// 最大响应延迟(毫秒)
+    pub delay_max_ms: u64,
+}
+
+impl Default for LoginLimit {
+    fn default() -> Self {
+        Self {
+            enable: true,
+            window_seconds: 900,
+            lock_after: 5,
+            lock_seconds: 900,
+            captcha_after: 3,
+            ip_max_failures: 20,
+            delay_step_ms: 500,
+            delay_max_ms: 5000,
+        }
+    }
 }
 
 /// JWT configuration structure.
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct JWT {
     /// The location where JWT tokens are expected to be found during
-    /// authentication.
-    pub location: Option<JWTLocation>,
-    /// The secret key For JWT token
+    /// authentication. A single location or an ordered list of locations,
+    /// defaults to Bearer.
+    pub location: Option<JWTLocationConfig>,
+    /// The secret key For JWT token, used by HS256
     pub secret: String,
     /// The expiration time for authentication tokens
     pub expiration: i64,
+    /// Signing algorithm, defaults to HS256
+    #[serde(default)]
+    pub algorithm: JWTAlgorithm,
+    /// Asymmetric keys identified by `kid`, used by RS256/ES256/EdDSA.
+    /// Keys without a private key only verify tokens, which allows rotation.
+    #[serde(default)]
+    pub keys: Vec<JWTKey>,
+    /// The `kid` used to sign new tokens, defaults to the first key with a
+    /// private key
+    pub signing_kid: Option<String>,
+    /// Cookie attributes used when login writes the token into a cookie
+    #[serde(default)]
+    pub cookie: JWTCookie,
+}
+
+impl JWT {
+    /// 按配置顺序返回token的获取位置, 未配置时为Bearer
+    pub fn locations(&self) -> Vec<JWTLocation> {
+        match &self.location {
+            None => vec![JWTLocation::Bearer],
+            Some(JWTLocationConfig::Single(location)) => vec![location.clone()],
+            Some(JWTLocationConfig::Multiple(locations)) if locations.is_empty() => {
+                vec![JWTLocation::Bearer]
+            }
+            Some(JWTLocationConfig::Multiple(locations)) => locations.clone(),
+        }
+    }
+
+    /// 配置了Cookie认证时, 返回第一个Cookie位置的名称
+    pub fn cookie_name(&self) -> Option<String> {
+        self.locations()
+            .into_iter()
+            .find_map(|location| match location {
+                JWTLocation::Cookie { name } => Some(name),
+                _ => None,
+            })
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum JWTLocationConfig {
+    Single(JWTLocation),
+    Multiple(Vec<JWTLocation>),
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -182,6 +865,71 @@ pub enum JWTLocation {
     Cookie { name: String },
 }
 
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum JWTAlgorithm {
+    #[default]
+    HS256,
+    RS256,
+    ES256,
+    EdDSA,
+}
+
+/// JWT asymmetric key pair, stored as PEM files.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct JWTKey {
+    /// Key id, written into the token header
+    pub kid: String,
+    /// PEM private key path, only needed by the signing key
+    pub private_key: Option<String>,
+    /// PEM public key path
+    pub public_key: String,
+}
+
+/// JWT cookie configuration, the cookie is always HttpOnly.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct JWTCookie {
+    /// Only send the cookie over HTTPS
+    #[serde(default = "default_true")]
+    pub secure: bool,
+    /// SameSite attribute: Strict, Lax or None
+    #[serde(default)]
+    pub same_site: SameSite,
+    /// Cookie path
+    #[serde(default = "default_cookie_path")]
+    pub path: String,
+    /// Cookie domain, defaults to the request host
+    pub domain: Option<String>,
+}
+
+impl Default for JWTCookie {
+    fn default() -> Self {
+        Self {
+            secure: true,
+            same_site: SameSite::default(),
+            path: default_cookie_path(),
+            domain: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub enum SameSite {
+    Strict,
+    #[default]
+    Lax,
+    None,
+}
+
+impl std::fmt::Display for SameSite {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        to_variant_name(self).expect("SameSite配置错误").fmt(f)
+    }
+}
+
+fn default_cookie_path() -> String {
+    "/".to_string()
+}
+
 fn default_false() -> bool {
     false
 }
@@ -198,3 +946,91 @@ pub struct Workers {
     /// The number of workers to start
     pub num_workers: u16,
 }
+
+/// 操作日志配置
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct OperLogConfig {
+    /// 单次导出的最大行数
+    pub export_max_rows: u64,
+    pub retention: OperLogRetention,
+    pub writer: OperLogWriterConfig,
+}
+
+impl Default for OperLogConfig {
+    fn default() -> Self {
+        Self {
+            export_max_rows: 10000,
+            retention: OperLogRetention::default(),
+            writer: OperLogWriterConfig::default(),
+        }
+    }
+}
+
+/// 操作日志异步写入配置, 日志先进入内存队列, 由后台任务批量写库
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct OperLogWriterConfig {
+    /// 关闭时在请求中同步写库
+    pub enable: bool,
+    /// 队列容量
+    pub capacity: usize,
+    /// 单批写入条数
+    pub batch_size: usize,
+    /// 未满一批时的最长等待时间(毫秒)
+    pub flush_interval_ms: u64,
+    /// 批量写入失败的重试次数, 重试仍失败则丢弃该批日志
+    pub max_retries: u32,
+    /// 队列已满时的处理策略
+    pub overflow: OverflowPolicy,
+}
+
+impl Default for OperLogWriterConfig {
+    fn default() -> Self {
+        Self {
+            enable: true,
+            capacity: 10000,
+            batch_size: 200,
+            flush_interval_ms: 1000,
+            max_retries: 3,
+            overflow: OverflowPolicy::Drop,
+        }
+    }
+}
+
+/// 队列已满时的处理策略
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OverflowPolicy {
+    /// 丢弃新日志并计数, 不影响请求耗时
+    #[default]
+    Drop,
+    /// 等待队列空出位置, 数据库不可用时会拖慢请求
+    Block,
+}
+
+/// 操作日志保留策略, 由内置定时任务按cron周期清理过期日志
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct OperLogRetention {
+    pub enable: bool,
+    /// 保留天数
+    pub days: i64,
+    /// 清理周期, 6位cron表达式(含秒)
+    pub cron: String,
+    /// 删除前是否先归档为CSV文件
+    pub archive: bool,
+    pub archive_dir: String,
+}
+
+impl Default for OperLogRetention {
+    fn default() -> Self {
+        Self {
+            enable: false,
+            days: 90,
+            cron: "0 0 3 * * *".to_string(),
+            archive: false,
+            archive_dir: "logs/archive".to_string(),
+        }
+    }
+}
@@ -1,5 +1,8 @@
 pub mod config;
 pub mod error;
 pub mod logger;
+pub mod redact;
+pub mod request_id;
 pub mod snowflake_id;
+pub mod tenant;
 pub mod traits;
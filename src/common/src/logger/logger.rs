@@ -1,4 +1,5 @@
 use std::error::Error;
+use std::io::{self, Write};
 
 use tracing::info;
 use tracing_appender::non_blocking::WorkerGuard;
@@ -7,12 +8,13 @@ use tracing_log::LogTracer;
 use tracing_subscriber::{EnvFilter, prelude::*};
 use tracing_subscriber::{
     Layer, Registry, filter,
-    fmt::{self, time::FormatTime},
+    fmt::{self, MakeWriter, time::FormatTime},
 };
 
 use crate::{
     config::{APP_CONFIG, config::LogLevel},
     logger::{API_LOG, MODULE_NAME},
+    redact::redactor,
 };
 
 #[derive(Debug, Clone)]
@@ -26,6 +28,37 @@ impl FormatTime for LocalTimer {
     }
 }
 
+/// 输出前对整条日志做文本脱敏
+#[derive(Debug, Clone)]
+pub struct RedactMakeWriter<M>(pub M);
+
+impl<'a, M: MakeWriter<'a>> MakeWriter<'a> for RedactMakeWriter<M> {
+    type Writer = RedactWriter<M::Writer>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        RedactWriter(self.0.make_writer())
+    }
+}
+
+pub struct RedactWriter<W>(W);
+
+impl<W: Write> Write for RedactWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        // fmt层每条日志格式化完成后整体写入一次, 可按完整文本脱敏
+        match std::str::from_utf8(buf) {
+            Ok(text) => {
+                self.0.write_all(redactor().mask_text(text).as_bytes())?;
+                Ok(buf.len())
+            }
+            Err(_) => self.0.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.flush()
+    }
+}
+
 pub fn init() -> Result<Vec<WorkerGuard>, Box<dyn Error>> {
     let mut guards = Vec::new();
 
@@ -83,7 +116,7 @@ pub fn init() -> Result<Vec<WorkerGuard>, Box<dyn Error>> {
             fmt::Layer::default()
                 .with_ansi(false)
                 .with_target(true)
-                .with_writer(web_file_appender)
+                .with_writer(RedactMakeWriter(web_file_appender))
                 .event_format(format.clone().compact())
                 .with_filter(filter::filter_fn(|metadata| metadata.target() != API_LOG)),
         )
@@ -91,7 +124,7 @@ pub fn init() -> Result<Vec<WorkerGuard>, Box<dyn Error>> {
             fmt::Layer::default()
                 .with_ansi(false)
                 .with_target(true)
-                .with_writer(api_file_appender)
+                .with_writer(RedactMakeWriter(api_file_appender))
                 .event_format(format.clone().compact())
                 .with_filter(filter::filter_fn(|metadata| metadata.target() == API_LOG)),
         )
@@ -99,7 +132,7 @@ pub fn init() -> Result<Vec<WorkerGuard>, Box<dyn Error>> {
             fmt::Layer::default()
                 .with_ansi(true)
                 .with_target(true)
-                .with_writer(console_non_blocking)
+                .with_writer(RedactMakeWriter(console_non_blocking))
                 .event_format(format.clone().pretty()),
         );
     tracing::subscriber::set_global_default(subscriber)?;
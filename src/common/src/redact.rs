@@ -0,0 +1,267 @@
+//! 日志脱敏
+//!
+//! 请求日志、操作日志写入前按配置替换敏感字段的值, web日志输出时再对整行文本做一次兜底脱敏。
+
+use std::borrow::Cow;
+
+use hyper::HeaderMap;
+use once_cell::sync::Lazy;
+use serde_json::Value;
+
+use crate::config::{APP_CONFIG, config::RedactConfig};
+
+static REDACTOR: Lazy<Redactor> = Lazy::new(|| Redactor::new(&APP_CONFIG.logger.redact));
+
+/// 按全局配置脱敏
+pub fn redactor() -> &'static Redactor {
+    &REDACTOR
+}
+
+/// 按字符截断, 不会在多字节字符中间切断
+pub fn truncate_chars(s: &str, max_chars: usize) -> &str {
+    match s.char_indices().nth(max_chars) {
+        Some((idx, _)) => &s[..idx],
+        None => s,
+    }
+}
+
+pub struct Redactor {
+    enable: bool,
+    fields: Vec<String>,
+    headers: Vec<String>,
+    mask: String,
+}
+
+impl Redactor {
+    pub fn new(config: &RedactConfig) -> Self {
+        let lower = |names: &Vec<String>| {
+            names
+                .iter()
+                .filter(|n| !n.is_empty())
+                .map(|n| n.to_ascii_lowercase())
+                .collect()
+        };
+        Self {
+            enable: config.enable,
+            fields: lower(&config.fields),
+            headers: lower(&config.headers),
+            mask: config.mask.clone(),
+        }
+    }
+
+    /// 字段名包含任一敏感字段即视为敏感, 如oldPassword、access_token
+    pub fn is_sensitive(&self, key: &str) -> bool {
+        let key = key.to_ascii_lowercase();
+        self.enable && self.fields.iter().any(|f| key.contains(f.as_str()))
+    }
+
+    /// 请求头脱敏
+    pub fn mask_header<'a>(&'a self, name: &str, value: &'a str) -> &'a str {
+        if self.enable && self.headers.iter().any(|h| h.eq_ignore_ascii_case(name)) {
+            &self.mask
+        } else {
+            value
+        }
+    }
+
+    /// 请求头格式化为`name: value`列表, 敏感请求头脱敏
+    pub fn format_headers(&self, headers: &HeaderMap) -> String {
+        headers
+            .iter()
+            .map(|(name, value)| {
+                let value = value.to_str().unwrap_or("<binary>");
+                format!("{}: {}", name, self.mask_header(name.as_str(), value))
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// 请求体、响应体脱敏, JSON按字段逐层处理, 其余内容(如表单)按文本处理
+    pub fn mask_body<'a>(&self, body: &'a str) -> Cow<'a, str> {
+        if !self.enable {
+            return Cow::Borrowed(body);
+        }
+        match serde_json::from_str::<Value>(body) {
+            Ok(mut value) if value.is_object() || value.is_array() => {
+                if self.mask_json(&mut value) {
+                    Cow::Owned(value.to_string())
+                } else {
+                    Cow::Borrowed(body)
+                }
+            }
+            _ => self.mask_text(body),
+        }
+    }
+
+    /// 返回是否有字段被脱敏
+    fn mask_json(&self, value: &mut Value) -> bool {
+        match value {
+            Value::Object(map) => {
+                let mut masked = false;
+                for (key, val) in map.iter_mut() {
+                    if self.is_sensitive(key) {
+                        if !val.is_null() {
+                            *val = Value::String(self.mask.clone());
+                            masked = true;
+                        }
+                    } else {
+                        masked |= self.mask_json(val);
+                    }
+                }
+                masked
+            }
+            Value::Array(list) => {
+                let mut masked = false;
+                for val in list.iter_mut() {
+                    masked |= self.mask_json(val);
+                }
+                masked
+            }
+            _ => false,
+        }
+    }
+
+    /// 文本脱敏, 识别`key=value`、`key: value`及`"key":"value"`形式(含转义引号)
+    pub fn mask_text<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        if !self.enable {
+            return Cow::Borrowed(text);
+        }
+        // 只转换ASCII字符, 字节位置与原文一致
+        let lower = text.to_ascii_lowercase();
+        let bytes = text.as_bytes();
+        let len = bytes.len();
+        let mut out = String::new();
+        let mut last = 0;
+        let mut pos = 0;
+        while let Some((start, key_len)) = self.find_key(&lower, pos) {
+            pos = start + key_len;
+            let mut i = pos;
+            // 字段名剩余部分, 如password_confirm
+            while i < len && (bytes[i].is_ascii_alphanumeric() || matches!(bytes[i], b'_' | b'-')) {
+                i += 1;
+            }
+            while i < len && matches!(bytes[i], b'"' | b'\'' | b'\\' | b' ') {
+                i += 1;
+            }
+            if i >= len || !matches!(bytes[i], b':' | b'=') {
+                continue;
+            }
+            i += 1;
+            while i < len && matches!(bytes[i], b' ' | b'\\') {
+                i += 1;
+            }
+            let (value_start, value_end) = match bytes.get(i) {
+                Some(&quote @ (b'"' | b'\'')) => {
+                    let value_start = i + 1;
+                    let mut end = bytes[value_start..]
+                        .iter()
+                        .position(|b| *b == quote)
+                        .map_or(len, |p| value_start + p);
+                    if end > value_start && bytes[end - 1] == b'\\' {
+                        end -= 1;
+                    }
+                    (value_start, end)
+                }
+                _ => {
+                    let mut end = token_end(bytes, i);
+                    // Authorization: Bearer xxx 连同凭证一起脱敏
+                    let scheme = &lower[i..end];
+                    if (scheme == "bearer" || scheme == "basic") && end < len && bytes[end] == b' '
+                    {
+                        end = token_end(bytes, end + 1);
+                    }
+                    (i, end)
+                }
+            };
+            if value_end <= value_start {
+                continue;
+            }
+            out.push_str(&text[last..value_start]);
+            out.push_str(&self.mask);
+            last = value_end;
+            pos = value_end;
+        }
+        if last == 0 {
+            return Cow::Borrowed(text);
+        }
+        out.push_str(&text[last..]);
+        Cow::Owned(out)
+    }
+
+    /// 从pos开始最先出现的敏感字段或请求头, 返回位置及长度
+    fn find_key(&self, lower: &str, pos: usize) -> Option<(usize, usize)> {
+        self.fields
+            .iter()
+            .chain(self.headers.iter())
+            .filter_map(|key| {
+                lower[pos..]
+                    .find(key.as_str())
+                    .map(|p| (pos + p, key.len()))
+            })
+            .min()
+    }
+}
+
+/// 未加引号的值在空白或分隔符处结束
+fn token_end(bytes: &[u8], start: usize) -> usize {
+    bytes[start..]
+        .iter()
+        .position(|b| {
+            b.is_ascii_whitespace() || matches!(b, b'&' | b',' | b';' | b'}' | b')' | b']')
+        })
+        .map_or(bytes.len(), |p| start + p)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn redactor() -> Redactor {
+        Redactor::new(&RedactConfig::default())
+    }
+
+    #[test]
+    fn mask_json_body() {
+        let r = redactor();
+        let body = r#"{"username":"admin","password":"123456","profile":{"oldPassword":"a"},"list":[{"accessToken":"t"}],"captcha":null}"#;
+        let masked: Value = serde_json::from_str(&r.mask_body(body)).unwrap();
+        assert_eq!(masked["username"], "admin");
+        assert_eq!(masked["password"], "******");
+        assert_eq!(masked["profile"]["oldPassword"], "******");
+        assert_eq!(masked["list"][0]["accessToken"], "******");
+        assert!(masked["captcha"].is_null());
+        assert!(matches!(
+            r.mask_body(r#"{"username":"admin"}"#),
+            Cow::Borrowed(_)
+        ));
+    }
+
+    #[test]
+    fn mask_plain_text() {
+        let r = redactor();
+        assert_eq!(
+            r.mask_body("username=admin&password=123&captcha_code=ab"),
+            "username=admin&password=******&captcha_code=******"
+        );
+        assert_eq!(
+            r.mask_text(r#"body:{\"password\": \"密码\", \"name\":\"a\"}"#),
+            r#"body:{\"password\": \"******\", \"name\":\"a\"}"#
+        );
+        assert_eq!(
+            r.mask_text("authorization: Bearer abc.def ok"),
+            "authorization: ****** ok"
+        );
+        assert_eq!(
+            r.mask_text("密码校验失败 password"),
+            "密码校验失败 password"
+        );
+        assert_eq!(r.mask_header("Cookie", "sid=1"), "******");
+        assert_eq!(r.mask_header("Accept", "*/*"), "*/*");
+    }
+
+    #[test]
+    fn truncate_on_char_boundary() {
+        assert_eq!(truncate_chars("中文abc", 2), "中文");
+        assert_eq!(truncate_chars("abc", 5), "abc");
+    }
+}
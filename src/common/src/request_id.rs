@@ -0,0 +1,22 @@
+//! 当前请求的请求ID
+//!
+//! 请求ID中间件在请求处理期间设置, 响应体、日志及异步任务据此关联到同一个请求。
+
+use std::future::Future;
+
+/// 请求ID请求头, 由服务端生成并原样返回
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+tokio::task_local! {
+    static CURRENT_REQUEST_ID: String;
+}
+
+/// 获取当前请求ID, 不在请求上下文中时返回None
+pub fn current_request_id() -> Option<String> {
+    CURRENT_REQUEST_ID.try_with(|id| id.clone()).ok()
+}
+
+/// 在指定请求ID上下文中执行
+pub async fn with_request_id<F: Future>(request_id: String, f: F) -> F::Output {
+    CURRENT_REQUEST_ID.scope(request_id, f).await
+}
@@ -0,0 +1,54 @@
+//! 当前请求的租户上下文
+//!
+//! 认证中间件在请求处理期间设置租户, 持久化层及缓存据此隔离数据;
+//! 登录、定时任务等没有租户上下文的场景不做隔离。
+
+use std::future::Future;
+
+/// 默认租户, 未启用多租户前的数据都属于该租户
+pub const DEFAULT_TENANT_ID: i64 = 0;
+
+tokio::task_local! {
+    static CURRENT_TENANT: i64;
+}
+
+/// 获取当前租户, 不在租户上下文中时返回None
+pub fn current_tenant() -> Option<i64> {
+    CURRENT_TENANT.try_with(|tenant_id| *tenant_id).ok()
+}
+
+/// 在指定租户上下文中执行
+pub async fn with_tenant<F: Future>(tenant_id: i64, f: F) -> F::Output {
+    CURRENT_TENANT.scope(tenant_id, f).await
+}
+
+/// 缓存key的租户前缀, 默认租户及无租户上下文时不加前缀, 与已有key保持兼容
+pub fn tenant_key(key: &str) -> String {
+    match current_tenant() {
+        Some(tenant_id) if tenant_id != DEFAULT_TENANT_ID => {
+            format!("tenant:{}:{}", tenant_id, key)
+        }
+        _ => key.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn tenant_scope() {
+        assert_eq!(current_tenant(), None);
+        assert_eq!(tenant_key("captcha"), "captcha");
+        with_tenant(DEFAULT_TENANT_ID, async {
+            assert_eq!(tenant_key("captcha"), "captcha");
+        })
+        .await;
+        with_tenant(7, async {
+            assert_eq!(current_tenant(), Some(7));
+            assert_eq!(tenant_key("captcha"), "tenant:7:captcha");
+        })
+        .await;
+        assert_eq!(current_tenant(), None);
+    }
+}
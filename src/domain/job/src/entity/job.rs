@@ -17,6 +17,10 @@ pub struct CreateJobDto {
     pub args: Option<String>,
     /// 是否重试（可选）
     pub retry: Option<bool>,
+    /// 创建人ID
+    pub create_by: i64,
+    /// 创建人所属部门（可选）
+    pub dept_id: Option<i64>,
     /// 创建时间
     pub created_at: DateTime<Local>,
     /// 更新时间
@@ -0,0 +1,10 @@
+use async_trait::async_trait;
+use commonx::error::AppError;
+
+use crate::entity::LoginLog;
+
+#[async_trait]
+pub trait LoginLogDomainTrait {
+    /// 记录一次登录尝试
+    async fn record(&self, log: LoginLog) -> Result<(), AppError>;
+}
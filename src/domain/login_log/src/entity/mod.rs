@@ -0,0 +1,39 @@
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+/// 登录成功
+pub const LOGIN_STATUS_SUCCESS: i16 = 0;
+/// 登录失败
+pub const LOGIN_STATUS_FAIL: i16 = 1;
+
+/// 用户名密码登录
+pub const LOGIN_TYPE_PASSWORD: &str = "password";
+/// 两步验证登录
+pub const LOGIN_TYPE_2FA: &str = "2fa";
+/// 外部认证(OIDC等)登录
+pub const LOGIN_TYPE_EXTERNAL: &str = "external";
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct LoginLog {
+    pub id: i64,
+    /// 登录成功或能确定账号时的用户ID
+    pub user_id: Option<i64>,
+    /// 尝试登录的用户名
+    pub username: String,
+    pub login_type: String,
+    pub ip: String,
+    pub user_agent: String,
+    /// 登录结果: 0成功 1失败
+    pub status: i16,
+    /// 失败原因
+    pub fail_reason: Option<String>,
+    /// 是否使用了图形验证码
+    pub captcha_used: bool,
+    /// 是否经过两步验证
+    pub two_factor_used: bool,
+    pub request_id: String,
+    /// 所属租户, 登录失败且无法确定账号时归属默认租户
+    pub tenant_id: i64,
+    pub dept_id: Option<i64>,
+    pub login_time: DateTime<Local>,
+}
@@ -0,0 +1,18 @@
+use crate::repository::LoginLogRepositoryTrait;
+
+pub mod api;
+pub mod entity;
+pub mod repository;
+pub mod services;
+
+pub const MODEL_LOGIN_LOG: &str = "loginLog";
+
+pub struct LoginLogDomainImpl {
+    repo: Box<dyn LoginLogRepositoryTrait + Sync + Send>,
+}
+
+pub fn new_login_log_domain(
+    repo: Box<dyn LoginLogRepositoryTrait + Sync + Send>,
+) -> LoginLogDomainImpl {
+    LoginLogDomainImpl { repo }
+}
@@ -0,0 +1,9 @@
+use async_trait::async_trait;
+use commonx::error::AppError;
+
+use crate::entity::LoginLog;
+
+#[async_trait]
+pub trait LoginLogRepositoryTrait {
+    async fn create(&self, log: LoginLog) -> Result<(), AppError>;
+}
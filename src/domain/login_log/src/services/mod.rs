@@ -0,0 +1,19 @@
+use async_trait::async_trait;
+use commonx::error::AppError;
+use tracing::info;
+
+use crate::{
+    LoginLogDomainImpl, MODEL_LOGIN_LOG,
+    api::traits::LoginLogDomainTrait,
+    entity::{LOGIN_STATUS_FAIL, LoginLog},
+};
+
+#[async_trait]
+impl LoginLogDomainTrait for LoginLogDomainImpl {
+    async fn record(&self, log: LoginLog) -> Result<(), AppError> {
+        if log.status == LOGIN_STATUS_FAIL {
+            info!(target: MODEL_LOGIN_LOG, "登录失败: username:{} ip:{} reason:{:?}", log.username, log.ip, log.fail_reason);
+        }
+        self.repo.create(log).await
+    }
+}
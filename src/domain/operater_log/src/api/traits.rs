@@ -1,4 +1,5 @@
 use async_trait::async_trait;
+use chrono::{DateTime, Local};
 use commonx::error::AppError;
 
 use crate::entity::OperaterLog;
@@ -6,4 +7,6 @@ use crate::entity::OperaterLog;
 #[async_trait]
 pub trait OperaterLogDomainTrait {
     async fn create(&self, log: OperaterLog) -> Result<(), AppError>;
+    /// 删除指定时间之前的操作日志, 返回删除条数
+    async fn clean_before(&self, before: DateTime<Local>) -> Result<u64, AppError>;
 }
@@ -1,19 +1,63 @@
 use chrono::{DateTime, Local};
 use serde::{Deserialize, Serialize};
 
+/// 操作成功
+pub const OPER_STATUS_SUCCESS: i16 = 0;
+/// 操作失败
+pub const OPER_STATUS_FAIL: i16 = 1;
+
+/// 业务类型, 由路由声明
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BusinessType {
+    #[default]
+    Other = 0,
+    Create = 1,
+    Update = 2,
+    Delete = 3,
+    Export = 4,
+    Login = 5,
+}
+
+impl BusinessType {
+    pub fn code(self) -> i16 {
+        self as i16
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 
 pub struct OperaterLog {
     pub id: i64,
     pub api_name: String,
+    /// 操作名称
+    pub title: String,
+    pub business_type: BusinessType,
     pub oper_ip: String,
     pub oper_id: i64,
     pub oper_name: String,
+    /// 操作人所属部门
+    pub dept_id: Option<i64>,
+    /// 所属租户, 异步写入时在入队前确定
+    pub tenant_id: i64,
     pub oper_url: String,
     pub oper_location: String,
     pub request_method: String,
+    /// 请求体
     pub oper_param: String,
+    /// 查询参数
+    pub oper_query: String,
     pub json_result: String,
+    /// 操作状态: 0成功 1失败
+    pub status: i16,
+    /// HTTP状态码
+    pub http_status: i32,
+    /// 响应体中的业务状态码
+    pub biz_code: Option<i32>,
+    /// 失败时的错误信息
+    pub error_msg: Option<String>,
+    pub user_agent: String,
+    pub request_id: String,
     pub cost_time: i64,
     pub oper_time: DateTime<Local>,
 }
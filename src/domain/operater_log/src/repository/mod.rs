@@ -1,4 +1,5 @@
 use async_trait::async_trait;
+use chrono::{DateTime, Local};
 use commonx::error::AppError;
 
 use crate::entity::OperaterLog;
@@ -6,4 +7,5 @@ use crate::entity::OperaterLog;
 #[async_trait]
 pub trait OperaterLogRepositoryTrait {
     async fn create(&self, log: OperaterLog) -> Result<(), AppError>;
+    async fn delete_before(&self, before: DateTime<Local>) -> Result<u64, AppError>;
 }
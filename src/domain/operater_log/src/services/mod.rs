@@ -1,11 +1,22 @@
 use async_trait::async_trait;
+use chrono::{DateTime, Local};
 use commonx::error::AppError;
+use tracing::info;
 
-use crate::{OperaterLogDomainImpl, api::traits::OperaterLogDomainTrait, entity::OperaterLog};
+use crate::{
+    MODEL_OPERATOR_LOG, OperaterLogDomainImpl, api::traits::OperaterLogDomainTrait,
+    entity::OperaterLog,
+};
 
 #[async_trait]
 impl OperaterLogDomainTrait for OperaterLogDomainImpl {
     async fn create(&self, log: OperaterLog) -> Result<(), AppError> {
         self.repo.create(log).await
     }
+
+    async fn clean_before(&self, before: DateTime<Local>) -> Result<u64, AppError> {
+        let count = self.repo.delete_before(before).await?;
+        info!(target: MODEL_OPERATOR_LOG, "清理{}之前的操作日志: {}条", before, count);
+        Ok(count)
+    }
 }
@@ -1,10 +1,13 @@
 use serde::{Deserialize, Serialize};
 
+use crate::{api::dto::user_info::UserInfoDto, entity::user::User};
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct AuthDto {
     pub username: String,
     pub password: String,
     pub client_id: String,
+    pub ip: String,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -13,4 +16,133 @@ pub struct AuthDtoWithCaptcha {
     pub password: String,
     pub client_id: String,
     pub captcha: String,
+    pub ip: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Login2faDto {
+    pub challenge_token: String,
+    /// TOTP动态码或恢复码
+    pub code: String,
+    pub ip: String,
+}
+
+/// 登录结果, 启用双因素认证的账号先返回挑战token
+#[derive(Debug, Serialize, Deserialize)]
+pub enum LoginOutcome {
+    Authenticated(Box<UserInfoDto>),
+    TwoFactorRequired {
+        challenge_token: String,
+        expires_in: u64,
+    },
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChangePasswordDto {
+    pub user_id: i64,
+    pub old_password: String,
+    pub new_password: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ForgotPasswordDto {
+    pub username: String,
+    pub ip: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ResetPasswordDto {
+    pub token: String,
+    pub new_password: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RegisterDto {
+    pub username: String,
+    pub password: String,
+    pub email: String,
+    pub name: Option<String>,
+    pub client_id: String,
+    pub captcha: String,
+    pub ip: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ResendEmailVerifyDto {
+    pub email: String,
+    pub ip: String,
+}
+
+/// 外部认证授权地址, state需由调用方绑定到发起请求的浏览器
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExternalAuthorizeDto {
+    pub url: String,
+    pub state: String,
+}
+
+/// 外部认证提供方回调参数
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExternalLoginDto {
+    pub provider: String,
+    pub code: String,
+    pub state: String,
+    pub ip: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateApiKeyDto {
+    pub user_id: i64,
+    pub name: String,
+    pub scopes: Vec<String>,
+    /// 有效期(天), 为空表示永不过期
+    pub expires_in_days: Option<i64>,
+}
+
+/// API Key 认证通过后的身份
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ApiKeyPrincipal {
+    pub key_id: i64,
+    pub scopes: Vec<String>,
+    pub user: UserInfoDto,
+}
+
+/// 登录失败时已识别出的账号, 用于登录日志归属用户、租户和部门
+#[derive(Debug, Clone, Default)]
+pub struct LoginSubject {
+    pub username: String,
+    pub user_id: Option<i64>,
+    pub tenant_id: i64,
+    pub dept_id: Option<i64>,
+}
+
+impl LoginSubject {
+    /// 只知道用户名, 本地不存在该账号
+    pub fn username(username: &str) -> Self {
+        Self {
+            username: username.to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+impl From<&User> for LoginSubject {
+    fn from(user: &User) -> Self {
+        Self {
+            username: user.username.clone(),
+            user_id: Some(user.id),
+            tenant_id: user.tenant_id,
+            dept_id: user.dept_id,
+        }
+    }
+}
+
+impl From<&UserInfoDto> for LoginSubject {
+    fn from(user: &UserInfoDto) -> Self {
+        Self {
+            username: user.username.clone(),
+            user_id: Some(user.id),
+            tenant_id: user.tenant_id,
+            dept_id: user.dept_id,
+        }
+    }
 }
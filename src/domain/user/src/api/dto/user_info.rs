@@ -7,6 +7,8 @@ use crate::entity;
 pub struct UserInfoDto {
     pub id: i64,
     pub role_id: i64,
+    pub tenant_id: i64,
+    pub dept_id: Option<i64>,
     pub username: String,
     pub name: Option<String>,
     pub identity_code: Option<String>,
@@ -21,6 +23,11 @@ pub struct UserInfoDto {
     pub update_by: Option<i64>,
     pub updated_at: Option<DateTime<Local>>,
     pub deleted_at: Option<DateTime<Local>>,
+    pub totp_enabled: bool,
+    pub last_login_ip: Option<String>,
+    pub last_login_time: Option<DateTime<Local>>,
+    /// 密码已过期, 需修改密码后才能使用其他功能
+    pub password_expired: bool,
 }
 
 impl From<entity::user::User> for UserInfoDto {
@@ -28,6 +35,8 @@ impl From<entity::user::User> for UserInfoDto {
         Self {
             id: user.id,
             role_id: user.role_id,
+            tenant_id: user.tenant_id,
+            dept_id: user.dept_id,
             username: user.username,
             name: user.name,
             identity_code: user.identity_code,
@@ -36,12 +45,16 @@ impl From<entity::user::User> for UserInfoDto {
             sex: user.sex,
             avatar: user.avatar,
             status: user.status,
+            last_login_ip: user.last_login_ip,
+            last_login_time: user.last_login_time,
             remark: user.remark,
             create_by: user.create_by,
             created_at: user.created_at,
             update_by: user.update_by,
             updated_at: user.updated_at,
             deleted_at: user.deleted_at,
+            totp_enabled: user.totp_enabled,
+            password_expired: false,
         }
     }
 }
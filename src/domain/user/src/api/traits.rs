@@ -1,12 +1,19 @@
 use crate::{
     api::dto::{
-        auth::{AuthDto, AuthDtoWithCaptcha},
+        auth::{
+            ApiKeyPrincipal, AuthDto, AuthDtoWithCaptcha, ChangePasswordDto, CreateApiKeyDto,
+            ExternalAuthorizeDto, ExternalLoginDto, ForgotPasswordDto, Login2faDto, LoginOutcome,
+            RegisterDto, ResendEmailVerifyDto, ResetPasswordDto,
+        },
         user_info::UserInfoDto,
     },
     commons::error::UserDomainError,
     entity::{
         self,
+        api_key::{ApiKey, ApiKeyCreated},
         captcha::{CaptchaCacheInfo, CaptchaImage},
+        session::UserSession,
+        totp::TotpEnrollment,
     },
 };
 use async_trait::async_trait;
@@ -21,13 +28,79 @@ pub trait UserDomainTrait {
     async fn gen_captcha(
         &self,
         client_id: String,
+        ip: String,
         width: u32,
         height: u32,
     ) -> Result<CaptchaImage, UserDomainError>;
     async fn get_captcha(&self, client_id: String) -> Result<CaptchaCacheInfo, UserDomainError>;
-    async fn login(&self, auth_req: AuthDto) -> Result<UserInfoDto, UserDomainError>;
+    async fn login(&self, auth_req: AuthDto) -> Result<LoginOutcome, UserDomainError>;
     async fn login_with_captcha(
         &self,
         auth_req: AuthDtoWithCaptcha,
-    ) -> Result<UserInfoDto, UserDomainError>;
+    ) -> Result<LoginOutcome, UserDomainError>;
+    /// 两步登录: 使用挑战token及TOTP动态码或恢复码完成登录
+    async fn login_2fa(&self, auth_req: Login2faDto) -> Result<UserInfoDto, UserDomainError>;
+    /// 外部认证: 生成授权请求并返回跳转到提供方的地址
+    async fn external_authorize(
+        &self,
+        provider: String,
+    ) -> Result<ExternalAuthorizeDto, UserDomainError>;
+    /// 外部认证回调: 校验授权码并映射到本地用户, 启用了TOTP的账号返回挑战token
+    async fn external_login(&self, req: ExternalLoginDto) -> Result<LoginOutcome, UserDomainError>;
+    /// 创建API Key, 明文仅在此时返回
+    async fn create_api_key(&self, req: CreateApiKeyDto) -> Result<ApiKeyCreated, UserDomainError>;
+    /// 获取用户的API Key列表
+    async fn list_api_keys(&self, user_id: i64) -> Result<Vec<ApiKey>, UserDomainError>;
+    /// 吊销API Key
+    async fn revoke_api_key(&self, user_id: i64, id: i64) -> Result<(), UserDomainError>;
+    /// 校验请求携带的API Key, 返回对应用户及权限范围
+    async fn authenticate_api_key(&self, key: String) -> Result<ApiKeyPrincipal, UserDomainError>;
+    /// 登录成功后更新用户最近一次登录的IP及时间
+    async fn update_last_login(&self, user_id: i64, ip: String) -> Result<(), UserDomainError>;
+    /// 登录成功后记录会话
+    async fn create_session(&self, session: UserSession) -> Result<(), UserDomainError>;
+    /// 分页获取有效会话, user_id为空时获取所有用户的会话
+    async fn list_sessions(
+        &self,
+        user_id: Option<i64>,
+        page: u64,
+        page_size: u64,
+    ) -> Result<Vec<UserSession>, UserDomainError>;
+    /// 吊销会话, user_id不为空时只能吊销该用户自己的会话
+    async fn revoke_session(
+        &self,
+        user_id: Option<i64>,
+        token_id: i64,
+    ) -> Result<(), UserDomainError>;
+    /// 校验token对应的会话未被吊销, 并更新最近活跃时间
+    async fn check_session(&self, token_id: i64) -> Result<(), UserDomainError>;
+    /// 修改当前用户密码
+    async fn change_password(&self, req: ChangePasswordDto)
+    -> Result<UserInfoDto, UserDomainError>;
+    /// 找回密码: 生成一次性token并发送重置邮件, 账号不存在时同样返回成功
+    async fn forgot_password(&self, req: ForgotPasswordDto) -> Result<(), UserDomainError>;
+    /// 使用找回密码token设置新密码
+    async fn reset_password(&self, req: ResetPasswordDto) -> Result<(), UserDomainError>;
+    /// 自助注册, 账号在邮箱验证前处于待激活状态
+    async fn register(&self, req: RegisterDto) -> Result<(), UserDomainError>;
+    /// 校验注册邮件中的token并激活账号
+    async fn verify_email(&self, token: String) -> Result<(), UserDomainError>;
+    /// 重新发送注册验证邮件, 邮箱不存在或账号已激活时同样返回成功
+    async fn resend_email_verify(&self, req: ResendEmailVerifyDto) -> Result<(), UserDomainError>;
+    /// 生成TOTP密钥, 需调用 totp_activate 校验动态码后才会启用
+    async fn totp_enroll(&self, user_id: i64) -> Result<TotpEnrollment, UserDomainError>;
+    /// 校验动态码并启用TOTP, 返回恢复码明文(仅此一次)
+    async fn totp_activate(
+        &self,
+        user_id: i64,
+        code: String,
+    ) -> Result<Vec<String>, UserDomainError>;
+    /// 校验动态码或恢复码后关闭TOTP
+    async fn totp_disable(&self, user_id: i64, code: String) -> Result<(), UserDomainError>;
+    /// 校验动态码后重新生成恢复码
+    async fn totp_regenerate_recovery_codes(
+        &self,
+        user_id: i64,
+        code: String,
+    ) -> Result<Vec<String>, UserDomainError>;
 }
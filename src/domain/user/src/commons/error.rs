@@ -1,6 +1,9 @@
 use commonx::error::AppError;
+use hyper::StatusCode;
 use thiserror::Error;
 
+use crate::api::dto::auth::LoginSubject;
+
 #[derive(Debug, Error)]
 pub enum UserDomainError {
     #[error("用户不存在:{0}")]
@@ -15,6 +18,36 @@ pub enum UserDomainError {
     #[error("认证错误:{0}")]
     AuthError(String),
 
+    #[error("账号已锁定, 请于{until}后重试")]
+    AccountLocked { username: String, until: String },
+
+    #[error("登录失败次数过多, 请使用验证码登录")]
+    CaptchaRequired,
+
+    #[error("登录失败次数过多, 请稍后重试")]
+    TooManyAttempts,
+
+    #[error("获取验证码过于频繁, 请稍后重试")]
+    CaptchaRateLimited,
+
+    #[error("两步验证失败:{0}")]
+    TwoFactorError(String),
+
+    #[error("密码不符合要求:{0}")]
+    PasswordPolicyError(String),
+
+    #[error("注册功能未开启")]
+    RegisterDisabled,
+
+    #[error("账号未激活, 请先完成邮箱验证")]
+    AccountNotActivated,
+
+    #[error("会话已失效,请重新登录")]
+    SessionRevoked,
+
+    #[error("参数错误:{0}")]
+    InvalidArgument(String),
+
     #[error("内部错误:{0}")]
     InternalError(String),
 
@@ -23,11 +56,50 @@ pub enum UserDomainError {
 
     #[error("内部错误(500), 未实现: {0}")]
     NotImplementedError(String),
+
+    /// 已识别出登录账号后的失败, 错误信息与原错误一致
+    #[error("{source}")]
+    LoginFailed {
+        subject: LoginSubject,
+        source: Box<UserDomainError>,
+    },
+}
+
+impl UserDomainError {
+    /// 附带登录账号, 供登录日志记录
+    pub fn with_subject(self, subject: LoginSubject) -> Self {
+        match self {
+            UserDomainError::LoginFailed { .. } => self,
+            e => UserDomainError::LoginFailed {
+                subject,
+                source: Box::new(e),
+            },
+        }
+    }
 }
 
 impl From<UserDomainError> for AppError {
     fn from(e: UserDomainError) -> Self {
         match e {
+            UserDomainError::LoginFailed { source, .. } => AppError::from(*source),
+            UserDomainError::AccountLocked { .. } => {
+                AppError::WithStatus(StatusCode::LOCKED, e.to_string())
+            }
+            UserDomainError::CaptchaRequired => {
+                AppError::WithStatus(StatusCode::PRECONDITION_REQUIRED, e.to_string())
+            }
+            UserDomainError::TooManyAttempts | UserDomainError::CaptchaRateLimited => {
+                AppError::WithStatus(StatusCode::TOO_MANY_REQUESTS, e.to_string())
+            }
+            UserDomainError::TwoFactorError(_) | UserDomainError::SessionRevoked => {
+                AppError::AuthError(e.to_string())
+            }
+            UserDomainError::PasswordPolicyError(_) | UserDomainError::InvalidArgument(_) => {
+                AppError::BadRequest(e.to_string())
+            }
+            UserDomainError::RegisterDisabled | UserDomainError::AccountNotActivated => {
+                AppError::WithStatus(StatusCode::FORBIDDEN, e.to_string())
+            }
             _ => AppError::InternalError(e.to_string()),
         }
     }
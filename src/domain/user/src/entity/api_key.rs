@@ -0,0 +1,98 @@
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+/// API Key, 只保存摘要, 明文仅在创建时返回一次
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ApiKey {
+    pub id: i64,
+    pub user_id: i64,
+    pub name: String,
+    /// 明文前缀, 用于查找及展示
+    pub prefix: String,
+    #[serde(skip_serializing)]
+    pub key_hash: String,
+    pub scopes: Vec<String>,
+    pub expires_at: Option<DateTime<Local>>,
+    pub last_used_at: Option<DateTime<Local>>,
+    pub revoked_at: Option<DateTime<Local>>,
+    pub created_at: Option<DateTime<Local>>,
+}
+
+impl ApiKey {
+    /// 未吊销且未过期
+    pub fn is_active(&self) -> bool {
+        self.revoked_at.is_none() && self.expires_at.map(|t| t > Local::now()).unwrap_or(true)
+    }
+}
+
+/// 创建API Key的返回, key 为完整明文
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ApiKeyCreated {
+    pub key: String,
+    pub api_key: ApiKey,
+}
+
+/// API Key 的权限范围, 格式为 `read:<路径前缀>` 或 `write:<路径前缀>`,
+/// 路径前缀为 `*` 时表示全部接口; read 只允许 GET/HEAD, write 允许所有方法
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiKeyScope {
+    pub write: bool,
+    pub path_prefix: Option<String>,
+}
+
+impl ApiKeyScope {
+    pub fn parse(scope: &str) -> Option<Self> {
+        let (access, path) = scope.split_once(':')?;
+        let write = match access {
+            "read" => false,
+            "write" => true,
+            _ => return None,
+        };
+        let path_prefix = match path {
+            "*" => None,
+            p if p.starts_with('/') && p.len() > 1 => Some(p.trim_end_matches('/').to_string()),
+            _ => return None,
+        };
+        Some(Self { write, path_prefix })
+    }
+
+    pub fn allows(&self, method: &str, path: &str) -> bool {
+        let read_only = method.eq_ignore_ascii_case("GET") || method.eq_ignore_ascii_case("HEAD");
+        if !self.write && !read_only {
+            return false;
+        }
+        match &self.path_prefix {
+            None => true,
+            Some(prefix) => {
+                path == prefix
+                    || path
+                        .strip_prefix(prefix.as_str())
+                        .is_some_and(|rest| rest.starts_with('/'))
+            }
+        }
+    }
+}
+
+/// 任一范围允许即可访问
+pub fn scopes_allow(scopes: &[String], method: &str, path: &str) -> bool {
+    scopes
+        .iter()
+        .filter_map(|s| ApiKeyScope::parse(s))
+        .any(|s| s.allows(method, path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scope_matching() {
+        let scopes = vec!["read:*".to_string(), "write:/cornJob".to_string()];
+        assert!(scopes_allow(&scopes, "GET", "/sys/user/info"));
+        assert!(!scopes_allow(&scopes, "POST", "/sys/init_all"));
+        assert!(scopes_allow(&scopes, "POST", "/cornJob/create"));
+        assert!(!scopes_allow(&scopes, "POST", "/cornJobX/create"));
+        assert!(ApiKeyScope::parse("admin:*").is_none());
+        assert!(ApiKeyScope::parse("read:cornJob").is_none());
+    }
+}
@@ -0,0 +1,54 @@
+use serde::{Deserialize, Serialize};
+
+/// 发起外部认证时生成的授权请求, 以state为key保存在缓存中
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AuthorizeRequest {
+    pub provider: String,
+    pub state: String,
+    pub nonce: String,
+    /// PKCE code_verifier
+    pub code_verifier: String,
+}
+
+/// 外部认证提供方返回的身份信息
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ExternalIdentity {
+    pub provider: String,
+    /// 提供方内的唯一标识
+    pub subject: String,
+    pub email: Option<String>,
+    pub email_verified: bool,
+    pub name: Option<String>,
+    pub preferred_username: Option<String>,
+}
+
+/// 外部身份映射到本地用户的策略
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ProvisionPolicy {
+    /// 未绑定时自动创建本地用户
+    pub auto_provision: bool,
+    /// 按已验证的邮箱绑定已有用户
+    pub link_by_email: bool,
+    /// 自动创建用户的角色
+    pub default_role_id: i64,
+}
+
+/// 目录服务(LDAP等)中的用户信息
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct DirectoryUser {
+    pub dn: String,
+    pub name: Option<String>,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    /// 所属组DN
+    pub groups: Vec<String>,
+}
+
+/// 目录服务认证结果
+#[derive(Debug, Clone)]
+pub enum DirectoryAuth {
+    Authenticated(DirectoryUser),
+    InvalidCredentials,
+    /// 目录中不存在该账号
+    NotFound,
+}
@@ -1,2 +1,8 @@
+pub mod api_key;
 pub mod captcha;
+pub mod identity;
+pub mod password_reset;
+pub mod register;
+pub mod session;
+pub mod totp;
 pub mod user;
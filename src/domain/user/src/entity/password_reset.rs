@@ -0,0 +1,7 @@
+use serde::{Deserialize, Serialize};
+
+/// 找回密码token, 保存在缓存中, 使用一次后失效
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PasswordResetToken {
+    pub user_id: i64,
+}
@@ -0,0 +1,7 @@
+use serde::{Deserialize, Serialize};
+
+/// 注册邮箱验证token, 保存在缓存中, 使用一次后失效
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct EmailVerifyToken {
+    pub user_id: i64,
+}
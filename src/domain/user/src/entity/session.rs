@@ -0,0 +1,24 @@
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+/// 登录会话, 与token中的token_id一一对应
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct UserSession {
+    pub token_id: i64,
+    pub user_id: i64,
+    pub tenant_id: i64,
+    pub username: String,
+    pub ip: String,
+    pub user_agent: String,
+    pub created_at: Option<DateTime<Local>>,
+    pub last_seen_at: Option<DateTime<Local>>,
+    pub expires_at: Option<DateTime<Local>>,
+    pub revoked_at: Option<DateTime<Local>>,
+}
+
+impl UserSession {
+    /// 未吊销且未过期
+    pub fn is_active(&self) -> bool {
+        self.revoked_at.is_none() && self.expires_at.map(|t| t > Local::now()).unwrap_or(true)
+    }
+}
@@ -0,0 +1,17 @@
+use serde::{Deserialize, Serialize};
+
+/// 绑定TOTP时返回的信息, 二维码为base64编码的图片
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TotpEnrollment {
+    pub secret: String,
+    pub otpauth_uri: String,
+    pub qr_image: String,
+}
+
+/// 两步登录挑战, 保存在缓存中
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LoginChallenge {
+    pub user_id: i64,
+    /// 完成密码校验时的IP, 提交动态码的请求须来自同一IP
+    pub ip: String,
+}
@@ -1,6 +1,16 @@
-use chrono::{DateTime, Local};
+use chrono::{DateTime, Duration, Local};
 use serde::{Deserialize, Serialize};
 
+/// 正常
+pub const USER_STATUS_ACTIVE: &str = "1";
+/// 自助注册, 等待邮箱验证
+pub const USER_STATUS_PENDING: &str = "2";
+
+/// 本地密码认证
+pub const AUTH_SOURCE_LOCAL: &str = "local";
+/// LDAP认证
+pub const AUTH_SOURCE_LDAP: &str = "ldap";
+
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct User {
     pub id: i64,
@@ -20,4 +30,46 @@ pub struct User {
     pub update_by: Option<i64>,
     pub updated_at: Option<DateTime<Local>>,
     pub deleted_at: Option<DateTime<Local>>,
+    /// 连续登录失败次数
+    pub login_fail_count: i32,
+    /// 账号锁定截止时间
+    pub locked_until: Option<DateTime<Local>>,
+    /// TOTP密钥(密文)
+    pub totp_secret: Option<String>,
+    /// 是否已启用TOTP双因素认证
+    pub totp_enabled: bool,
+    /// 恢复码哈希, JSON数组
+    pub totp_recovery_codes: Option<String>,
+    /// 最近一次修改密码的时间
+    pub password_changed_at: Option<DateTime<Local>>,
+    /// 认证来源, 为空时视为本地认证
+    pub auth_source: Option<String>,
+    /// 所属租户
+    pub tenant_id: i64,
+    /// 所属部门
+    pub dept_id: Option<i64>,
+    /// 最近一次登录的IP
+    pub last_login_ip: Option<String>,
+    /// 最近一次登录的时间
+    pub last_login_time: Option<DateTime<Local>>,
+}
+
+impl User {
+    /// 账号当前是否处于锁定状态
+    pub fn is_locked(&self) -> bool {
+        self.locked_until
+            .map(|until| until > Local::now())
+            .unwrap_or(false)
+    }
+
+    /// 密码是否已超过有效期, 从未修改过密码时按创建时间计算
+    pub fn is_password_expired(&self, max_age_days: i64) -> bool {
+        if max_age_days <= 0 {
+            return false;
+        }
+        self.password_changed_at
+            .or(self.created_at)
+            .map(|changed| changed + Duration::days(max_age_days) < Local::now())
+            .unwrap_or(false)
+    }
 }
@@ -6,24 +6,50 @@ mod services;
 
 pub const MODEL_USER_DOMAIN: &str = "userDomain";
 
+use commonx::config::config::Auth;
+
 use crate::repository::{
-    cache::CacheRepositoryTrait, encrypt::PwdEncryptTrait, user::UserRepositoryTrait,
+    auth_provider::{AuthProviderTrait, DirectoryProviderTrait},
+    cache::CacheRepositoryTrait,
+    encrypt::{PwdEncryptTrait, SecretEncryptTrait},
+    mailer::MailerRepositoryTrait,
+    totp::TotpTrait,
+    user::UserRepositoryTrait,
 };
 
 pub struct UserDomainImpl {
     cache: Box<dyn CacheRepositoryTrait + Sync + Send>,
     user_repo: Box<dyn UserRepositoryTrait + Sync + Send>,
     pwd_encrypt: Box<dyn PwdEncryptTrait + Sync + Send>,
+    secret_encrypt: Box<dyn SecretEncryptTrait + Sync + Send>,
+    totp: Box<dyn TotpTrait + Sync + Send>,
+    mailer: Box<dyn MailerRepositoryTrait + Sync + Send>,
+    auth_providers: Vec<Box<dyn AuthProviderTrait + Sync + Send>>,
+    directory: Option<Box<dyn DirectoryProviderTrait + Sync + Send>>,
+    auth_config: Auth,
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn new_user_domain(
     cache: Box<dyn CacheRepositoryTrait + Sync + Send>,
     user_repo: Box<dyn UserRepositoryTrait + Sync + Send>,
     pwd_encrypt: Box<dyn PwdEncryptTrait + Sync + Send>,
+    secret_encrypt: Box<dyn SecretEncryptTrait + Sync + Send>,
+    totp: Box<dyn TotpTrait + Sync + Send>,
+    mailer: Box<dyn MailerRepositoryTrait + Sync + Send>,
+    auth_providers: Vec<Box<dyn AuthProviderTrait + Sync + Send>>,
+    directory: Option<Box<dyn DirectoryProviderTrait + Sync + Send>>,
+    auth_config: Auth,
 ) -> UserDomainImpl {
     UserDomainImpl {
         cache,
         user_repo,
         pwd_encrypt,
+        secret_encrypt,
+        totp,
+        mailer,
+        auth_providers,
+        directory,
+        auth_config,
     }
 }
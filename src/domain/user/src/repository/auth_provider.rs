@@ -0,0 +1,36 @@
+use async_trait::async_trait;
+
+use crate::{
+    commons::error::UserDomainError,
+    entity::identity::{AuthorizeRequest, DirectoryAuth, ExternalIdentity, ProvisionPolicy},
+};
+
+/// 外部认证提供方(OIDC等), 作为密码登录之外的认证方式
+#[async_trait]
+pub trait AuthProviderTrait {
+    /// 提供方标识
+    fn name(&self) -> &str;
+    /// 外部身份映射到本地用户的策略
+    fn provision_policy(&self) -> ProvisionPolicy;
+    /// 是否跳过本地两步验证
+    fn skip_local_2fa(&self) -> bool;
+    /// 生成跳转到提供方的授权地址
+    async fn authorize_url(&self, request: &AuthorizeRequest) -> Result<String, UserDomainError>;
+    /// 使用授权码换取并校验外部身份
+    async fn exchange_code(
+        &self,
+        code: &str,
+        request: &AuthorizeRequest,
+    ) -> Result<ExternalIdentity, UserDomainError>;
+}
+
+/// 使用用户名密码认证的目录服务(LDAP/AD等)
+#[async_trait]
+pub trait DirectoryProviderTrait {
+    /// 以用户身份bind校验密码, 并返回目录中的用户信息
+    async fn authenticate(
+        &self,
+        username: &str,
+        password: &str,
+    ) -> Result<DirectoryAuth, UserDomainError>;
+}
@@ -1,4 +1,10 @@
-use crate::{commons::error::UserDomainError, entity::captcha::CaptchaCacheInfo};
+use crate::{
+    commons::error::UserDomainError,
+    entity::{
+        captcha::CaptchaCacheInfo, identity::AuthorizeRequest, password_reset::PasswordResetToken,
+        register::EmailVerifyToken, totp::LoginChallenge,
+    },
+};
 use async_trait::async_trait;
 
 #[async_trait]
@@ -7,7 +13,91 @@ pub trait CacheRepositoryTrait {
         &self,
         key: String,
         captcha: CaptchaCacheInfo,
+        ttl: u64,
     ) -> Result<bool, UserDomainError>;
 
+    /// 验证码生成计数加1, 返回统计窗口内的生成次数
+    async fn incr_captcha_count(&self, key: String, ttl: u64) -> Result<u64, UserDomainError>;
+
     async fn get_captcha(&self, client_id: String) -> Result<CaptchaCacheInfo, UserDomainError>;
+
+    /// 登录失败次数加一, 返回统计窗口内的失败次数
+    async fn incr_login_failure(&self, key: String, ttl: u64) -> Result<u64, UserDomainError>;
+
+    /// 获取统计窗口内的登录失败次数
+    async fn get_login_failure(&self, key: String) -> Result<u64, UserDomainError>;
+
+    /// 清除登录失败次数
+    async fn clear_login_failure(&self, key: String) -> Result<(), UserDomainError>;
+
+    /// 保存两步登录挑战
+    async fn set_login_challenge(
+        &self,
+        key: String,
+        value: LoginChallenge,
+        ttl: u64,
+    ) -> Result<(), UserDomainError>;
+
+    /// 获取两步登录挑战(获取后删除)
+    async fn take_login_challenge(
+        &self,
+        key: String,
+    ) -> Result<Option<LoginChallenge>, UserDomainError>;
+
+    /// 保存找回密码token
+    async fn set_password_reset(
+        &self,
+        key: String,
+        value: PasswordResetToken,
+        ttl: u64,
+    ) -> Result<(), UserDomainError>;
+
+    /// 获取找回密码token(不删除)
+    async fn get_password_reset(
+        &self,
+        key: String,
+    ) -> Result<Option<PasswordResetToken>, UserDomainError>;
+
+    /// 获取找回密码token(获取后删除)
+    async fn take_password_reset(
+        &self,
+        key: String,
+    ) -> Result<Option<PasswordResetToken>, UserDomainError>;
+
+    /// 保存注册邮箱验证token
+    async fn set_email_verify(
+        &self,
+        key: String,
+        value: EmailVerifyToken,
+        ttl: u64,
+    ) -> Result<(), UserDomainError>;
+
+    /// 获取注册邮箱验证token(获取后删除)
+    async fn take_email_verify(
+        &self,
+        key: String,
+    ) -> Result<Option<EmailVerifyToken>, UserDomainError>;
+
+    /// 保存外部认证的授权请求
+    async fn set_authorize_request(
+        &self,
+        key: String,
+        value: AuthorizeRequest,
+        ttl: u64,
+    ) -> Result<(), UserDomainError>;
+
+    /// 获取外部认证的授权请求(获取后删除)
+    async fn take_authorize_request(
+        &self,
+        key: String,
+    ) -> Result<Option<AuthorizeRequest>, UserDomainError>;
+
+    /// 标记会话已吊销, ttl 不小于token剩余有效期
+    async fn set_session_revoked(&self, key: String, ttl: u64) -> Result<(), UserDomainError>;
+
+    /// 会话是否已吊销
+    async fn is_session_revoked(&self, key: String) -> Result<bool, UserDomainError>;
+
+    /// 标记会话在ttl内已活跃, 返回是否为本窗口内首次标记
+    async fn mark_session_seen(&self, key: String, ttl: u64) -> Result<bool, UserDomainError>;
 }
@@ -3,4 +3,14 @@ use crate::commons::error::UserDomainError;
 pub trait PwdEncryptTrait {
     fn encrypt(&self, password: &String) -> Result<String, UserDomainError>;
     fn verify(&self, password: &String, encrypted_pwd: &String) -> bool;
+    /// 密码哈希的算法或参数已过时, 需要重新生成
+    fn needs_rehash(&self, encrypted_pwd: &String) -> bool;
+}
+
+/// 可逆加密, 用于保存需要还原的敏感数据
+pub trait SecretEncryptTrait {
+    fn encrypt(&self, plain: &str) -> Result<String, UserDomainError>;
+    fn decrypt(&self, cipher: &str) -> Result<String, UserDomainError>;
+    /// 不可逆摘要, 用于保存API Key等高熵随机token
+    fn digest(&self, plain: &str) -> String;
 }
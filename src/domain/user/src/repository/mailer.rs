@@ -0,0 +1,13 @@
+use crate::commons::error::UserDomainError;
+use async_trait::async_trait;
+
+#[async_trait]
+pub trait MailerRepositoryTrait {
+    /// 异步发送邮件(加入任务队列)
+    async fn send_mail(
+        &self,
+        from: Option<String>,
+        to: String,
+        text: String,
+    ) -> Result<(), UserDomainError>;
+}
@@ -1,3 +1,6 @@
+pub mod auth_provider;
 pub mod cache;
 pub mod encrypt;
+pub mod mailer;
+pub mod totp;
 pub mod user;
@@ -0,0 +1,12 @@
+use crate::commons::error::UserDomainError;
+
+pub trait TotpTrait {
+    /// 生成base32编码的随机密钥
+    fn generate_secret(&self) -> String;
+    /// 生成认证器App使用的 otpauth URI
+    fn otpauth_uri(&self, secret: &str, account: &str, issuer: &str) -> String;
+    /// 将 otpauth URI 生成base64编码的二维码图片
+    fn qr_image(&self, otpauth_uri: &str) -> Result<String, UserDomainError>;
+    /// 校验动态码, skew为允许的前后时间窗口数, 通过时返回匹配的时间窗口
+    fn verify(&self, secret: &str, code: &str, skew: u8) -> Option<u64>;
+}
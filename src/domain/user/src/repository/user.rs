@@ -1,13 +1,110 @@
 use async_trait::async_trait;
+use chrono::{DateTime, Local};
 
 use crate::commons::error::UserDomainError;
-use crate::entity::user::User;
+use crate::entity::{api_key::ApiKey, session::UserSession, user::User};
 
 #[async_trait]
 pub trait UserRepositoryTrait {
     async fn get_by_username(&self, username: String) -> Result<Option<User>, UserDomainError>;
+    async fn get_by_email(&self, email: String) -> Result<Option<User>, UserDomainError>;
     async fn get_by_id(&self, id: i64) -> Result<Option<User>, UserDomainError>;
     async fn update_by_id(&self, id: i64, user: User) -> Result<(), UserDomainError>;
     async fn create(&self, user: User) -> Result<i64, UserDomainError>;
     async fn remove(&self, id: i64) -> Result<(), UserDomainError>;
+    /// 更新登录失败次数及锁定状态
+    async fn update_login_lock(
+        &self,
+        id: i64,
+        login_fail_count: i32,
+        locked_until: Option<DateTime<Local>>,
+    ) -> Result<(), UserDomainError>;
+    /// 更新最近一次登录的IP及时间
+    async fn update_last_login(
+        &self,
+        id: i64,
+        ip: String,
+        login_time: DateTime<Local>,
+    ) -> Result<(), UserDomainError>;
+    /// 更新账号状态
+    async fn update_status(&self, id: i64, status: String) -> Result<(), UserDomainError>;
+    /// 更新密码哈希, changed_at 为空时只更新哈希(如升级哈希参数)
+    async fn update_password(
+        &self,
+        id: i64,
+        password: String,
+        changed_at: Option<DateTime<Local>>,
+    ) -> Result<(), UserDomainError>;
+    /// 获取最近使用过的密码哈希
+    async fn get_password_history(
+        &self,
+        user_id: i64,
+        limit: u64,
+    ) -> Result<Vec<String>, UserDomainError>;
+    /// 记录密码历史
+    async fn add_password_history(
+        &self,
+        user_id: i64,
+        password: String,
+    ) -> Result<(), UserDomainError>;
+    /// 更新目录服务同步过来的资料, 为空的字段不更新
+    async fn update_profile(
+        &self,
+        id: i64,
+        name: Option<String>,
+        email: Option<String>,
+        phone: Option<String>,
+        role_id: Option<i64>,
+    ) -> Result<(), UserDomainError>;
+    /// 根据前缀获取API Key
+    async fn get_api_key_by_prefix(
+        &self,
+        prefix: String,
+    ) -> Result<Option<ApiKey>, UserDomainError>;
+    /// 获取用户的全部API Key
+    async fn list_api_keys(&self, user_id: i64) -> Result<Vec<ApiKey>, UserDomainError>;
+    async fn create_api_key(&self, api_key: ApiKey) -> Result<i64, UserDomainError>;
+    /// 吊销API Key, 返回是否存在该用户的Key
+    async fn revoke_api_key(&self, id: i64, user_id: i64) -> Result<bool, UserDomainError>;
+    /// 更新最近使用时间
+    async fn touch_api_key(&self, id: i64) -> Result<(), UserDomainError>;
+    async fn create_session(&self, session: UserSession) -> Result<(), UserDomainError>;
+    async fn get_session(&self, token_id: i64) -> Result<Option<UserSession>, UserDomainError>;
+    /// 分页获取有效会话, user_id为空时获取所有用户的会话
+    async fn list_sessions(
+        &self,
+        user_id: Option<i64>,
+        page: u64,
+        page_size: u64,
+    ) -> Result<Vec<UserSession>, UserDomainError>;
+    /// 吊销会话, 返回是否有未吊销的会话被更新
+    async fn revoke_session(&self, token_id: i64) -> Result<bool, UserDomainError>;
+    /// 吊销用户的全部有效会话, 返回被吊销的token_id
+    async fn revoke_user_sessions(&self, user_id: i64) -> Result<Vec<i64>, UserDomainError>;
+    /// 更新最近活跃时间
+    async fn touch_session(&self, token_id: i64) -> Result<(), UserDomainError>;
+    /// 根据外部身份获取绑定的本地用户ID
+    async fn get_identity_user_id(
+        &self,
+        provider: String,
+        subject: String,
+    ) -> Result<Option<i64>, UserDomainError>;
+    /// 绑定外部身份
+    async fn add_identity(
+        &self,
+        user_id: i64,
+        provider: String,
+        subject: String,
+        email: Option<String>,
+    ) -> Result<(), UserDomainError>;
+    /// 更新TOTP密钥、启用状态及恢复码
+    async fn update_totp(
+        &self,
+        id: i64,
+        totp_secret: Option<String>,
+        totp_enabled: bool,
+        totp_recovery_codes: Option<String>,
+    ) -> Result<(), UserDomainError>;
+    /// 记录通过校验的TOTP时间窗口, 该窗口已使用过时返回false
+    async fn consume_totp_step(&self, id: i64, step: u64) -> Result<bool, UserDomainError>;
 }
@@ -0,0 +1,163 @@
+use chrono::{Duration, Local};
+use tracing::{info, warn};
+
+use crate::{
+    MODEL_USER_DOMAIN, UserDomainImpl,
+    api::dto::auth::{ApiKeyPrincipal, CreateApiKeyDto},
+    commons::error::UserDomainError,
+    entity::{
+        api_key::{ApiKey, ApiKeyCreated, ApiKeyScope},
+        user::USER_STATUS_PENDING,
+    },
+    services::service::random_string,
+};
+
+const API_KEY_PREFIX: &str = "vela";
+const PREFIX_LEN: usize = 8;
+const SECRET_LEN: usize = 40;
+
+fn invalid_api_key() -> UserDomainError {
+    UserDomainError::AuthError("API Key无效".to_string())
+}
+
+impl UserDomainImpl {
+    pub(super) async fn do_create_api_key(
+        &self,
+        req: CreateApiKeyDto,
+    ) -> Result<ApiKeyCreated, UserDomainError> {
+        let config = &self.auth_config.api_key;
+        if !config.enable {
+            return Err(UserDomainError::InvalidArgument(
+                "API Key未开启".to_string(),
+            ));
+        }
+        if req.scopes.is_empty() {
+            return Err(UserDomainError::InvalidArgument(
+                "至少需要一个权限范围".to_string(),
+            ));
+        }
+        if let Some(scope) = req.scopes.iter().find(|s| ApiKeyScope::parse(s).is_none()) {
+            return Err(UserDomainError::InvalidArgument(format!(
+                "权限范围格式错误: {}",
+                scope
+            )));
+        }
+        let expires_at = match req.expires_in_days {
+            Some(days) if days <= 0 => {
+                return Err(UserDomainError::InvalidArgument(
+                    "有效期必须大于0天".to_string(),
+                ));
+            }
+            Some(days) if config.max_ttl_days > 0 && days > config.max_ttl_days => {
+                return Err(UserDomainError::InvalidArgument(format!(
+                    "有效期不能超过{}天",
+                    config.max_ttl_days
+                )));
+            }
+            None if config.max_ttl_days > 0 => {
+                return Err(UserDomainError::InvalidArgument(
+                    "必须设置有效期".to_string(),
+                ));
+            }
+            days => days.map(|d| Local::now() + Duration::days(d)),
+        };
+        let active = self
+            .user_repo
+            .list_api_keys(req.user_id)
+            .await?
+            .iter()
+            .filter(|k| k.is_active())
+            .count() as u64;
+        if active >= config.max_per_user {
+            return Err(UserDomainError::InvalidArgument(format!(
+                "最多只能创建{}个API Key",
+                config.max_per_user
+            )));
+        }
+
+        let prefix = random_string(PREFIX_LEN);
+        let key = format!(
+            "{}_{}_{}",
+            API_KEY_PREFIX,
+            prefix,
+            random_string(SECRET_LEN)
+        );
+        let mut api_key = ApiKey {
+            user_id: req.user_id,
+            name: req.name,
+            prefix,
+            key_hash: self.secret_encrypt.digest(&key),
+            scopes: req.scopes,
+            expires_at,
+            created_at: Some(Local::now()),
+            ..Default::default()
+        };
+        api_key.id = self.user_repo.create_api_key(api_key.clone()).await?;
+        info!(target: MODEL_USER_DOMAIN, "创建API Key: user_id:{} prefix:{}", api_key.user_id, api_key.prefix);
+        Ok(ApiKeyCreated { key, api_key })
+    }
+
+    pub(super) async fn do_revoke_api_key(
+        &self,
+        user_id: i64,
+        id: i64,
+    ) -> Result<(), UserDomainError> {
+        if !self.user_repo.revoke_api_key(id, user_id).await? {
+            return Err(UserDomainError::InvalidArgument(
+                "API Key不存在".to_string(),
+            ));
+        }
+        info!(target: MODEL_USER_DOMAIN, "吊销API Key: user_id:{} id:{}", user_id, id);
+        Ok(())
+    }
+
+    pub(super) async fn do_authenticate_api_key(
+        &self,
+        key: String,
+    ) -> Result<ApiKeyPrincipal, UserDomainError> {
+        let config = &self.auth_config.api_key;
+        if !config.enable {
+            return Err(invalid_api_key());
+        }
+        let prefix = key
+            .strip_prefix(API_KEY_PREFIX)
+            .and_then(|rest| rest.strip_prefix('_'))
+            .and_then(|rest| rest.split_once('_'))
+            .map(|(prefix, _)| prefix.to_string())
+            .ok_or_else(invalid_api_key)?;
+        let api_key = self
+            .user_repo
+            .get_api_key_by_prefix(prefix)
+            .await?
+            .filter(|k| k.key_hash == self.secret_encrypt.digest(&key))
+            .ok_or_else(invalid_api_key)?;
+        if !api_key.is_active() {
+            warn!(target: MODEL_USER_DOMAIN, "使用已失效的API Key: id:{} prefix:{}", api_key.id, api_key.prefix);
+            return Err(UserDomainError::AuthError(
+                "API Key已过期或已吊销".to_string(),
+            ));
+        }
+
+        let user = self
+            .user_repo
+            .get_by_id(api_key.user_id)
+            .await?
+            .ok_or_else(invalid_api_key)?;
+        if user.is_locked() || user.status.as_deref() == Some(USER_STATUS_PENDING) {
+            return Err(UserDomainError::AuthError("账号不可用".to_string()));
+        }
+
+        let stale = api_key
+            .last_used_at
+            .map(|t| Local::now() - t > Duration::seconds(config.touch_interval))
+            .unwrap_or(true);
+        if stale && let Err(e) = self.user_repo.touch_api_key(api_key.id).await {
+            warn!(target: MODEL_USER_DOMAIN, "更新API Key使用时间失败: id:{} err:{}", api_key.id, e);
+        }
+        Ok(ApiKeyPrincipal {
+            key_id: api_key.id,
+            scopes: api_key.scopes,
+            user: self.user_info(user),
+        })
+    }
+}
@@ -0,0 +1,345 @@
+use std::f32::consts::TAU;
+
+use base64::{Engine, engine::general_purpose::STANDARD};
+use commonx::config::config::{CaptchaConfig, CaptchaKind};
+use image::{ColorType, Rgb, RgbImage, codecs::png::PngEncoder};
+use rand::Rng;
+
+use crate::commons::error::UserDomainError;
+
+// 去掉了容易混淆的 0/O、1/I/L
+const TEXT_CHARSET: &[u8] = b"ABCDEFGHJKMNPQRSTUVWXYZ23456789";
+
+const COLORS: [[u8; 3]; 6] = [
+    [47, 84, 235],
+    [19, 168, 168],
+    [56, 158, 13],
+    [212, 107, 8],
+    [207, 19, 34],
+    [83, 29, 171],
+];
+
+type Stroke = &'static [(u8, u8)];
+
+/// 字形轮廓, 每个字符由若干笔画组成, 坐标为4x6网格(x:0-4, y:0-6, 原点在左上角)
+#[rustfmt::skip]
+fn glyph(c: char) -> &'static [Stroke] {
+    match c {
+        'A' => &[&[(0, 6), (2, 0), (4, 6)], &[(1, 4), (3, 4)]],
+        'B' => &[&[(0, 0), (0, 6), (3, 6), (4, 5), (4, 4), (3, 3), (0, 3)], &[(0, 0), (3, 0), (4, 1), (4, 2), (3, 3)]],
+        'C' => &[&[(4, 1), (3, 0), (1, 0), (0, 1), (0, 5), (1, 6), (3, 6), (4, 5)]],
+        'D' => &[&[(0, 0), (0, 6), (2, 6), (4, 4), (4, 2), (2, 0), (0, 0)]],
+        'E' => &[&[(4, 0), (0, 0), (0, 6), (4, 6)], &[(0, 3), (3, 3)]],
+        'F' => &[&[(4, 0), (0, 0), (0, 6)], &[(0, 3), (3, 3)]],
+        'G' => &[&[(4, 1), (3, 0), (1, 0), (0, 1), (0, 5), (1, 6), (3, 6), (4, 5), (4, 3), (2, 3)]],
+        'H' => &[&[(0, 0), (0, 6)], &[(4, 0), (4, 6)], &[(0, 3), (4, 3)]],
+        'J' => &[&[(1, 0), (4, 0), (4, 5), (3, 6), (1, 6), (0, 5)]],
+        'K' => &[&[(0, 0), (0, 6)], &[(4, 0), (0, 4)], &[(1, 3), (4, 6)]],
+        'M' => &[&[(0, 6), (0, 0), (2, 3), (4, 0), (4, 6)]],
+        'N' => &[&[(0, 6), (0, 0), (4, 6), (4, 0)]],
+        'P' => &[&[(0, 6), (0, 0), (3, 0), (4, 1), (4, 2), (3, 3), (0, 3)]],
+        'Q' => &[&[(1, 0), (3, 0), (4, 1), (4, 5), (3, 6), (1, 6), (0, 5), (0, 1), (1, 0)], &[(2, 4), (4, 6)]],
+        'R' => &[&[(0, 6), (0, 0), (3, 0), (4, 1), (4, 2), (3, 3), (0, 3)], &[(2, 3), (4, 6)]],
+        'S' => &[&[(4, 1), (3, 0), (1, 0), (0, 1), (0, 2), (1, 3), (3, 3), (4, 4), (4, 5), (3, 6), (1, 6), (0, 5)]],
+        'T' => &[&[(0, 0), (4, 0)], &[(2, 0), (2, 6)]],
+        'U' => &[&[(0, 0), (0, 5), (1, 6), (3, 6), (4, 5), (4, 0)]],
+        'V' => &[&[(0, 0), (2, 6), (4, 0)]],
+        'W' => &[&[(0, 0), (1, 6), (2, 3), (3, 6), (4, 0)]],
+        'X' => &[&[(0, 0), (4, 6)], &[(4, 0), (0, 6)]],
+        'Y' => &[&[(0, 0), (2, 3), (4, 0)], &[(2, 3), (2, 6)]],
+        'Z' => &[&[(0, 0), (4, 0), (0, 6), (4, 6)]],
+        '0' => &[&[(1, 0), (3, 0), (4, 1), (4, 5), (3, 6), (1, 6), (0, 5), (0, 1), (1, 0)], &[(4, 1), (0, 5)]],
+        '1' => &[&[(1, 1), (2, 0), (2, 6)], &[(1, 6), (3, 6)]],
+        '2' => &[&[(0, 1), (1, 0), (3, 0), (4, 1), (4, 2), (0, 6), (4, 6)]],
+        '3' => &[&[(0, 1), (1, 0), (3, 0), (4, 1), (4, 2), (3, 3), (4, 4), (4, 5), (3, 6), (1, 6), (0, 5)], &[(1, 3), (3, 3)]],
+        '4' => &[&[(3, 6), (3, 0), (0, 4), (4, 4)]],
+        '5' => &[&[(4, 0), (0, 0), (0, 3), (3, 3), (4, 4), (4, 5), (3, 6), (0, 6)]],
+        '6' => &[&[(3, 0), (1, 0), (0, 1), (0, 5), (1, 6), (3, 6), (4, 5), (4, 4), (3, 3), (0, 3)]],
+        '7' => &[&[(0, 0), (4, 0), (1, 6)]],
+        '8' => &[&[(1, 3), (0, 2), (0, 1), (1, 0), (3, 0), (4, 1), (4, 2), (3, 3), (1, 3), (0, 4), (0, 5), (1, 6), (3, 6), (4, 5), (4, 4), (3, 3)]],
+        '9' => &[&[(4, 3), (1, 3), (0, 2), (0, 1), (1, 0), (3, 0), (4, 1), (4, 5), (3, 6), (1, 6)]],
+        '+' => &[&[(0, 3), (4, 3)], &[(2, 1), (2, 5)]],
+        '-' => &[&[(0, 3), (4, 3)]],
+        '×' => &[&[(0, 1), (4, 5)], &[(4, 1), (0, 5)]],
+        '=' => &[&[(0, 2), (4, 2)], &[(0, 4), (4, 4)]],
+        '?' => &[&[(0, 1), (1, 0), (3, 0), (4, 1), (4, 2), (2, 4)], &[(2, 5), (2, 6)]],
+        _ => &[],
+    }
+}
+
+/// 生成验证码, 返回(图片上显示的内容, 答案)
+pub(super) fn gen_challenge(config: &CaptchaConfig) -> (String, String) {
+    let mut rng = rand::rng();
+    match config.kind {
+        CaptchaKind::Text => {
+            let text: String = (0..config.text_length())
+                .map(|_| TEXT_CHARSET[rng.random_range(0..TEXT_CHARSET.len())] as char)
+                .collect();
+            (text.clone(), text)
+        }
+        CaptchaKind::Arithmetic => {
+            let complexity = config.complexity.clamp(1, 10);
+            let (a, b, op) = match complexity {
+                1..=3 => (rng.random_range(1..=9), rng.random_range(1..=9), '+'),
+                4..=6 => {
+                    let a = rng.random_range(1..=20);
+                    let b = rng.random_range(1..=20);
+                    if rng.random_bool(0.5) {
+                        (a, b, '+')
+                    } else {
+                        // 保证结果非负
+                        (a.max(b), a.min(b), '-')
+                    }
+                }
+                _ => (rng.random_range(2..=9), rng.random_range(2..=9), '*'),
+            };
+            let answer = match op {
+                '+' => a + b,
+                '-' => a - b,
+                _ => a * b,
+            };
+            let display = if op == '*' { '×' } else { op };
+            (format!("{}{}{}=?", a, display, b), answer.to_string())
+        }
+    }
+}
+
+/// 渲染为 PNG 图片, 返回 data URI。
+/// 笔画逐点采样后经倾斜、旋转、整图正弦扭曲再栅格化, 图片中不保留字形的笔画结构
+pub(super) fn render_png(
+    text: &str,
+    width: u32,
+    height: u32,
+    complexity: u8,
+) -> Result<String, UserDomainError> {
+    let mut rng = rand::rng();
+    let level = complexity.clamp(1, 10) as f32;
+    let (w, h) = (width.max(40), height.max(20));
+    let (wf, hf) = (w as f32, h as f32);
+    let wave = Wave {
+        amp_x: hf * 0.008 * level * rng.random_range(0.5..=1.0),
+        amp_y: hf * 0.01 * level * rng.random_range(0.5..=1.0),
+        freq_x: TAU / (wf * rng.random_range(0.3..=0.8)),
+        freq_y: TAU / (hf * rng.random_range(0.8..=1.6)),
+        phase_x: rng.random_range(0.0..TAU),
+        phase_y: rng.random_range(0.0..TAU),
+    };
+    let mut canvas = Canvas {
+        img: RgbImage::from_fn(w, h, |_, _| {
+            let v = rng.random_range(232..=250);
+            Rgb([v, v, v])
+        }),
+        mask: vec![0.0; (w * h) as usize],
+        wave,
+    };
+
+    // 细干扰曲线
+    for _ in 0..level as usize + 2 {
+        let radius = rng.random_range(0.3..=0.6);
+        canvas.curve(random_curve(&mut rng, wf, hf), radius);
+        canvas.fill(random_color(&mut rng));
+    }
+
+    let chars: Vec<char> = text.chars().collect();
+    let step = wf / (chars.len() as f32 + 1.0);
+    // 字形按4x6的网格绘制, 高度取图片高度的2/3, 且不超出字符间距
+    let scale = (hf * 2.0 / 3.0 / 6.0).min(step / 4.0 * 1.2).max(2.0);
+    let radius = (scale / 5.0).max(0.8);
+    let max_angle = level * 4.0;
+    let jitter = scale * level / 20.0;
+    for (i, c) in chars.iter().enumerate() {
+        let cx = step * (i as f32 + 1.0);
+        let cy = hf / 2.0 + rng.random_range(-hf / 8.0..=hf / 8.0);
+        let (sin, cos) = rng
+            .random_range(-max_angle..=max_angle)
+            .to_radians()
+            .sin_cos();
+        let skew = rng.random_range(-0.3..=0.3) * level / 10.0;
+        for stroke in glyph(*c) {
+            let points: Vec<(f32, f32)> = stroke
+                .iter()
+                .map(|(gx, gy)| {
+                    // 以字形中心为原点, 依次做倾斜、旋转、随机抖动
+                    let y = (*gy as f32 - 3.0) * scale;
+                    let x = (*gx as f32 - 2.0) * scale + y * skew;
+                    (
+                        cx + x * cos - y * sin + rng.random_range(-jitter..=jitter),
+                        cy + x * sin + y * cos + rng.random_range(-jitter..=jitter),
+                    )
+                })
+                .collect();
+            for pair in points.windows(2) {
+                canvas.segment(pair[0], pair[1], radius);
+            }
+        }
+        canvas.fill(random_color(&mut rng));
+    }
+
+    // 与字符笔画同样粗细的干扰曲线, 增加字符分割难度
+    for _ in 0..level as usize / 4 {
+        canvas.curve(random_curve(&mut rng, wf, hf), radius);
+        canvas.fill(random_color(&mut rng));
+    }
+
+    for _ in 0..(wf * hf * level / 400.0) as usize {
+        let (x, y) = (rng.random_range(0..w), rng.random_range(0..h));
+        canvas.img.put_pixel(x, y, Rgb(random_color(&mut rng)));
+    }
+
+    let mut png = Vec::new();
+    PngEncoder::new(&mut png)
+        .encode(canvas.img.as_raw(), w, h, ColorType::Rgb8)
+        .map_err(|e| UserDomainError::InternalError(format!("验证码图片编码失败: {}", e)))?;
+    Ok(format!("data:image/png;base64,{}", STANDARD.encode(png)))
+}
+
+fn random_color(rng: &mut impl Rng) -> [u8; 3] {
+    COLORS[rng.random_range(0..COLORS.len())]
+}
+
+/// 横穿图片的二次贝塞尔曲线
+fn random_curve(rng: &mut impl Rng, w: f32, h: f32) -> [(f32, f32); 3] {
+    [
+        (rng.random_range(0.0..w / 4.0), rng.random_range(0.0..h)),
+        (
+            rng.random_range(w / 4.0..w * 3.0 / 4.0),
+            rng.random_range(-h / 2.0..h * 1.5),
+        ),
+        (rng.random_range(w * 3.0 / 4.0..w), rng.random_range(0.0..h)),
+    ]
+}
+
+/// 整幅图共用的正弦扭曲, 字符与干扰线一起变形
+struct Wave {
+    amp_x: f32,
+    amp_y: f32,
+    freq_x: f32,
+    freq_y: f32,
+    phase_x: f32,
+    phase_y: f32,
+}
+
+impl Wave {
+    fn apply(&self, x: f32, y: f32) -> (f32, f32) {
+        (
+            x + self.amp_x * (y * self.freq_y + self.phase_x).sin(),
+            y + self.amp_y * (x * self.freq_x + self.phase_y).sin(),
+        )
+    }
+}
+
+/// 笔画先写入遮罩, 再按遮罩的覆盖率整体混合颜色, 避免同一笔画重叠处颜色加深
+struct Canvas {
+    img: RgbImage,
+    mask: Vec<f32>,
+    wave: Wave,
+}
+
+impl Canvas {
+    fn curve(&mut self, [p0, p1, p2]: [(f32, f32); 3], radius: f32) {
+        const STEPS: usize = 24;
+        let at = |t: f32| {
+            let u = 1.0 - t;
+            (
+                u * u * p0.0 + 2.0 * u * t * p1.0 + t * t * p2.0,
+                u * u * p0.1 + 2.0 * u * t * p1.1 + t * t * p2.1,
+            )
+        };
+        for i in 0..STEPS {
+            let (a, b) = (i as f32 / STEPS as f32, (i + 1) as f32 / STEPS as f32);
+            self.segment(at(a), at(b), radius);
+        }
+    }
+
+    /// 沿线段按半像素间隔采样, 扭曲后以圆形笔触写入遮罩
+    fn segment(&mut self, from: (f32, f32), to: (f32, f32), radius: f32) {
+        let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+        let steps = ((dx * dx + dy * dy).sqrt() * 2.0).ceil().max(1.0) as usize;
+        for i in 0..=steps {
+            let t = i as f32 / steps as f32;
+            let (x, y) = self.wave.apply(from.0 + dx * t, from.1 + dy * t);
+            self.stamp(x, y, radius);
+        }
+    }
+
+    fn stamp(&mut self, cx: f32, cy: f32, radius: f32) {
+        let (w, h) = self.img.dimensions();
+        let reach = radius + 1.0;
+        let span = |c: f32, max: u32| {
+            (c - reach).floor().max(0.0) as u32
+                ..=(c + reach).ceil().clamp(0.0, (max - 1) as f32) as u32
+        };
+        for y in span(cy, h) {
+            for x in span(cx, w) {
+                let d = ((x as f32 + 0.5 - cx).powi(2) + (y as f32 + 0.5 - cy).powi(2)).sqrt();
+                let cover = (radius + 0.5 - d).clamp(0.0, 1.0);
+                let m = &mut self.mask[(y * w + x) as usize];
+                *m = m.max(cover);
+            }
+        }
+    }
+
+    /// 以遮罩为透明度混合颜色, 并清空遮罩
+    fn fill(&mut self, color: [u8; 3]) {
+        for (pixel, m) in self.img.pixels_mut().zip(self.mask.iter_mut()) {
+            if *m > 0.0 {
+                for (c, t) in pixel.0.iter_mut().zip(color) {
+                    *c = (*c as f32 * (1.0 - *m) + t as f32 * *m).round() as u8;
+                }
+                *m = 0.0;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arithmetic_answer_fits_answer_length() {
+        for complexity in 1..=10 {
+            let config = CaptchaConfig {
+                kind: CaptchaKind::Arithmetic,
+                complexity,
+                ..Default::default()
+            };
+            let (min, max) = config.answer_length();
+            for _ in 0..200 {
+                let (_, answer) = gen_challenge(&config);
+                assert!(answer.len() >= min && answer.len() <= max, "{}", answer);
+            }
+        }
+    }
+
+    #[test]
+    fn every_challenge_char_has_glyph() {
+        for kind in [CaptchaKind::Text, CaptchaKind::Arithmetic] {
+            let config = CaptchaConfig {
+                kind,
+                complexity: 10,
+                ..Default::default()
+            };
+            for _ in 0..50 {
+                let (text, _) = gen_challenge(&config);
+                assert!(text.chars().all(|c| !glyph(c).is_empty()), "{}", text);
+            }
+        }
+    }
+
+    #[test]
+    fn same_text_renders_differently() {
+        let decode = |uri: String| {
+            let png = STANDARD
+                .decode(uri.trim_start_matches("data:image/png;base64,"))
+                .unwrap();
+            image::load_from_memory(&png).unwrap().to_rgb8()
+        };
+        for complexity in [1, 5, 10] {
+            let a = decode(render_png("A", 120, 40, complexity).unwrap());
+            let b = decode(render_png("A", 120, 40, complexity).unwrap());
+            assert_eq!(a.dimensions(), (120, 40));
+            assert_ne!(a.as_raw(), b.as_raw());
+        }
+    }
+}
@@ -0,0 +1,183 @@
+use chrono::Local;
+use commonx::config::config::{LdapGroupRole, LdapMode};
+use tracing::{info, warn};
+
+use crate::{
+    MODEL_USER_DOMAIN, UserDomainImpl,
+    commons::error::UserDomainError,
+    entity::{
+        identity::{DirectoryAuth, DirectoryUser},
+        user::{AUTH_SOURCE_LDAP, USER_STATUS_ACTIVE, User},
+    },
+    services::service::random_string,
+};
+
+/// 密码校验结果
+pub(super) enum PasswordCheck {
+    /// 校验通过, local 表示使用的是本地密码
+    Passed {
+        user: User,
+        local: bool,
+    },
+    Failed(Option<User>),
+}
+
+// 组可以配置为完整DN, 也可以只写cn
+fn group_matches(group_dn: &str, group: &str) -> bool {
+    if group_dn.eq_ignore_ascii_case(group) {
+        return true;
+    }
+    group_dn
+        .split(',')
+        .next()
+        .and_then(|rdn| rdn.split_once('='))
+        .map(|(attr, value)| {
+            attr.trim().eq_ignore_ascii_case("cn") && value.trim().eq_ignore_ascii_case(group)
+        })
+        .unwrap_or(false)
+}
+
+/// 按配置顺序返回第一个匹配的角色
+fn map_group_role(groups: &[String], mappings: &[LdapGroupRole]) -> Option<i64> {
+    mappings
+        .iter()
+        .find(|m| groups.iter().any(|g| group_matches(g, &m.group)))
+        .map(|m| m.role_id)
+}
+
+impl UserDomainImpl {
+    // 按配置选择LDAP或本地密码校验
+    pub(super) async fn check_password(
+        &self,
+        username: &str,
+        password: &String,
+        user: Option<User>,
+    ) -> Result<PasswordCheck, UserDomainError> {
+        // 本地来源的账号只校验本地密码, 避免目录中的同名条目冒用本地账号
+        let ldap_user = user
+            .as_ref()
+            .is_some_and(|u| u.auth_source.as_deref() == Some(AUTH_SOURCE_LDAP));
+        let use_directory = match self.auth_config.ldap.mode {
+            LdapMode::Global => user.is_none() || ldap_user,
+            LdapMode::PerUser => ldap_user,
+        };
+
+        if let (true, Some(directory)) = (use_directory, &self.directory) {
+            // 空密码会被LDAP当作匿名bind, 直接拒绝
+            if password.is_empty() {
+                return Ok(PasswordCheck::Failed(user));
+            }
+            return match directory.authenticate(username, password).await? {
+                DirectoryAuth::Authenticated(entry) => {
+                    let user = self.sync_directory_user(username, user, entry).await?;
+                    Ok(PasswordCheck::Passed { user, local: false })
+                }
+                DirectoryAuth::InvalidCredentials | DirectoryAuth::NotFound => {
+                    Ok(PasswordCheck::Failed(user))
+                }
+            };
+        }
+
+        match user {
+            Some(user) if self.pwd_encrypt.verify(password, &user.password) => {
+                Ok(PasswordCheck::Passed { user, local: true })
+            }
+            user => Ok(PasswordCheck::Failed(user)),
+        }
+    }
+
+    // 同步目录中的资料及组角色, 本地不存在时按配置自动创建
+    async fn sync_directory_user(
+        &self,
+        username: &str,
+        user: Option<User>,
+        entry: DirectoryUser,
+    ) -> Result<User, UserDomainError> {
+        let config = &self.auth_config.ldap;
+        let role_id = map_group_role(&entry.groups, &config.group_roles);
+
+        if let Some(user) = &user
+            && user.auth_source.as_deref() != Some(AUTH_SOURCE_LDAP)
+        {
+            warn!(target: MODEL_USER_DOMAIN, "LDAP账号与本地账号同名, 拒绝登录: username:{} dn:{}", username, entry.dn);
+            return Err(UserDomainError::AuthError("账号认证来源不匹配".to_string()));
+        }
+        let Some(user) = user else {
+            if !config.auto_provision {
+                warn!(target: MODEL_USER_DOMAIN, "LDAP账号未在系统中开通: username:{} dn:{}", username, entry.dn);
+                return Err(UserDomainError::AuthError("账号未开通".to_string()));
+            }
+            // 随机密码, 该账号只能通过LDAP认证
+            let password = self.pwd_encrypt.encrypt(&random_string(32))?;
+            let user = User {
+                role_id: role_id.unwrap_or(config.default_role_id),
+                username: username.to_string(),
+                name: entry.name.or_else(|| Some(username.to_string())),
+                email: entry.email,
+                phone: entry.phone,
+                password,
+                status: Some(USER_STATUS_ACTIVE.to_string()),
+                remark: Some("LDAP自动创建".to_string()),
+                password_changed_at: Some(Local::now()),
+                auth_source: Some(AUTH_SOURCE_LDAP.to_string()),
+                ..Default::default()
+            };
+            let id = self.user_repo.create(user).await?;
+            info!(target: MODEL_USER_DOMAIN, "LDAP账号自动创建用户: username:{} dn:{}", username, entry.dn);
+            return self
+                .user_repo
+                .get_by_id(id)
+                .await?
+                .ok_or_else(|| UserDomainError::UserNotFound(id.to_string()));
+        };
+
+        let (name, email, phone) = if config.sync_profile {
+            (
+                entry.name.filter(|v| user.name.as_ref() != Some(v)),
+                entry.email.filter(|v| user.email.as_ref() != Some(v)),
+                entry.phone.filter(|v| user.phone.as_ref() != Some(v)),
+            )
+        } else {
+            (None, None, None)
+        };
+        // 未匹配到组映射时保留原角色
+        let role_id = role_id.filter(|r| *r != user.role_id);
+        if name.is_none() && email.is_none() && phone.is_none() && role_id.is_none() {
+            return Ok(user);
+        }
+        self.user_repo
+            .update_profile(user.id, name, email, phone, role_id)
+            .await?;
+        info!(target: MODEL_USER_DOMAIN, "同步LDAP用户资料: username:{} role_id:{:?}", username, role_id);
+        self.user_repo
+            .get_by_id(user.id)
+            .await?
+            .ok_or_else(|| UserDomainError::UserNotFound(user.id.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn group_role_mapping() {
+        let mappings = vec![
+            LdapGroupRole {
+                group: "vela-admins".to_string(),
+                role_id: 1,
+            },
+            LdapGroupRole {
+                group: "cn=staff,ou=groups,dc=example,dc=org".to_string(),
+                role_id: 2,
+            },
+        ];
+        let groups = vec![
+            "CN=Staff,OU=Groups,DC=example,DC=org".to_string(),
+            "cn=vela-admins,ou=groups,dc=example,dc=org".to_string(),
+        ];
+        assert_eq!(map_group_role(&groups, &mappings), Some(1));
+        assert_eq!(map_group_role(&groups[..1], &mappings), Some(2));
+        assert_eq!(map_group_role(&[], &mappings), None);
+    }
+}
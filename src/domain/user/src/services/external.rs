@@ -0,0 +1,221 @@
+use chrono::Local;
+use tracing::{info, warn};
+
+use crate::{
+    MODEL_USER_DOMAIN, UserDomainImpl,
+    api::dto::auth::{ExternalAuthorizeDto, ExternalLoginDto, LoginOutcome, LoginSubject},
+    commons::error::UserDomainError,
+    entity::{
+        identity::{AuthorizeRequest, ExternalIdentity},
+        user::{USER_STATUS_ACTIVE, USER_STATUS_PENDING, User},
+    },
+    repository::auth_provider::AuthProviderTrait,
+    services::service::{format_locked_until, random_string},
+};
+
+fn authorize_request_key(state: &str) -> String {
+    format!("authorize_request:{}", state)
+}
+
+// 未绑定本地用户时, 登录日志记录外部账号的名称
+fn identity_subject(identity: &ExternalIdentity) -> LoginSubject {
+    let username = identity
+        .preferred_username
+        .as_ref()
+        .or(identity.email.as_ref())
+        .cloned()
+        .unwrap_or_else(|| format!("{}:{}", identity.provider, identity.subject));
+    LoginSubject::username(&username)
+}
+
+impl UserDomainImpl {
+    fn auth_provider(
+        &self,
+        name: &str,
+    ) -> Result<&(dyn AuthProviderTrait + Sync + Send), UserDomainError> {
+        self.auth_providers
+            .iter()
+            .find(|p| p.name() == name)
+            .map(|p| p.as_ref())
+            .ok_or_else(|| UserDomainError::AuthError(format!("不支持的认证方式:{}", name)))
+    }
+
+    pub(super) async fn do_external_authorize(
+        &self,
+        provider: String,
+    ) -> Result<ExternalAuthorizeDto, UserDomainError> {
+        let auth_provider = self.auth_provider(&provider)?;
+        let request = AuthorizeRequest {
+            provider,
+            state: random_string(32),
+            nonce: random_string(32),
+            code_verifier: random_string(64),
+        };
+        let url = auth_provider.authorize_url(&request).await?;
+        let state = request.state.clone();
+        self.cache
+            .set_authorize_request(
+                authorize_request_key(&state),
+                request,
+                self.auth_config.oidc.state_ttl,
+            )
+            .await?;
+        Ok(ExternalAuthorizeDto { url, state })
+    }
+
+    // 启用了TOTP的账号与密码登录一样需要输入动态码, 提供方配置了skip_local_2fa时除外
+    pub(super) async fn do_external_login(
+        &self,
+        req: ExternalLoginDto,
+    ) -> Result<LoginOutcome, UserDomainError> {
+        let request = self
+            .cache
+            .take_authorize_request(authorize_request_key(&req.state))
+            .await?
+            .filter(|r| r.provider == req.provider)
+            .ok_or_else(|| UserDomainError::AuthError("授权请求无效或已过期".to_string()))?;
+        let auth_provider = self.auth_provider(&req.provider)?;
+        let identity = auth_provider.exchange_code(&req.code, &request).await?;
+        let user = self
+            .resolve_identity(auth_provider, &identity)
+            .await
+            .map_err(|e| e.with_subject(identity_subject(&identity)))?;
+
+        if user.is_locked() {
+            return Err(UserDomainError::AccountLocked {
+                username: user.username.clone(),
+                until: format_locked_until(user.locked_until),
+            }
+            .with_subject(LoginSubject::from(&user)));
+        }
+        if user.status.as_deref() == Some(USER_STATUS_PENDING) {
+            return Err(
+                UserDomainError::AccountNotActivated.with_subject(LoginSubject::from(&user))
+            );
+        }
+        info!(target: MODEL_USER_DOMAIN,
+            "外部认证登录: provider:{} subject:{} username:{} ip:{}",
+            identity.provider, identity.subject, user.username, req.ip
+        );
+        if user.totp_enabled && auth_provider.skip_local_2fa() {
+            info!(target: MODEL_USER_DOMAIN,
+                "提供方已配置跳过本地两步验证: provider:{} username:{}",
+                identity.provider, user.username
+            );
+            return Ok(LoginOutcome::Authenticated(Box::new(self.user_info(user))));
+        }
+        self.login_outcome(user, &req.ip).await
+    }
+
+    // 外部身份映射到本地用户: 已绑定 -> 按邮箱绑定 -> 自动创建
+    async fn resolve_identity(
+        &self,
+        auth_provider: &(dyn AuthProviderTrait + Sync + Send),
+        identity: &ExternalIdentity,
+    ) -> Result<User, UserDomainError> {
+        if let Some(user_id) = self
+            .user_repo
+            .get_identity_user_id(identity.provider.clone(), identity.subject.clone())
+            .await?
+        {
+            return self
+                .user_repo
+                .get_by_id(user_id)
+                .await?
+                .ok_or_else(|| UserDomainError::UserNotFound(user_id.to_string()));
+        }
+
+        let policy = auth_provider.provision_policy();
+        if policy.link_by_email
+            && identity.email_verified
+            && let Some(email) = identity.email.clone()
+            && let Some(user) = self.user_repo.get_by_email(email).await?
+        {
+            self.bind_identity(user.id, identity).await?;
+            info!(target: MODEL_USER_DOMAIN,
+                "外部身份按邮箱绑定: provider:{} subject:{} username:{}",
+                identity.provider, identity.subject, user.username
+            );
+            return Ok(user);
+        }
+
+        if !policy.auto_provision {
+            warn!(target: MODEL_USER_DOMAIN,
+                "外部身份未绑定本地用户: provider:{} subject:{}",
+                identity.provider, identity.subject
+            );
+            return Err(UserDomainError::AuthError(
+                "外部账号未绑定本地用户".to_string(),
+            ));
+        }
+
+        let username = self.provision_username(identity).await?;
+        // 随机密码, 用户需通过找回密码设置后才能使用密码登录
+        let password = self.pwd_encrypt.encrypt(&random_string(32))?;
+        let user = User {
+            role_id: policy.default_role_id,
+            username: username.clone(),
+            name: identity.name.clone().or_else(|| Some(username.clone())),
+            email: identity.email.clone(),
+            password,
+            status: Some(USER_STATUS_ACTIVE.to_string()),
+            remark: Some(format!("外部认证自动创建: {}", identity.provider)),
+            password_changed_at: Some(Local::now()),
+            ..Default::default()
+        };
+        let id = self.user_repo.create(user).await?;
+        self.bind_identity(id, identity).await?;
+        info!(target: MODEL_USER_DOMAIN,
+            "外部身份自动创建用户: provider:{} subject:{} username:{}",
+            identity.provider, identity.subject, username
+        );
+        self.user_repo
+            .get_by_id(id)
+            .await?
+            .ok_or_else(|| UserDomainError::UserNotFound(id.to_string()))
+    }
+
+    async fn bind_identity(
+        &self,
+        user_id: i64,
+        identity: &ExternalIdentity,
+    ) -> Result<(), UserDomainError> {
+        self.user_repo
+            .add_identity(
+                user_id,
+                identity.provider.clone(),
+                identity.subject.clone(),
+                identity.email.clone(),
+            )
+            .await
+    }
+
+    // 优先使用 preferred_username, 其次邮箱前缀, 重名时追加随机后缀
+    async fn provision_username(
+        &self,
+        identity: &ExternalIdentity,
+    ) -> Result<String, UserDomainError> {
+        let base = identity
+            .preferred_username
+            .clone()
+            .or_else(|| {
+                identity
+                    .email
+                    .as_ref()
+                    .and_then(|e| e.split('@').next().map(|s| s.to_string()))
+            })
+            .filter(|s| !s.is_empty())
+            .unwrap_or_else(|| format!("{}_{}", identity.provider, identity.subject));
+        let base: String = base.chars().take(20).collect();
+        if self
+            .user_repo
+            .get_by_username(base.clone())
+            .await?
+            .is_none()
+        {
+            return Ok(base);
+        }
+        let prefix: String = base.chars().take(13).collect();
+        Ok(format!("{}_{}", prefix, random_string(6).to_lowercase()))
+    }
+}
@@ -1 +1,9 @@
 pub mod service;
+mod api_key;
+mod captcha;
+mod directory;
+mod external;
+mod password;
+mod register;
+mod session;
+mod totp;
@@ -0,0 +1,250 @@
+use chrono::Local;
+use commonx::config::config::PasswordPolicy;
+use tracing::{error, info, warn};
+
+use crate::{
+    MODEL_USER_DOMAIN, UserDomainImpl,
+    api::dto::{
+        auth::{ChangePasswordDto, ForgotPasswordDto, ResetPasswordDto},
+        user_info::UserInfoDto,
+    },
+    commons::error::UserDomainError,
+    entity::{password_reset::PasswordResetToken, user::User},
+    services::service::random_string,
+};
+
+fn password_reset_key(token: &str) -> String {
+    format!("password_reset:{}", token)
+}
+
+/// 按密码策略校验新密码
+pub(super) fn check_password_policy(
+    policy: &PasswordPolicy,
+    username: &str,
+    password: &str,
+) -> Result<(), UserDomainError> {
+    let err = |msg: String| Err(UserDomainError::PasswordPolicyError(msg));
+    let len = password.chars().count();
+    if len < policy.min_length || len > policy.max_length {
+        return err(format!(
+            "密码长度必须在{}-{}之间",
+            policy.min_length, policy.max_length
+        ));
+    }
+    if policy.require_uppercase && !password.chars().any(|c| c.is_ascii_uppercase()) {
+        return err("必须包含大写字母".to_string());
+    }
+    if policy.require_lowercase && !password.chars().any(|c| c.is_ascii_lowercase()) {
+        return err("必须包含小写字母".to_string());
+    }
+    if policy.require_digit && !password.chars().any(|c| c.is_ascii_digit()) {
+        return err("必须包含数字".to_string());
+    }
+    if policy.require_special && password.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return err("必须包含特殊字符".to_string());
+    }
+    if policy.disallow_username && password.eq_ignore_ascii_case(username) {
+        return err("不能与用户名相同".to_string());
+    }
+    Ok(())
+}
+
+impl UserDomainImpl {
+    /// 转换为返回给前端的用户信息, 带上密码是否过期
+    pub(super) fn user_info(&self, user: User) -> UserInfoDto {
+        let password_expired =
+            user.is_password_expired(self.auth_config.password_policy.max_age_days);
+        let mut info: UserInfoDto = user.into();
+        info.password_expired = password_expired;
+        info
+    }
+
+    /// 登录成功后, 旧参数生成的密码哈希按当前参数重新生成
+    pub(super) async fn rehash_password_if_needed(&self, user: &User, password: &String) {
+        if !self.pwd_encrypt.needs_rehash(&user.password) {
+            return;
+        }
+        let result = match self.pwd_encrypt.encrypt(password) {
+            Ok(hash) => self.user_repo.update_password(user.id, hash, None).await,
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(_) => {
+                info!(target: MODEL_USER_DOMAIN, "升级密码哈希: username:{}", user.username)
+            }
+            Err(e) => {
+                warn!(target: MODEL_USER_DOMAIN, "升级密码哈希失败: username:{} err:{}", user.username, e)
+            }
+        }
+    }
+
+    pub(super) async fn do_change_password(
+        &self,
+        req: ChangePasswordDto,
+    ) -> Result<UserInfoDto, UserDomainError> {
+        let mut user = self
+            .user_repo
+            .get_by_id(req.user_id)
+            .await?
+            .ok_or_else(|| UserDomainError::UserNotFound(req.user_id.to_string()))?;
+        if !self.pwd_encrypt.verify(&req.old_password, &user.password) {
+            return Err(UserDomainError::AuthError("原密码错误".to_string()));
+        }
+
+        self.set_password(&mut user, &req.new_password).await?;
+        // 调用方随后为当前终端重新签发token
+        self.revoke_user_sessions(user.id).await?;
+        info!(target: MODEL_USER_DOMAIN, "修改密码: username:{}", user.username);
+        Ok(self.user_info(user))
+    }
+
+    pub(super) async fn do_forgot_password(
+        &self,
+        req: ForgotPasswordDto,
+    ) -> Result<(), UserDomainError> {
+        // 不向调用方透露账号是否存在, 失败只记录日志
+        let user = match self.user_repo.get_by_username(req.username.clone()).await {
+            Ok(Some(user)) => user,
+            Ok(None) => {
+                info!(target: MODEL_USER_DOMAIN, "找回密码, 账号不存在: username:{} ip:{}", req.username, req.ip);
+                return Ok(());
+            }
+            Err(e) => {
+                error!(target: MODEL_USER_DOMAIN, "找回密码, 查询账号失败: username:{} err:{}", req.username, e);
+                return Ok(());
+            }
+        };
+        let Some(email) = user.email.clone().filter(|e| !e.is_empty()) else {
+            info!(target: MODEL_USER_DOMAIN, "找回密码, 账号未绑定邮箱: username:{}", user.username);
+            return Ok(());
+        };
+        if let Err(e) = self.send_password_reset(&user, email).await {
+            error!(target: MODEL_USER_DOMAIN, "找回密码, 发送邮件失败: username:{} err:{}", user.username, e);
+        }
+        Ok(())
+    }
+
+    pub(super) async fn do_reset_password(
+        &self,
+        req: ResetPasswordDto,
+    ) -> Result<(), UserDomainError> {
+        let invalid = || UserDomainError::AuthError("重置链接无效或已过期".to_string());
+        let key = password_reset_key(&req.token);
+        // 新密码校验通过后再作废token, 密码不符合策略时链接仍可使用
+        let reset = self
+            .cache
+            .get_password_reset(key.clone())
+            .await?
+            .ok_or_else(invalid)?;
+        let mut user = self
+            .user_repo
+            .get_by_id(reset.user_id)
+            .await?
+            .ok_or_else(invalid)?;
+        self.check_new_password(&user, &req.new_password).await?;
+        // 并发使用同一链接时只有一次能成功
+        match self.cache.take_password_reset(key).await? {
+            Some(taken) if taken.user_id == user.id => {}
+            _ => return Err(invalid()),
+        }
+        self.save_password(&mut user, &req.new_password).await?;
+        // 重置密码后解除账号锁定
+        if user.login_fail_count > 0 || user.locked_until.is_some() {
+            self.user_repo.update_login_lock(user.id, 0, None).await?;
+        }
+        self.revoke_user_sessions(user.id).await?;
+        info!(target: MODEL_USER_DOMAIN, "重置密码: username:{}", user.username);
+        Ok(())
+    }
+
+    async fn send_password_reset(&self, user: &User, email: String) -> Result<(), UserDomainError> {
+        let config = &self.auth_config.password_reset;
+        let token = random_string(48);
+        self.cache
+            .set_password_reset(
+                password_reset_key(&token),
+                PasswordResetToken { user_id: user.id },
+                config.token_ttl,
+            )
+            .await?;
+        let text = format!(
+            "{}，您好：\n\n请在{}分钟内点击以下链接重置密码：\n{}\n\n如果不是您本人操作，请忽略此邮件。",
+            user.name.as_deref().unwrap_or(&user.username),
+            config.token_ttl / 60,
+            config.reset_url.replace("{token}", &token)
+        );
+        self.mailer
+            .send_mail(config.mail_from.clone(), email, text)
+            .await
+    }
+
+    // 按密码策略及历史密码校验后保存新密码
+    async fn set_password(
+        &self,
+        user: &mut User,
+        password: &String,
+    ) -> Result<(), UserDomainError> {
+        self.check_new_password(user, password).await?;
+        self.save_password(user, password).await
+    }
+
+    // 按密码策略及历史密码校验新密码
+    async fn check_new_password(
+        &self,
+        user: &User,
+        password: &String,
+    ) -> Result<(), UserDomainError> {
+        let policy = &self.auth_config.password_policy;
+        check_password_policy(policy, &user.username, password)?;
+        if policy.history > 0 {
+            let mut used = self
+                .user_repo
+                .get_password_history(user.id, policy.history)
+                .await?;
+            used.push(user.password.clone());
+            if used
+                .iter()
+                .any(|hash| self.pwd_encrypt.verify(password, hash))
+            {
+                return Err(UserDomainError::PasswordPolicyError(format!(
+                    "不能与最近{}次使用过的密码相同",
+                    policy.history
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    async fn save_password(
+        &self,
+        user: &mut User,
+        password: &String,
+    ) -> Result<(), UserDomainError> {
+        let hash = self.pwd_encrypt.encrypt(password)?;
+        let now = Local::now();
+        self.user_repo
+            .update_password(user.id, hash.clone(), Some(now))
+            .await?;
+        self.user_repo
+            .add_password_history(user.id, hash.clone())
+            .await?;
+        user.password = hash;
+        user.password_changed_at = Some(now);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_password_policy() {
+        let policy = PasswordPolicy::default();
+        assert!(check_password_policy(&policy, "admin", "abc12345").is_ok());
+        assert!(check_password_policy(&policy, "admin", "abc123").is_err());
+        assert!(check_password_policy(&policy, "admin", "abcdefgh").is_err());
+        assert!(check_password_policy(&policy, "admin", "12345678").is_err());
+        assert!(check_password_policy(&policy, "admin123", "Admin123").is_err());
+    }
+}
@@ -0,0 +1,157 @@
+use chrono::{Duration, Local};
+use tracing::{error, info};
+
+use crate::{
+    MODEL_USER_DOMAIN, UserDomainImpl,
+    api::dto::auth::{RegisterDto, ResendEmailVerifyDto},
+    commons::error::UserDomainError,
+    entity::{
+        register::EmailVerifyToken,
+        user::{USER_STATUS_ACTIVE, USER_STATUS_PENDING, User},
+    },
+    services::{password::check_password_policy, service::random_string},
+};
+
+fn email_verify_key(token: &str) -> String {
+    format!("email_verify:{}", token)
+}
+
+impl UserDomainImpl {
+    pub(super) async fn do_register(&self, req: RegisterDto) -> Result<(), UserDomainError> {
+        let config = &self.auth_config.register;
+        if !config.enable {
+            return Err(UserDomainError::RegisterDisabled);
+        }
+        self.verify_captcha(&req.client_id, &req.captcha).await?;
+        check_password_policy(
+            &self.auth_config.password_policy,
+            &req.username,
+            &req.password,
+        )?;
+        // 验证已过期的待激活账号不再占用用户名及邮箱
+        if let Some(user) = self.user_repo.get_by_username(req.username.clone()).await?
+            && !self.remove_stale_pending(&user).await?
+        {
+            return Err(UserDomainError::AuthError("用户名已存在".to_string()));
+        }
+        if let Some(user) = self.user_repo.get_by_email(req.email.clone()).await?
+            && !self.remove_stale_pending(&user).await?
+        {
+            return Err(UserDomainError::AuthError("邮箱已被使用".to_string()));
+        }
+
+        let password = self.pwd_encrypt.encrypt(&req.password)?;
+        let user = User {
+            role_id: config.default_role_id,
+            username: req.username.clone(),
+            name: Some(req.name.unwrap_or_else(|| req.username.clone())),
+            email: Some(req.email.clone()),
+            password: password.clone(),
+            status: Some(USER_STATUS_PENDING.to_string()),
+            remark: Some(format!("自助注册 ip:{}", req.ip)),
+            password_changed_at: Some(Local::now()),
+            ..Default::default()
+        };
+        let id = self.user_repo.create(user).await?;
+        self.user_repo.add_password_history(id, password).await?;
+        info!(target: MODEL_USER_DOMAIN, "自助注册: username:{} ip:{}", req.username, req.ip);
+
+        // 账号已创建, 验证邮件发送失败时可重新发送, 过期后可重新注册
+        if let Err(e) = self.send_email_verify(id, &req.username, req.email).await {
+            error!(target: MODEL_USER_DOMAIN, "发送注册验证邮件失败: username:{} err:{}", req.username, e);
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    pub(super) async fn do_verify_email(&self, token: String) -> Result<(), UserDomainError> {
+        let verify = self
+            .cache
+            .take_email_verify(email_verify_key(&token))
+            .await?
+            .ok_or_else(|| UserDomainError::AuthError("验证链接无效或已过期".to_string()))?;
+        let user = self
+            .user_repo
+            .get_by_id(verify.user_id)
+            .await?
+            .ok_or_else(|| UserDomainError::AuthError("验证链接无效或已过期".to_string()))?;
+        if user.status.as_deref() != Some(USER_STATUS_PENDING) {
+            return Ok(());
+        }
+        self.user_repo
+            .update_status(user.id, USER_STATUS_ACTIVE.to_string())
+            .await?;
+        info!(target: MODEL_USER_DOMAIN, "邮箱验证通过, 账号已激活: username:{}", user.username);
+        Ok(())
+    }
+
+    pub(super) async fn do_resend_email_verify(
+        &self,
+        req: ResendEmailVerifyDto,
+    ) -> Result<(), UserDomainError> {
+        if !self.auth_config.register.enable {
+            return Err(UserDomainError::RegisterDisabled);
+        }
+        // 不向调用方透露邮箱是否已注册, 失败只记录日志
+        let user = match self.user_repo.get_by_email(req.email.clone()).await {
+            Ok(Some(user)) if user.status.as_deref() == Some(USER_STATUS_PENDING) => user,
+            Ok(_) => {
+                info!(target: MODEL_USER_DOMAIN, "重发验证邮件, 无待激活账号: email:{} ip:{}", req.email, req.ip);
+                return Ok(());
+            }
+            Err(e) => {
+                error!(target: MODEL_USER_DOMAIN, "重发验证邮件, 查询账号失败: email:{} err:{}", req.email, e);
+                return Ok(());
+            }
+        };
+        if let Err(e) = self
+            .send_email_verify(user.id, &user.username, req.email)
+            .await
+        {
+            error!(target: MODEL_USER_DOMAIN, "重发验证邮件失败: username:{} err:{}", user.username, e);
+        }
+        Ok(())
+    }
+
+    // 删除验证已过期的待激活账号, 返回是否已删除
+    async fn remove_stale_pending(&self, user: &User) -> Result<bool, UserDomainError> {
+        if user.status.as_deref() != Some(USER_STATUS_PENDING) {
+            return Ok(false);
+        }
+        let ttl = Duration::seconds(self.auth_config.register.verify_ttl as i64);
+        let expired = user
+            .created_at
+            .is_none_or(|created_at| created_at + ttl < Local::now());
+        if expired {
+            self.user_repo.remove(user.id).await?;
+            info!(target: MODEL_USER_DOMAIN, "删除验证已过期的待激活账号: username:{}", user.username);
+        }
+        Ok(expired)
+    }
+
+    async fn send_email_verify(
+        &self,
+        user_id: i64,
+        username: &str,
+        email: String,
+    ) -> Result<(), UserDomainError> {
+        let config = &self.auth_config.register;
+        let token = random_string(48);
+        self.cache
+            .set_email_verify(
+                email_verify_key(&token),
+                EmailVerifyToken { user_id },
+                config.verify_ttl,
+            )
+            .await?;
+        let text = format!(
+            "{}，您好：\n\n感谢注册，请在{}小时内点击以下链接完成邮箱验证：\n{}\n\n如果不是您本人操作，请忽略此邮件。",
+            username,
+            config.verify_ttl / 3600,
+            config.verify_url.replace("{token}", &token)
+        );
+        self.mailer
+            .send_mail(config.mail_from.clone(), email, text)
+            .await
+    }
+}
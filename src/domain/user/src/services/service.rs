@@ -2,7 +2,11 @@ use crate::{
     MODEL_USER_DOMAIN, UserDomainImpl,
     api::{
         dto::{
-            auth::{AuthDto, AuthDtoWithCaptcha},
+            auth::{
+                ApiKeyPrincipal, AuthDto, AuthDtoWithCaptcha, ChangePasswordDto, CreateApiKeyDto,
+                ExternalAuthorizeDto, ExternalLoginDto, ForgotPasswordDto, Login2faDto,
+                LoginOutcome, RegisterDto, ResendEmailVerifyDto, ResetPasswordDto,
+            },
             user_info::UserInfoDto,
         },
         traits::UserDomainTrait,
@@ -10,39 +14,66 @@ use crate::{
     commons::error::UserDomainError,
     entity::{
         self,
+        api_key::{ApiKey, ApiKeyCreated},
         captcha::{CaptchaCacheInfo, CaptchaImage},
+        session::UserSession,
+        totp::TotpEnrollment,
+        user::{USER_STATUS_PENDING, User},
     },
+    services::{captcha, directory::PasswordCheck},
 };
 use async_trait::async_trait;
-use captcha_rust::Captcha;
-use tracing::{error, info};
+use chrono::{DateTime, Duration, Local};
+use rand::{Rng, distr::Alphanumeric};
+use tracing::{info, warn};
 
 fn get_cache_key(client_id: &str) -> String {
     format!("capcha:{}", client_id)
 }
 
+fn login_fail_user_key(username: &str) -> String {
+    format!("login_fail:user:{}", username)
+}
+
+fn login_fail_ip_key(ip: &str) -> String {
+    format!("login_fail:ip:{}", ip)
+}
+
+/// 生成随机字母数字字符串, 用于各类一次性token
+pub(super) fn random_string(len: usize) -> String {
+    rand::rng()
+        .sample_iter(&Alphanumeric)
+        .take(len)
+        .map(char::from)
+        .collect()
+}
+
 #[async_trait]
 impl UserDomainTrait for UserDomainImpl {
     async fn gen_captcha(
         &self,
         client_id: String,
+        ip: String,
         width: u32,
         height: u32,
     ) -> Result<CaptchaImage, UserDomainError> {
-        let captcha = Captcha::new(5, width, height);
+        let config = &self.auth_config.captcha;
+        self.check_captcha_rate(&client_id, &ip).await?;
+        let (text, answer) = captcha::gen_challenge(config);
         self.cache
             .set_captcha(
                 get_cache_key(&client_id),
                 CaptchaCacheInfo {
                     client_id: client_id.clone(),
-                    cache_text: captcha.text.clone(),
+                    cache_text: answer.clone(),
                 },
+                config.ttl,
             )
             .await?;
-        info!(target: MODEL_USER_DOMAIN,"生成验证码:{} -> {}", client_id, captcha.text);
+        info!(target: MODEL_USER_DOMAIN, "生成验证码:{}", client_id);
         Ok(CaptchaImage {
             client_id: client_id,
-            image: captcha.base_img,
+            image: captcha::render_png(&text, width, height, config.complexity)?,
         })
     }
 
@@ -67,61 +98,302 @@ impl UserDomainTrait for UserDomainImpl {
             .map_err(|e| UserDomainError::DbError(e.to_string()))
     }
 
-    async fn login(&self, auth_req: AuthDto) -> Result<UserInfoDto, UserDomainError> {
+    async fn login(&self, auth_req: AuthDto) -> Result<LoginOutcome, UserDomainError> {
         let user = self
-            .user_repo
-            .get_by_username(auth_req.username.clone())
+            .authenticate(&auth_req.username, &auth_req.password, &auth_req.ip, false)
             .await?;
-
-        if user.is_none() {
-            return Err(UserDomainError::AuthError(format!("用户不存在")));
-        }
-
-        let user = user.unwrap();
-        if !self.pwd_encrypt.verify(&auth_req.password, &user.password) {
-            error!(target: MODEL_USER_DOMAIN, "密码错误: password:{} except:{}", auth_req.password, user.password);
-            return Err(UserDomainError::AuthError(format!("密码错误")));
-        }
-        Ok(user.into())
+        self.login_outcome(user, &auth_req.ip).await
     }
 
     async fn login_with_captcha(
         &self,
         auth_req: AuthDtoWithCaptcha,
-    ) -> Result<UserInfoDto, UserDomainError> {
-        let captcha_info = self
-            .cache
-            .get_captcha(get_cache_key(&auth_req.client_id))
+    ) -> Result<LoginOutcome, UserDomainError> {
+        self.verify_captcha(&auth_req.client_id, &auth_req.captcha)
             .await?;
-        info!(target: MODEL_USER_DOMAIN,
-            "获取验证码:{}:{}",
-            auth_req.client_id.clone(),
-            captcha_info.cache_text
-        );
+        let user = self
+            .authenticate(&auth_req.username, &auth_req.password, &auth_req.ip, true)
+            .await?;
+        self.login_outcome(user, &auth_req.ip).await
+    }
+
+    async fn login_2fa(&self, auth_req: Login2faDto) -> Result<UserInfoDto, UserDomainError> {
+        self.do_login_2fa(auth_req).await
+    }
+
+    async fn change_password(
+        &self,
+        req: ChangePasswordDto,
+    ) -> Result<UserInfoDto, UserDomainError> {
+        self.do_change_password(req).await
+    }
+
+    async fn register(&self, req: RegisterDto) -> Result<(), UserDomainError> {
+        self.do_register(req).await
+    }
+
+    async fn verify_email(&self, token: String) -> Result<(), UserDomainError> {
+        self.do_verify_email(token).await
+    }
+
+    async fn resend_email_verify(&self, req: ResendEmailVerifyDto) -> Result<(), UserDomainError> {
+        self.do_resend_email_verify(req).await
+    }
+
+    async fn external_authorize(
+        &self,
+        provider: String,
+    ) -> Result<ExternalAuthorizeDto, UserDomainError> {
+        self.do_external_authorize(provider).await
+    }
+
+    async fn external_login(&self, req: ExternalLoginDto) -> Result<LoginOutcome, UserDomainError> {
+        self.do_external_login(req).await
+    }
+
+    async fn create_api_key(&self, req: CreateApiKeyDto) -> Result<ApiKeyCreated, UserDomainError> {
+        self.do_create_api_key(req).await
+    }
+
+    async fn list_api_keys(&self, user_id: i64) -> Result<Vec<ApiKey>, UserDomainError> {
+        self.user_repo.list_api_keys(user_id).await
+    }
+
+    async fn revoke_api_key(&self, user_id: i64, id: i64) -> Result<(), UserDomainError> {
+        self.do_revoke_api_key(user_id, id).await
+    }
+
+    async fn authenticate_api_key(&self, key: String) -> Result<ApiKeyPrincipal, UserDomainError> {
+        self.do_authenticate_api_key(key).await
+    }
+
+    async fn update_last_login(&self, user_id: i64, ip: String) -> Result<(), UserDomainError> {
+        self.user_repo
+            .update_last_login(user_id, ip, Local::now())
+            .await
+    }
+
+    async fn create_session(&self, session: UserSession) -> Result<(), UserDomainError> {
+        self.do_create_session(session).await
+    }
+
+    async fn list_sessions(
+        &self,
+        user_id: Option<i64>,
+        page: u64,
+        page_size: u64,
+    ) -> Result<Vec<UserSession>, UserDomainError> {
+        self.user_repo.list_sessions(user_id, page, page_size).await
+    }
 
-        if captcha_info.client_id != auth_req.client_id {
+    async fn revoke_session(
+        &self,
+        user_id: Option<i64>,
+        token_id: i64,
+    ) -> Result<(), UserDomainError> {
+        self.do_revoke_session(user_id, token_id).await
+    }
+
+    async fn check_session(&self, token_id: i64) -> Result<(), UserDomainError> {
+        self.do_check_session(token_id).await
+    }
+
+    async fn forgot_password(&self, req: ForgotPasswordDto) -> Result<(), UserDomainError> {
+        self.do_forgot_password(req).await
+    }
+
+    async fn reset_password(&self, req: ResetPasswordDto) -> Result<(), UserDomainError> {
+        self.do_reset_password(req).await
+    }
+
+    async fn totp_enroll(&self, user_id: i64) -> Result<TotpEnrollment, UserDomainError> {
+        self.do_totp_enroll(user_id).await
+    }
+
+    async fn totp_activate(
+        &self,
+        user_id: i64,
+        code: String,
+    ) -> Result<Vec<String>, UserDomainError> {
+        self.do_totp_activate(user_id, code).await
+    }
+
+    async fn totp_disable(&self, user_id: i64, code: String) -> Result<(), UserDomainError> {
+        self.do_totp_disable(user_id, code).await
+    }
+
+    async fn totp_regenerate_recovery_codes(
+        &self,
+        user_id: i64,
+        code: String,
+    ) -> Result<Vec<String>, UserDomainError> {
+        self.do_totp_regenerate_recovery_codes(user_id, code).await
+    }
+}
+
+impl UserDomainImpl {
+    // 按client_id和IP限制验证码生成频率
+    async fn check_captcha_rate(&self, client_id: &str, ip: &str) -> Result<(), UserDomainError> {
+        let config = &self.auth_config.captcha;
+        let limits = [
+            (
+                format!("captcha_rate:client:{}", client_id),
+                config.client_max_per_window,
+            ),
+            (format!("captcha_rate:ip:{}", ip), config.ip_max_per_window),
+        ];
+        for (key, max) in limits {
+            if max == 0 {
+                continue;
+            }
+            let count = self
+                .cache
+                .incr_captcha_count(key, config.rate_window_seconds)
+                .await?;
+            if count > max {
+                warn!(target: MODEL_USER_DOMAIN, "验证码生成过于频繁: client_id:{} ip:{}", client_id, ip);
+                return Err(UserDomainError::CaptchaRateLimited);
+            }
+        }
+        Ok(())
+    }
+
+    /// 校验验证码, 验证码使用一次后失效
+    pub(super) async fn verify_captcha(
+        &self,
+        client_id: &str,
+        captcha: &str,
+    ) -> Result<(), UserDomainError> {
+        let captcha_info = self.cache.get_captcha(get_cache_key(client_id)).await?;
+        info!(target: MODEL_USER_DOMAIN, "校验验证码:{}", client_id);
+
+        if captcha_info.client_id != client_id {
             return Err(UserDomainError::AuthError(format!(
                 "获取的验证码client_id与登录请求client_id不一致"
             )));
         }
 
-        if captcha_info.cache_text.to_lowercase() != auth_req.captcha.to_lowercase() {
+        if captcha_info.cache_text.to_lowercase() != captcha.to_lowercase() {
             return Err(UserDomainError::AuthError(format!("验证码错误")));
         }
-        let user = self
-            .user_repo
-            .get_by_username(auth_req.username.clone())
-            .await?;
+        Ok(())
+    }
+
+    // 校验用户名密码, 按账号和IP统计失败次数, 达到阈值后要求验证码或锁定账号
+    async fn authenticate(
+        &self,
+        username: &str,
+        password: &String,
+        ip: &str,
+        captcha_passed: bool,
+    ) -> Result<User, UserDomainError> {
+        let limit = &self.auth_config.login_limit;
+        if limit.enable {
+            let ip_failures = self.cache.get_login_failure(login_fail_ip_key(ip)).await?;
+            if ip_failures >= limit.ip_max_failures {
+                warn!(target: MODEL_USER_DOMAIN, "IP登录失败次数过多: ip:{} failures:{}", ip, ip_failures);
+                return Err(UserDomainError::TooManyAttempts);
+            }
+            let user_failures = self
+                .cache
+                .get_login_failure(login_fail_user_key(username))
+                .await?;
+            if !captcha_passed && limit.captcha_after > 0 && user_failures >= limit.captcha_after {
+                return Err(UserDomainError::CaptchaRequired);
+            }
+        }
+
+        let user = self.user_repo.get_by_username(username.to_string()).await?;
+        if let Some(user) = user.as_ref().filter(|u| u.is_locked()) {
+            return Err(UserDomainError::AccountLocked {
+                username: user.username.clone(),
+                until: format_locked_until(user.locked_until),
+            });
+        }
+
+        let (user, local) = match self.check_password(username, password, user).await? {
+            PasswordCheck::Passed { user, local } => (user, local),
+            PasswordCheck::Failed(None) => {
+                self.login_failed(username, ip, None).await?;
+                return Err(UserDomainError::AuthError(format!("用户不存在")));
+            }
+            PasswordCheck::Failed(Some(user)) => {
+                self.login_failed(username, ip, Some(&user)).await?;
+                return Err(UserDomainError::AuthError(format!("密码错误")));
+            }
+        };
+
+        if user.status.as_deref() == Some(USER_STATUS_PENDING) {
+            return Err(UserDomainError::AccountNotActivated);
+        }
+
+        if limit.enable {
+            self.cache
+                .clear_login_failure(login_fail_user_key(username))
+                .await?;
+        }
+        if user.login_fail_count > 0 || user.locked_until.is_some() {
+            self.user_repo.update_login_lock(user.id, 0, None).await?;
+        }
+        if local {
+            self.rehash_password_if_needed(&user, password).await;
+        }
+        Ok(user)
+    }
 
-        if user.is_none() {
-            return Err(UserDomainError::AuthError(format!("用户不存在")));
+    // 记录一次登录失败, 达到阈值时锁定账号, 并按失败次数递增响应延迟
+    pub(super) async fn login_failed(
+        &self,
+        username: &str,
+        ip: &str,
+        user: Option<&User>,
+    ) -> Result<(), UserDomainError> {
+        let limit = &self.auth_config.login_limit;
+        if !limit.enable {
+            return Ok(());
         }
+        self.cache
+            .incr_login_failure(login_fail_ip_key(ip), limit.window_seconds)
+            .await?;
+        let failures = self
+            .cache
+            .incr_login_failure(login_fail_user_key(username), limit.window_seconds)
+            .await?;
 
-        let user = user.unwrap();
+        if let Some(user) = user {
+            if limit.lock_after > 0 && failures >= limit.lock_after {
+                let locked_until =
+                    Some(Local::now() + Duration::seconds(limit.lock_seconds as i64));
+                self.user_repo
+                    .update_login_lock(user.id, failures as i32, locked_until)
+                    .await?;
+                self.cache
+                    .clear_login_failure(login_fail_user_key(username))
+                    .await?;
+                warn!(target: MODEL_USER_DOMAIN, "账号已锁定: username:{} ip:{} failures:{}", username, ip, failures);
+                return Err(UserDomainError::AccountLocked {
+                    username: username.to_string(),
+                    until: format_locked_until(locked_until),
+                });
+            }
+            self.user_repo
+                .update_login_lock(user.id, failures as i32, None)
+                .await?;
+        }
 
-        if !self.pwd_encrypt.verify(&auth_req.password, &user.password) {
-            return Err(UserDomainError::AuthError(format!("密码错误")));
+        let delay = limit
+            .delay_step_ms
+            .saturating_mul(failures)
+            .min(limit.delay_max_ms);
+        if delay > 0 {
+            tokio::time::sleep(std::time::Duration::from_millis(delay)).await;
         }
-        Ok(user.into())
+        Ok(())
     }
 }
+
+pub(super) fn format_locked_until(locked_until: Option<DateTime<Local>>) -> String {
+    locked_until
+        .map(|until| until.format("%Y-%m-%d %H:%M:%S").to_string())
+        .unwrap_or_default()
+}
@@ -0,0 +1,104 @@
+use tracing::{info, warn};
+
+use crate::{
+    MODEL_USER_DOMAIN, UserDomainImpl, commons::error::UserDomainError,
+    entity::session::UserSession,
+};
+
+fn session_revoked_key(token_id: i64) -> String {
+    format!("session:revoked:{}", token_id)
+}
+
+fn session_seen_key(token_id: i64) -> String {
+    format!("session:seen:{}", token_id)
+}
+
+impl UserDomainImpl {
+    pub(super) async fn do_create_session(
+        &self,
+        session: UserSession,
+    ) -> Result<(), UserDomainError> {
+        if !self.auth_config.session.enable {
+            return Ok(());
+        }
+        info!(target: MODEL_USER_DOMAIN, "创建会话: user_id:{} token_id:{} ip:{}", session.user_id, session.token_id, session.ip);
+        self.user_repo.create_session(session).await
+    }
+
+    pub(super) async fn do_revoke_session(
+        &self,
+        user_id: Option<i64>,
+        token_id: i64,
+    ) -> Result<(), UserDomainError> {
+        let session = self
+            .user_repo
+            .get_session(token_id)
+            .await?
+            .filter(|s| user_id.map(|id| id == s.user_id).unwrap_or(true))
+            .ok_or_else(|| UserDomainError::InvalidArgument("会话不存在".to_string()))?;
+        self.user_repo.revoke_session(token_id).await?;
+        // token在过期前都可能被使用, 吊销标记至少保留到token过期
+        self.cache
+            .set_session_revoked(
+                session_revoked_key(token_id),
+                self.auth_config.jwt.expiration.max(1) as u64,
+            )
+            .await?;
+        info!(target: MODEL_USER_DOMAIN, "吊销会话: user_id:{} token_id:{} operator:{:?}", session.user_id, token_id, user_id);
+        Ok(())
+    }
+
+    /// 吊销用户的全部会话, 修改或重置密码后其他终端持有的token随之失效
+    pub(super) async fn revoke_user_sessions(&self, user_id: i64) -> Result<(), UserDomainError> {
+        let token_ids = self.user_repo.revoke_user_sessions(user_id).await?;
+        for token_id in &token_ids {
+            self.cache
+                .set_session_revoked(
+                    session_revoked_key(*token_id),
+                    self.auth_config.jwt.expiration.max(1) as u64,
+                )
+                .await?;
+        }
+        info!(target: MODEL_USER_DOMAIN, "吊销用户全部会话: user_id:{} count:{}", user_id, token_ids.len());
+        Ok(())
+    }
+
+    /// 吊销标记在缓存中, 缓存丢失(如重启)后每个活跃窗口会从数据库重新同步一次
+    pub(super) async fn do_check_session(&self, token_id: i64) -> Result<(), UserDomainError> {
+        let config = &self.auth_config.session;
+        if !config.enable {
+            return Ok(());
+        }
+        if self
+            .cache
+            .is_session_revoked(session_revoked_key(token_id))
+            .await?
+        {
+            return Err(UserDomainError::SessionRevoked);
+        }
+        if !self
+            .cache
+            .mark_session_seen(session_seen_key(token_id), config.touch_interval.max(1))
+            .await?
+        {
+            return Ok(());
+        }
+        // 启用会话前签发的token没有会话记录, 允许继续使用
+        let Some(session) = self.user_repo.get_session(token_id).await? else {
+            return Ok(());
+        };
+        if session.revoked_at.is_some() {
+            self.cache
+                .set_session_revoked(
+                    session_revoked_key(token_id),
+                    self.auth_config.jwt.expiration.max(1) as u64,
+                )
+                .await?;
+            return Err(UserDomainError::SessionRevoked);
+        }
+        if let Err(e) = self.user_repo.touch_session(token_id).await {
+            warn!(target: MODEL_USER_DOMAIN, "更新会话活跃时间失败: token_id:{} err:{}", token_id, e);
+        }
+        Ok(())
+    }
+}
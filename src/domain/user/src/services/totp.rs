@@ -0,0 +1,271 @@
+use tracing::{info, warn};
+
+use crate::{
+    MODEL_USER_DOMAIN, UserDomainImpl,
+    api::dto::{
+        auth::{Login2faDto, LoginOutcome, LoginSubject},
+        user_info::UserInfoDto,
+    },
+    commons::error::UserDomainError,
+    entity::{
+        totp::{LoginChallenge, TotpEnrollment},
+        user::User,
+    },
+    services::service::random_string,
+};
+
+fn login_challenge_key(token: &str) -> String {
+    format!("login_challenge:{}", token)
+}
+
+// 恢复码格式: xxxxx-xxxxx
+fn gen_recovery_code() -> String {
+    let code = random_string(10).to_lowercase();
+    format!("{}-{}", &code[..5], &code[5..])
+}
+
+fn is_totp_code(code: &str) -> bool {
+    code.len() == 6 && code.chars().all(|c| c.is_ascii_digit())
+}
+
+impl UserDomainImpl {
+    /// 账号启用了TOTP时返回挑战token, 否则直接登录成功
+    pub(super) async fn login_outcome(
+        &self,
+        user: User,
+        ip: &str,
+    ) -> Result<LoginOutcome, UserDomainError> {
+        if !user.totp_enabled {
+            return Ok(LoginOutcome::Authenticated(Box::new(self.user_info(user))));
+        }
+        let ttl = self.auth_config.totp.challenge_ttl;
+        let challenge_token = random_string(32);
+        self.cache
+            .set_login_challenge(
+                login_challenge_key(&challenge_token),
+                LoginChallenge {
+                    user_id: user.id,
+                    ip: ip.to_string(),
+                },
+                ttl,
+            )
+            .await?;
+        info!(target: MODEL_USER_DOMAIN, "账号已启用两步验证, 等待动态码: username:{}", user.username);
+        Ok(LoginOutcome::TwoFactorRequired {
+            challenge_token,
+            expires_in: ttl,
+        })
+    }
+
+    pub(super) async fn do_login_2fa(
+        &self,
+        auth_req: Login2faDto,
+    ) -> Result<UserInfoDto, UserDomainError> {
+        let challenge = self
+            .cache
+            .take_login_challenge(login_challenge_key(&auth_req.challenge_token))
+            .await?
+            .ok_or_else(|| UserDomainError::TwoFactorError("登录已过期, 请重新登录".to_string()))?;
+        let user = self.totp_user(challenge.user_id).await?;
+        self.check_login_2fa(&user, &challenge, &auth_req)
+            .await
+            .map_err(|e| e.with_subject(LoginSubject::from(&user)))?;
+        Ok(self.user_info(user))
+    }
+
+    async fn check_login_2fa(
+        &self,
+        user: &User,
+        challenge: &LoginChallenge,
+        auth_req: &Login2faDto,
+    ) -> Result<(), UserDomainError> {
+        // 挑战token只能在完成密码校验的IP上使用, 挑战已作废, 需重新登录
+        if challenge.ip != auth_req.ip {
+            warn!(target: MODEL_USER_DOMAIN, "两步验证IP与登录IP不一致: username:{} login_ip:{} ip:{}", user.username, challenge.ip, auth_req.ip);
+            return Err(UserDomainError::TwoFactorError(
+                "登录环境发生变化, 请重新登录".to_string(),
+            ));
+        }
+        if !user.totp_enabled {
+            return Err(UserDomainError::TwoFactorError(
+                "未启用两步验证".to_string(),
+            ));
+        }
+        if !self.verify_second_factor(user, &auth_req.code).await? {
+            self.login_failed(&user.username, &auth_req.ip, Some(user))
+                .await?;
+            return Err(UserDomainError::TwoFactorError("动态码错误".to_string()));
+        }
+        Ok(())
+    }
+
+    pub(super) async fn do_totp_enroll(
+        &self,
+        user_id: i64,
+    ) -> Result<TotpEnrollment, UserDomainError> {
+        // 未配置加密口令时密钥等同明文保存, 不允许绑定
+        if self.auth_config.totp.encrypt_key.trim().is_empty() {
+            return Err(UserDomainError::TwoFactorError(
+                "未配置TOTP加密口令(auth.totp.encrypt_key), 暂不能启用两步验证".to_string(),
+            ));
+        }
+        let user = self.totp_user(user_id).await?;
+        if user.totp_enabled {
+            return Err(UserDomainError::TwoFactorError(
+                "已启用两步验证, 请先关闭".to_string(),
+            ));
+        }
+        let secret = self.totp.generate_secret();
+        let otpauth_uri =
+            self.totp
+                .otpauth_uri(&secret, &user.username, &self.auth_config.totp.issuer);
+        let qr_image = self.totp.qr_image(&otpauth_uri)?;
+        self.user_repo
+            .update_totp(
+                user.id,
+                Some(self.secret_encrypt.encrypt(&secret)?),
+                false,
+                None,
+            )
+            .await?;
+        Ok(TotpEnrollment {
+            secret,
+            otpauth_uri,
+            qr_image,
+        })
+    }
+
+    pub(super) async fn do_totp_activate(
+        &self,
+        user_id: i64,
+        code: String,
+    ) -> Result<Vec<String>, UserDomainError> {
+        let user = self.totp_user(user_id).await?;
+        if user.totp_enabled {
+            return Err(UserDomainError::TwoFactorError(
+                "已启用两步验证".to_string(),
+            ));
+        }
+        self.verify_totp(&user, &code).await?;
+        let codes = self.save_recovery_codes(&user).await?;
+        info!(target: MODEL_USER_DOMAIN, "启用两步验证: username:{}", user.username);
+        Ok(codes)
+    }
+
+    pub(super) async fn do_totp_disable(
+        &self,
+        user_id: i64,
+        code: String,
+    ) -> Result<(), UserDomainError> {
+        let user = self.totp_user(user_id).await?;
+        if !user.totp_enabled {
+            return Err(UserDomainError::TwoFactorError(
+                "未启用两步验证".to_string(),
+            ));
+        }
+        if !self.verify_second_factor(&user, &code).await? {
+            return Err(UserDomainError::TwoFactorError("动态码错误".to_string()));
+        }
+        self.user_repo
+            .update_totp(user.id, None, false, None)
+            .await?;
+        info!(target: MODEL_USER_DOMAIN, "关闭两步验证: username:{}", user.username);
+        Ok(())
+    }
+
+    pub(super) async fn do_totp_regenerate_recovery_codes(
+        &self,
+        user_id: i64,
+        code: String,
+    ) -> Result<Vec<String>, UserDomainError> {
+        let user = self.totp_user(user_id).await?;
+        if !user.totp_enabled {
+            return Err(UserDomainError::TwoFactorError(
+                "未启用两步验证".to_string(),
+            ));
+        }
+        self.verify_totp(&user, &code).await?;
+        self.save_recovery_codes(&user).await
+    }
+
+    async fn totp_user(&self, user_id: i64) -> Result<User, UserDomainError> {
+        self.user_repo
+            .get_by_id(user_id)
+            .await?
+            .ok_or_else(|| UserDomainError::UserNotFound(user_id.to_string()))
+    }
+
+    // 只接受TOTP动态码, 同一时间窗口的动态码只能使用一次
+    async fn verify_totp(&self, user: &User, code: &str) -> Result<(), UserDomainError> {
+        let cipher = user
+            .totp_secret
+            .as_ref()
+            .ok_or_else(|| UserDomainError::TwoFactorError("请先绑定两步验证".to_string()))?;
+        let secret = self.secret_encrypt.decrypt(cipher)?;
+        let step = is_totp_code(code)
+            .then(|| self.totp.verify(&secret, code, self.auth_config.totp.skew))
+            .flatten()
+            .ok_or_else(|| UserDomainError::TwoFactorError("动态码错误".to_string()))?;
+        if !self.user_repo.consume_totp_step(user.id, step).await? {
+            info!(target: MODEL_USER_DOMAIN, "动态码重复使用: username:{}", user.username);
+            return Err(UserDomainError::TwoFactorError(
+                "动态码已使用, 请等待下一个动态码".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    // 接受TOTP动态码或恢复码, 恢复码使用后作废
+    async fn verify_second_factor(&self, user: &User, code: &str) -> Result<bool, UserDomainError> {
+        let code = code.trim();
+        if is_totp_code(code) {
+            return Ok(self.verify_totp(user, code).await.is_ok());
+        }
+
+        let mut hashes: Vec<String> = user
+            .totp_recovery_codes
+            .as_deref()
+            .map(serde_json::from_str)
+            .transpose()
+            .map_err(|e| UserDomainError::InternalError(e.to_string()))?
+            .unwrap_or_default();
+        let code = code.to_lowercase();
+        let Some(index) = hashes
+            .iter()
+            .position(|hash| self.pwd_encrypt.verify(&code, hash))
+        else {
+            return Ok(false);
+        };
+        hashes.remove(index);
+        self.user_repo
+            .update_totp(
+                user.id,
+                user.totp_secret.clone(),
+                true,
+                Some(serde_json::to_string(&hashes).unwrap_or_default()),
+            )
+            .await?;
+        info!(target: MODEL_USER_DOMAIN, "使用恢复码: username:{} 剩余:{}", user.username, hashes.len());
+        Ok(true)
+    }
+
+    // 生成新的恢复码并启用TOTP, 数据库只保存哈希
+    async fn save_recovery_codes(&self, user: &User) -> Result<Vec<String>, UserDomainError> {
+        let codes: Vec<String> = (0..self.auth_config.totp.recovery_codes)
+            .map(|_| gen_recovery_code())
+            .collect();
+        let hashes = codes
+            .iter()
+            .map(|code| self.pwd_encrypt.encrypt(code))
+            .collect::<Result<Vec<_>, _>>()?;
+        self.user_repo
+            .update_totp(
+                user.id,
+                user.totp_secret.clone(),
+                true,
+                Some(serde_json::to_string(&hashes).unwrap_or_default()),
+            )
+            .await?;
+        Ok(codes)
+    }
+}
@@ -0,0 +1,210 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use commonx::{config::config::LdapConfig, web_info};
+use ldap3::{
+    Ldap, LdapConnAsync, LdapConnSettings, LdapError, Scope, SearchEntry, dn_escape, ldap_escape,
+};
+use user_domain::{
+    commons::error::UserDomainError,
+    entity::identity::{DirectoryAuth, DirectoryUser},
+    repository::auth_provider::DirectoryProviderTrait,
+};
+
+/// LDAP result code: invalidCredentials
+const RC_INVALID_CREDENTIALS: u32 = 49;
+
+/// LDAP/AD 认证, 以用户身份bind校验密码
+pub struct LdapProviderImpl {
+    config: LdapConfig,
+}
+
+fn ldap_error(e: LdapError) -> UserDomainError {
+    UserDomainError::AuthError(format!("LDAP服务异常: {}", e))
+}
+
+fn first_attr(entry: &SearchEntry, attr: &str) -> Option<String> {
+    entry
+        .attrs
+        .get(attr)
+        .and_then(|values| values.first())
+        .filter(|v| !v.is_empty())
+        .cloned()
+}
+
+impl LdapProviderImpl {
+    pub fn new(config: LdapConfig) -> Self {
+        Self { config }
+    }
+
+    async fn connect(&self) -> Result<Ldap, UserDomainError> {
+        let settings = LdapConnSettings::new()
+            .set_conn_timeout(Duration::from_secs(self.config.timeout))
+            .set_starttls(self.config.starttls);
+        let (conn, ldap) = LdapConnAsync::with_settings(settings, &self.config.url)
+            .await
+            .map_err(ldap_error)?;
+        ldap3::drive!(conn);
+        Ok(ldap)
+    }
+
+    fn attrs(&self) -> Vec<&str> {
+        vec![
+            self.config.attr_name.as_str(),
+            self.config.attr_email.as_str(),
+            self.config.attr_phone.as_str(),
+            self.config.attr_groups.as_str(),
+        ]
+    }
+
+    // 使用服务账号按 user_filter 查询用户
+    async fn find_user(
+        &self,
+        ldap: &mut Ldap,
+        username: &str,
+    ) -> Result<Option<SearchEntry>, UserDomainError> {
+        let bind_dn = self.config.bind_dn.as_deref().unwrap_or_default();
+        let bind_password = self.config.bind_password.as_deref().unwrap_or_default();
+        ldap.simple_bind(bind_dn, bind_password)
+            .await
+            .and_then(|r| r.success())
+            .map_err(ldap_error)?;
+        let filter = self
+            .config
+            .user_filter
+            .replace("{username}", &ldap_escape(username));
+        let (entries, _) = ldap
+            .search(&self.config.base_dn, Scope::Subtree, &filter, self.attrs())
+            .await
+            .and_then(|r| r.success())
+            .map_err(ldap_error)?;
+        if entries.len() > 1 {
+            return Err(UserDomainError::AuthError(format!(
+                "LDAP中存在多个匹配的账号: {}",
+                username
+            )));
+        }
+        Ok(entries.into_iter().next().map(SearchEntry::construct))
+    }
+
+    // 以用户DN bind后读取自身条目
+    async fn read_entry(&self, ldap: &mut Ldap, dn: &str) -> Result<SearchEntry, UserDomainError> {
+        let (entries, _) = ldap
+            .search(dn, Scope::Base, "(objectClass=*)", self.attrs())
+            .await
+            .and_then(|r| r.success())
+            .map_err(ldap_error)?;
+        entries
+            .into_iter()
+            .next()
+            .map(SearchEntry::construct)
+            .ok_or_else(|| UserDomainError::AuthError(format!("读取LDAP用户失败: {}", dn)))
+    }
+
+    async fn do_authenticate(
+        &self,
+        ldap: &mut Ldap,
+        username: &str,
+        password: &str,
+    ) -> Result<DirectoryAuth, UserDomainError> {
+        let found = if self.config.bind_dn.is_some() {
+            match self.find_user(ldap, username).await? {
+                Some(entry) => Some(entry),
+                None => return Ok(DirectoryAuth::NotFound),
+            }
+        } else {
+            None
+        };
+        let dn = match (&found, &self.config.user_dn_template) {
+            (Some(entry), _) => entry.dn.clone(),
+            (None, Some(template)) => template.replace("{username}", &dn_escape(username)),
+            (None, None) => {
+                return Err(UserDomainError::AuthError(
+                    "LDAP需要配置bind_dn或user_dn_template".to_string(),
+                ));
+            }
+        };
+
+        match ldap
+            .simple_bind(&dn, password)
+            .await
+            .and_then(|r| r.success())
+        {
+            Ok(_) => {}
+            Err(LdapError::LdapResult { result }) if result.rc == RC_INVALID_CREDENTIALS => {
+                return Ok(DirectoryAuth::InvalidCredentials);
+            }
+            Err(e) => return Err(ldap_error(e)),
+        }
+
+        let entry = match found {
+            Some(entry) => entry,
+            None => self.read_entry(ldap, &dn).await?,
+        };
+        web_info!("LDAP认证通过: {} -> {}", username, entry.dn);
+        Ok(DirectoryAuth::Authenticated(DirectoryUser {
+            name: first_attr(&entry, &self.config.attr_name),
+            email: first_attr(&entry, &self.config.attr_email),
+            phone: first_attr(&entry, &self.config.attr_phone),
+            groups: entry
+                .attrs
+                .get(&self.config.attr_groups)
+                .cloned()
+                .unwrap_or_default(),
+            dn: entry.dn,
+        }))
+    }
+}
+
+#[async_trait]
+impl DirectoryProviderTrait for LdapProviderImpl {
+    async fn authenticate(
+        &self,
+        username: &str,
+        password: &str,
+    ) -> Result<DirectoryAuth, UserDomainError> {
+        let mut ldap = self.connect().await?;
+        let result = self.do_authenticate(&mut ldap, username, password).await;
+        let _ = ldap.unbind().await;
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 需要本地 OpenLDAP, 例如:
+    // docker run -p 389:389 -e LDAP_ORGANISATION=example -e LDAP_DOMAIN=example.org \
+    //     -e LDAP_ADMIN_PASSWORD=admin osixia/openldap
+    // 然后设置 LDAP_TEST_URL=ldap://127.0.0.1:389 运行 cargo test -- --ignored
+    #[tokio::test]
+    #[ignore]
+    async fn bind_against_local_openldap() {
+        let url = std::env::var("LDAP_TEST_URL").unwrap_or("ldap://127.0.0.1:389".to_string());
+        let provider = LdapProviderImpl::new(LdapConfig {
+            enable: true,
+            url,
+            bind_dn: Some("cn=admin,dc=example,dc=org".to_string()),
+            bind_password: Some("admin".to_string()),
+            base_dn: "dc=example,dc=org".to_string(),
+            user_filter: "(cn={username})".to_string(),
+            ..Default::default()
+        });
+
+        let auth = provider.authenticate("admin", "admin").await.unwrap();
+        let DirectoryAuth::Authenticated(user) = auth else {
+            panic!("expected authenticated, got {:?}", auth);
+        };
+        assert_eq!(user.dn, "cn=admin,dc=example,dc=org");
+
+        assert!(matches!(
+            provider.authenticate("admin", "wrong").await.unwrap(),
+            DirectoryAuth::InvalidCredentials
+        ));
+        assert!(matches!(
+            provider.authenticate("nobody", "admin").await.unwrap(),
+            DirectoryAuth::NotFound
+        ));
+    }
+}
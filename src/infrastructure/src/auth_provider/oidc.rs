@@ -0,0 +1,381 @@
+use std::{str::FromStr, time::Duration};
+
+use async_trait::async_trait;
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use commonx::{config::config::OidcProvider, web_info};
+use jsonwebtoken::{Algorithm, DecodingKey, Validation, decode, decode_header, jwk::JwkSet};
+use reqwest::{Client, Url};
+use serde::{Deserialize, de::DeserializeOwned};
+use sha2::{Digest, Sha256};
+use tokio::sync::{OnceCell, RwLock};
+use user_domain::{
+    commons::error::UserDomainError,
+    entity::identity::{AuthorizeRequest, ExternalIdentity, ProvisionPolicy},
+    repository::auth_provider::AuthProviderTrait,
+};
+
+/// `.well-known/openid-configuration` 中用到的字段
+#[derive(Debug, Clone, Deserialize)]
+struct ProviderMetadata {
+    issuer: String,
+    authorization_endpoint: String,
+    token_endpoint: String,
+    jwks_uri: Option<String>,
+    #[serde(default)]
+    id_token_signing_alg_values_supported: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    id_token: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct IdTokenClaims {
+    sub: String,
+    nonce: Option<String>,
+    email: Option<String>,
+    #[serde(default)]
+    email_verified: bool,
+    name: Option<String>,
+    preferred_username: Option<String>,
+}
+
+/// OIDC 授权码 + PKCE 登录, 端点信息通过 discovery 获取并缓存
+pub struct OidcProviderImpl {
+    config: OidcProvider,
+    http: Client,
+    metadata: OnceCell<ProviderMetadata>,
+    jwks: RwLock<Option<JwkSet>>,
+}
+
+impl OidcProviderImpl {
+    pub fn new(config: OidcProvider, timeout: u64) -> Self {
+        let http = Client::builder()
+            .timeout(Duration::from_secs(timeout))
+            .build()
+            .unwrap_or_default();
+        Self {
+            config,
+            http,
+            metadata: OnceCell::new(),
+            jwks: RwLock::new(None),
+        }
+    }
+
+    async fn get_json<T: DeserializeOwned>(&self, url: &str) -> Result<T, UserDomainError> {
+        self.http
+            .get(url)
+            .send()
+            .await
+            .and_then(|resp| resp.error_for_status())
+            .map_err(|e| UserDomainError::AuthError(format!("请求认证服务失败: {}", e)))?
+            .json::<T>()
+            .await
+            .map_err(|e| UserDomainError::AuthError(format!("认证服务响应格式错误: {}", e)))
+    }
+
+    async fn metadata(&self) -> Result<&ProviderMetadata, UserDomainError> {
+        self.metadata
+            .get_or_try_init(|| async {
+                let issuer = self.config.issuer.trim_end_matches('/');
+                let url = format!("{}/.well-known/openid-configuration", issuer);
+                let metadata: ProviderMetadata = self.get_json(&url).await?;
+                if metadata.issuer.trim_end_matches('/') != issuer {
+                    return Err(UserDomainError::AuthError(format!(
+                        "认证服务issuer不一致: {}",
+                        metadata.issuer
+                    )));
+                }
+                web_info!("OIDC discovery: {} -> {}", self.config.name, url);
+                Ok(metadata)
+            })
+            .await
+    }
+
+    // 按kid查找签名公钥, 找不到时重新拉取JWKS以支持提供方轮换密钥
+    async fn jwk_key(&self, kid: Option<&str>) -> Result<DecodingKey, UserDomainError> {
+        for refresh in [false, true] {
+            if refresh || self.jwks.read().await.is_none() {
+                let jwks_uri =
+                    self.metadata().await?.jwks_uri.clone().ok_or_else(|| {
+                        UserDomainError::AuthError("认证服务未提供jwks_uri".into())
+                    })?;
+                let jwks: JwkSet = self.get_json(&jwks_uri).await?;
+                *self.jwks.write().await = Some(jwks);
+            }
+            let guard = self.jwks.read().await;
+            let jwk = guard.as_ref().and_then(|set| match kid {
+                Some(kid) => set.find(kid),
+                None => set.keys.first(),
+            });
+            if let Some(jwk) = jwk {
+                return DecodingKey::from_jwk(jwk)
+                    .map_err(|e| UserDomainError::AuthError(format!("无效的签名公钥: {}", e)));
+            }
+        }
+        Err(UserDomainError::AuthError("找不到ID Token签名公钥".into()))
+    }
+
+    // 优先使用配置的算法, 其次为discovery声明的非对称算法, 都没有时为RS256
+    async fn allowed_algs(&self) -> Result<Vec<Algorithm>, UserDomainError> {
+        if !self.config.id_token_signing_algs.is_empty() {
+            return self
+                .config
+                .id_token_signing_algs
+                .iter()
+                .map(|alg| {
+                    Algorithm::from_str(alg).map_err(|_| {
+                        UserDomainError::AuthError(format!("不支持的ID Token签名算法: {}", alg))
+                    })
+                })
+                .collect();
+        }
+        let algs: Vec<Algorithm> = self
+            .metadata()
+            .await?
+            .id_token_signing_alg_values_supported
+            .iter()
+            .filter_map(|alg| Algorithm::from_str(alg).ok())
+            .filter(|alg| !is_hmac(*alg))
+            .collect();
+        Ok(if algs.is_empty() {
+            vec![Algorithm::RS256]
+        } else {
+            algs
+        })
+    }
+
+    async fn verify_id_token(
+        &self,
+        id_token: &str,
+        nonce: &str,
+    ) -> Result<IdTokenClaims, UserDomainError> {
+        let header = decode_header(id_token)
+            .map_err(|e| UserDomainError::AuthError(format!("无效的ID Token: {}", e)))?;
+        if !self.allowed_algs().await?.contains(&header.alg) {
+            return Err(UserDomainError::AuthError(format!(
+                "ID Token签名算法不被允许: {:?}",
+                header.alg
+            )));
+        }
+        let key = match header.alg {
+            // 对称算法使用 client_secret 签名
+            alg if is_hmac(alg) => {
+                let secret = self.config.client_secret.as_deref().ok_or_else(|| {
+                    UserDomainError::AuthError("HS256签名的ID Token需要配置client_secret".into())
+                })?;
+                DecodingKey::from_secret(secret.as_bytes())
+            }
+            _ => self.jwk_key(header.kid.as_deref()).await?,
+        };
+        let mut validation = Validation::new(header.alg);
+        validation.set_audience(&[&self.config.client_id]);
+        validation.set_issuer(&[&self.metadata().await?.issuer]);
+        let claims = decode::<IdTokenClaims>(id_token, &key, &validation)
+            .map_err(|e| UserDomainError::AuthError(format!("ID Token校验失败: {}", e)))?
+            .claims;
+        if claims.nonce.as_deref() != Some(nonce) {
+            return Err(UserDomainError::AuthError("ID Token nonce不匹配".into()));
+        }
+        Ok(claims)
+    }
+}
+
+fn is_hmac(alg: Algorithm) -> bool {
+    matches!(alg, Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512)
+}
+
+fn code_challenge(code_verifier: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(code_verifier.as_bytes()))
+}
+
+#[async_trait]
+impl AuthProviderTrait for OidcProviderImpl {
+    fn name(&self) -> &str {
+        &self.config.name
+    }
+
+    fn provision_policy(&self) -> ProvisionPolicy {
+        ProvisionPolicy {
+            auto_provision: self.config.auto_provision,
+            link_by_email: self.config.link_by_email,
+            default_role_id: self.config.default_role_id,
+        }
+    }
+
+    fn skip_local_2fa(&self) -> bool {
+        self.config.skip_local_2fa
+    }
+
+    async fn authorize_url(&self, request: &AuthorizeRequest) -> Result<String, UserDomainError> {
+        let metadata = self.metadata().await?;
+        let mut url = Url::parse(&metadata.authorization_endpoint)
+            .map_err(|e| UserDomainError::AuthError(format!("无效的授权地址: {}", e)))?;
+        url.query_pairs_mut()
+            .append_pair("response_type", "code")
+            .append_pair("client_id", &self.config.client_id)
+            .append_pair("redirect_uri", &self.config.redirect_uri)
+            .append_pair("scope", &self.config.scopes.join(" "))
+            .append_pair("state", &request.state)
+            .append_pair("nonce", &request.nonce)
+            .append_pair("code_challenge", &code_challenge(&request.code_verifier))
+            .append_pair("code_challenge_method", "S256");
+        Ok(url.to_string())
+    }
+
+    async fn exchange_code(
+        &self,
+        code: &str,
+        request: &AuthorizeRequest,
+    ) -> Result<ExternalIdentity, UserDomainError> {
+        let metadata = self.metadata().await?;
+        let mut form = vec![
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", self.config.redirect_uri.as_str()),
+            ("client_id", self.config.client_id.as_str()),
+            ("code_verifier", request.code_verifier.as_str()),
+        ];
+        if let Some(secret) = self.config.client_secret.as_deref() {
+            form.push(("client_secret", secret));
+        }
+        let token: TokenResponse = self
+            .http
+            .post(&metadata.token_endpoint)
+            .form(&form)
+            .send()
+            .await
+            .and_then(|resp| resp.error_for_status())
+            .map_err(|e| UserDomainError::AuthError(format!("授权码换取token失败: {}", e)))?
+            .json()
+            .await
+            .map_err(|e| UserDomainError::AuthError(format!("认证服务响应格式错误: {}", e)))?;
+
+        let claims = self
+            .verify_id_token(&token.id_token, &request.nonce)
+            .await?;
+        Ok(ExternalIdentity {
+            provider: self.config.name.clone(),
+            subject: claims.sub,
+            email: claims.email,
+            email_verified: claims.email_verified,
+            name: claims.name,
+            preferred_username: claims.preferred_username,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use axum::{Form, Json, Router, extract::State, routing::get, routing::post};
+    use jsonwebtoken::{EncodingKey, Header, encode};
+    use serde_json::{Value, json};
+
+    use super::*;
+
+    const CLIENT_ID: &str = "vela";
+    const CLIENT_SECRET: &str = "mock-secret";
+    const NONCE: &str = "test-nonce";
+
+    // 本地模拟的 OIDC issuer, 校验 PKCE 后签发 HS256 ID Token
+    async fn mock_issuer(challenge: String) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let issuer = format!("http://{}", listener.local_addr().unwrap());
+        let discovery = json!({
+            "issuer": issuer,
+            "authorization_endpoint": format!("{}/authorize", issuer),
+            "token_endpoint": format!("{}/token", issuer),
+        });
+        let app = Router::new()
+            .route(
+                "/.well-known/openid-configuration",
+                get(move || async move { Json(discovery) }),
+            )
+            .route("/token", post(token))
+            .with_state((issuer.clone(), challenge));
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        issuer
+    }
+
+    async fn token(
+        State((issuer, challenge)): State<(String, String)>,
+        Form(form): Form<HashMap<String, String>>,
+    ) -> Json<Value> {
+        assert_eq!(form["grant_type"], "authorization_code");
+        assert_eq!(form["code"], "auth-code");
+        assert_eq!(code_challenge(&form["code_verifier"]), challenge);
+        let claims = json!({
+            "iss": issuer,
+            "aud": CLIENT_ID,
+            "sub": "user-1",
+            "exp": chrono::Local::now().timestamp() + 300,
+            "nonce": NONCE,
+            "email": "alice@example.com",
+            "email_verified": true,
+            "preferred_username": "alice",
+        });
+        let id_token = encode(
+            &Header::new(Algorithm::HS256),
+            &claims,
+            &EncodingKey::from_secret(CLIENT_SECRET.as_bytes()),
+        )
+        .unwrap();
+        Json(json!({ "id_token": id_token, "access_token": "x", "token_type": "Bearer" }))
+    }
+
+    #[tokio::test]
+    async fn authorization_code_with_pkce() {
+        let request = AuthorizeRequest {
+            provider: "sso".to_string(),
+            state: "test-state".to_string(),
+            nonce: NONCE.to_string(),
+            code_verifier: "v".repeat(64),
+        };
+        let issuer = mock_issuer(code_challenge(&request.code_verifier)).await;
+        let config = OidcProvider {
+            name: "sso".to_string(),
+            issuer,
+            client_id: CLIENT_ID.to_string(),
+            client_secret: Some(CLIENT_SECRET.to_string()),
+            redirect_uri: "http://localhost/callback".to_string(),
+            scopes: vec!["openid".to_string()],
+            auto_provision: false,
+            link_by_email: false,
+            default_role_id: 0,
+            id_token_signing_algs: vec!["HS256".to_string()],
+            skip_local_2fa: false,
+        };
+        let provider = OidcProviderImpl::new(config.clone(), 5);
+
+        let url = provider.authorize_url(&request).await.unwrap();
+        assert!(url.contains("code_challenge_method=S256"));
+        assert!(url.contains("state=test-state"));
+
+        let identity = provider.exchange_code("auth-code", &request).await.unwrap();
+        assert_eq!(identity.subject, "user-1");
+        assert_eq!(identity.email.as_deref(), Some("alice@example.com"));
+        assert!(identity.email_verified);
+
+        let mut wrong_nonce = request.clone();
+        wrong_nonce.nonce = "other".to_string();
+        assert!(
+            provider
+                .exchange_code("auth-code", &wrong_nonce)
+                .await
+                .is_err()
+        );
+
+        // 未显式配置HS256时, 使用client_secret签名的ID Token不被接受
+        let provider = OidcProviderImpl::new(
+            OidcProvider {
+                id_token_signing_algs: vec![],
+                ..config
+            },
+            5,
+        );
+        assert!(provider.exchange_code("auth-code", &request).await.is_err());
+    }
+}
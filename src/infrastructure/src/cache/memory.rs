@@ -3,7 +3,7 @@ use std::{
     time::Duration,
 };
 
-use commonx::error::AppError;
+use commonx::{error::AppError, tenant::tenant_key};
 use dashmap::DashMap;
 use serde::{Deserialize, Serialize};
 use tokio::time::interval;
@@ -91,6 +91,11 @@ impl MemoryCache {
             Ok(format!("{}:{}", namespace, key))
         }
     }
+
+    /// 键值类数据按当前租户隔离, 队列等集合类数据为全局共享, 不经过该方法
+    async fn get_tenant_key(&self, key: &str) -> Result<String, AppError> {
+        self.get_namespaced_key(&tenant_key(key)).await
+    }
 }
 
 #[async_trait::async_trait]
@@ -104,8 +109,9 @@ impl CacheTrait for MemoryCache {
     }
 
     async fn set_string_ex(&self, k: &str, value: &str, ttl: i32) -> Result<bool, AppError> {
+        let key = self.get_tenant_key(k).await?;
         let item = MemoryCacheItem::new(value.to_string(), Some(ttl as usize));
-        self.storage.insert(k.to_string(), item);
+        self.storage.insert(key, item);
         Ok(true)
     }
 
@@ -129,21 +135,21 @@ impl CacheTrait for MemoryCache {
     }
 
     async fn get_string(&self, k: &str) -> Result<String, AppError> {
-        let key = self.get_namespaced_key(k).await?;
+        let key = self.get_tenant_key(k).await?;
         if let Some(item) = self.storage.get(&key) {
             if item.is_expired() {
                 self.storage.remove(&key);
-                Err("Key not found or expired".into())
+                Err(AppError::CacheNotFoundError(format!("数据不存在: {}", key)))
             } else {
                 Ok(item.value.clone())
             }
         } else {
-            Err("Key not found or expired".into())
+            Err(AppError::CacheNotFoundError(format!("数据不存在: {}", key)))
         }
     }
 
     async fn remove(&self, k: &str) -> Result<usize, AppError> {
-        let key = self.get_namespaced_key(k).await?;
+        let key = self.get_tenant_key(k).await?;
         let mut removed = 0;
         if self.storage.remove(&key).is_some() {
             removed += 1;
@@ -185,8 +191,10 @@ impl CacheTrait for MemoryCache {
     where
         V: ToString + Send + Sync,
     {
-        let namespace_key = self.get_namespaced_key(&key).await?;
+        let namespace_key = self.get_tenant_key(key).await?;
 
+        // 已过期但尚未被清理的值视为不存在
+        self.storage.remove_if(&namespace_key, |_, item| item.is_expired());
         if self.storage.contains_key(&namespace_key)
             || self.lists.contains_key(&namespace_key)
             || self.sets.contains_key(&namespace_key)
@@ -266,6 +274,20 @@ impl CacheTrait for MemoryCache {
             Ok(false)
         }
     }
+
+    async fn incr_ex(&self, k: &str, ttl: usize) -> Result<i64, AppError> {
+        let key = self.get_tenant_key(k).await?;
+        let mut entry = self
+            .storage
+            .entry(key)
+            .or_insert_with(|| MemoryCacheItem::new("0".to_string(), Some(ttl)));
+        if entry.is_expired() {
+            *entry = MemoryCacheItem::new("0".to_string(), Some(ttl));
+        }
+        let value = entry.value.parse::<i64>().unwrap_or_default() + 1;
+        entry.value = value.to_string();
+        Ok(value)
+    }
 }
 
 impl Clone for MemoryCache {
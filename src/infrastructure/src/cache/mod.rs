@@ -72,6 +72,9 @@ pub trait CacheTrait: Send + Sync {
     async fn get_value<T>(&self, k: &str) -> Result<T, AppError>
     where
         T: Serialize + for<'de> Deserialize<'de> + Sync + Send;
+
+    /// 计数器加一, 首次创建时设置过期时间, 返回加一后的值
+    async fn incr_ex(&self, k: &str, ttl: usize) -> Result<i64, AppError>;
 }
 
 static MODULE_NAME: &str = "[cache]";
@@ -214,6 +217,27 @@ impl Cache {
         }
     }
 
+    pub async fn get_string(&self, k: &str) -> Result<String, AppError> {
+        match self {
+            Cache::Redis(cache) => cache.get_string(k).await,
+            Cache::Memory(cache) => cache.get_string(k).await,
+        }
+    }
+
+    pub async fn remove(&self, k: &str) -> Result<usize, AppError> {
+        match self {
+            Cache::Redis(cache) => cache.remove(k).await,
+            Cache::Memory(cache) => cache.remove(k).await,
+        }
+    }
+
+    pub async fn incr_ex(&self, k: &str, ttl: usize) -> Result<i64, AppError> {
+        match self {
+            Cache::Redis(cache) => cache.incr_ex(k, ttl).await,
+            Cache::Memory(cache) => cache.incr_ex(k, ttl).await,
+        }
+    }
+
     pub async fn zrem<V>(&self, key: &str, value: V) -> Result<bool, AppError>
     where
         V: ToString + Send + Sync,
@@ -5,7 +5,7 @@ use std::{
 
 use bb8::Pool;
 use bb8_redis::{RedisConnectionManager, bb8, redis};
-use commonx::{error::AppError, web_error};
+use commonx::{error::AppError, tenant::tenant_key, web_error};
 use redis::{AsyncCommands, RedisResult};
 use serde::{Deserialize, Serialize};
 
@@ -13,6 +13,15 @@ use commonx::web_info;
 
 use crate::cache::CacheTrait;
 
+/// bb8-redis依赖的redis未启用script特性, 通过EVAL执行
+const INCR_EX_SCRIPT: &str = r"
+local value = redis.call('INCR', KEYS[1])
+if redis.call('TTL', KEYS[1]) < 0 then
+    redis.call('EXPIRE', KEYS[1], ARGV[1])
+end
+return value
+";
+
 #[derive(Debug)]
 pub struct RedisCache {
     pool: Pool<RedisConnectionManager>,
@@ -53,6 +62,11 @@ impl RedisCache {
         }
     }
 
+    /// 键值类数据按当前租户隔离, 队列等集合类数据为全局共享, 不经过该方法
+    fn get_tenant_key(&self, key: &str) -> String {
+        self.get_namespaced_key(&tenant_key(key))
+    }
+
     fn get_namespaced_keys(&self, keys: &Vec<String>) -> Vec<String> {
         let mut result: Vec<String> = vec![];
         let namespace = self.namespace.read().unwrap();
@@ -89,7 +103,7 @@ impl CacheTrait for RedisCache {
     }
 
     async fn set_string_ex(&self, k: &str, v: &str, t: i32) -> Result<bool, AppError> {
-        let key = self.get_namespaced_key(k);
+        let key = self.get_tenant_key(k);
         let mut conn = self.pool.get().await?;
         let result: RedisResult<()> = conn.set_ex(&key, v, t as u64).await;
         web_info!(
@@ -118,7 +132,7 @@ impl CacheTrait for RedisCache {
     }
 
     async fn get_string(&self, k: &str) -> Result<String, AppError> {
-        let key = self.get_namespaced_key(k);
+        let key = self.get_tenant_key(k);
         let mut conn = self.pool.get().await?;
         let result: Option<String> = conn.get(&key).await?;
         result
@@ -127,7 +141,7 @@ impl CacheTrait for RedisCache {
     }
 
     async fn remove(&self, k: &str) -> Result<usize, AppError> {
-        let key = self.get_namespaced_key(k);
+        let key = self.get_tenant_key(k);
         let mut conn = self.pool.get().await?;
         let result: usize = conn.del(&key).await?;
         Ok(result)
@@ -158,7 +172,7 @@ impl CacheTrait for RedisCache {
     where
         V: ToString + Sync + Send,
     {
-        let namespace_key = self.get_namespaced_key(key);
+        let namespace_key = self.get_tenant_key(key);
         let mut conn = self.pool.get().await?;
         let result = redis::cmd("SET")
             .arg(namespace_key)
@@ -211,6 +225,20 @@ impl CacheTrait for RedisCache {
         let result: i64 = conn.zrem(&namespaced_key, value.to_string()).await?;
         Ok(result > 0)
     }
+
+    async fn incr_ex(&self, k: &str, ttl: usize) -> Result<i64, AppError> {
+        let key = self.get_tenant_key(k);
+        let mut conn = self.pool.get().await?;
+        // INCR与EXPIRE在同一脚本中原子执行; 没有过期时间的旧key同时补上, 避免计数永不过期
+        let result: i64 = redis::cmd("EVAL")
+            .arg(INCR_EX_SCRIPT)
+            .arg(1)
+            .arg(&key)
+            .arg(ttl)
+            .query_async(&mut *conn)
+            .await?;
+        Ok(result)
+    }
 }
 
 #[cfg(test)]
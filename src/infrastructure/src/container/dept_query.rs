@@ -0,0 +1,41 @@
+use async_trait::async_trait;
+
+use chrono::{Local, TimeZone};
+use commonx::error::AppError;
+use queryx::dept::api::DeptQueryTrait;
+use queryx::dept::entity::DeptVo;
+use queryx::dept::services::DeptQueryImpl;
+
+use crate::persistence::entities::sys_dept::Model as DeptModel;
+
+pub struct DeptQueryRepositoryImpl {}
+
+impl From<DeptModel> for DeptVo {
+    fn from(model: DeptModel) -> Self {
+        Self {
+            id: model.id,
+            parent_id: model.parent_id,
+            name: model.name,
+            order_num: model.order_num,
+            status: model.status,
+            created_at: Local
+                .from_local_datetime(&model.created_at)
+                .single()
+                .unwrap_or_default(),
+        }
+    }
+}
+
+#[async_trait]
+impl DeptQueryTrait for DeptQueryRepositoryImpl {
+    async fn list(&self) -> Result<Vec<DeptVo>, AppError> {
+        DeptModel::list()
+            .await
+            .map_err(|e| e.into())
+            .map(|models| models.into_iter().map(DeptVo::from).collect())
+    }
+}
+
+pub fn new_dept_query_service() -> DeptQueryImpl {
+    DeptQueryImpl::new(Box::new(DeptQueryRepositoryImpl {}))
+}
@@ -24,6 +24,8 @@ impl From<CornJobModel> for CreateJobDto {
             queue: model.queue,
             args: model.args,
             retry: model.retry,
+            create_by: model.create_by,
+            dept_id: model.dept_id,
             created_at: Local
                 .from_local_datetime(&model.created_at)
                 .single()
@@ -46,6 +48,8 @@ impl From<CornJobModel> for JobVo {
             queue: model.queue,
             args: model.args,
             retry: model.retry,
+            dept_id: model.dept_id,
+            create_by: model.create_by,
             created_at: Local
                 .from_local_datetime(&model.created_at)
                 .single()
@@ -61,10 +65,14 @@ impl From<CornJobModel> for JobVo {
 #[async_trait]
 impl JobQueryTrait for JobDomainRepositoryImpl {
     async fn list(&self, query: ListJobQo) -> Result<Vec<JobVo>, AppError> {
-        CornJobModel::list(query.page_req.page, query.page_req.page_size)
-            .await
-            .map_err(|e| e.into())
-            .map(|models| models.into_iter().map(JobVo::from).collect())
+        CornJobModel::list(
+            query.page_req.page,
+            query.page_req.page_size,
+            &query.data_scope,
+        )
+        .await
+        .map_err(|e| e.into())
+        .map(|models| models.into_iter().map(JobVo::from).collect())
     }
 
     async fn get_by_id(&self, id: i64) -> Result<Option<JobVo>, AppError> {
@@ -78,11 +86,9 @@ impl JobQueryTrait for JobDomainRepositoryImpl {
 #[async_trait]
 impl JobRepositoryTrait for JobDomainRepositoryImpl {
     async fn create(&self, job: CreateJobDto) -> Result<i64, JobDomainError> {
-        CornJobModel::create(
-            job.name, job.class, job.cron, job.queue, job.args, job.retry,
-        )
-        .await
-        .map_err(|e| JobDomainError::DbError(e.to_string()))
+        CornJobModel::create(job)
+            .await
+            .map_err(|e| JobDomainError::DbError(e.to_string()))
     }
 
     async fn delete_by_id(&self, id: i64) -> Result<(), JobDomainError> {
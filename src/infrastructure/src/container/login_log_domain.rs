@@ -0,0 +1,67 @@
+use crate::persistence::entities::sys_login_log::Model as LoginLogModel;
+use async_trait::async_trait;
+use chrono::{Local, TimeZone};
+use commonx::error::AppError;
+use login_log_domain::{
+    LoginLogDomainImpl, entity::LoginLog, new_login_log_domain, repository::LoginLogRepositoryTrait,
+};
+use queryx::login_log::{
+    api::LoginLogQueryTrait,
+    entity::{ListLoginLogQo, LoginLogPageVo, LoginLogVo},
+    services::LoginLogQueryImpl,
+};
+
+#[derive(Default)]
+pub struct LoginLogRepositoryImpl {}
+
+impl From<LoginLogModel> for LoginLogVo {
+    fn from(model: LoginLogModel) -> Self {
+        Self {
+            id: model.id,
+            user_id: model.user_id,
+            username: model.username,
+            login_type: model.login_type,
+            ip: model.ip,
+            user_agent: model.user_agent,
+            status: model.status,
+            fail_reason: model.fail_reason,
+            captcha_used: model.captcha_used,
+            two_factor_used: model.two_factor_used,
+            request_id: model.request_id,
+            dept_id: model.dept_id,
+            login_time: Local
+                .from_local_datetime(&model.login_time)
+                .single()
+                .unwrap_or_default(),
+        }
+    }
+}
+
+#[async_trait]
+impl LoginLogRepositoryTrait for LoginLogRepositoryImpl {
+    async fn create(&self, log: LoginLog) -> Result<(), AppError> {
+        LoginLogModel::create(log).await.map_err(AppError::from)?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl LoginLogQueryTrait for LoginLogRepositoryImpl {
+    async fn list(&self, query: ListLoginLogQo) -> Result<LoginLogPageVo, AppError> {
+        LoginLogModel::list(&query)
+            .await
+            .map_err(|e| e.into())
+            .map(|(models, total)| LoginLogPageVo {
+                list: models.into_iter().map(LoginLogVo::from).collect(),
+                total,
+            })
+    }
+}
+
+pub fn new_login_log_domain_service() -> LoginLogDomainImpl {
+    new_login_log_domain(Box::new(LoginLogRepositoryImpl {}))
+}
+
+pub fn new_login_log_query_service() -> LoginLogQueryImpl {
+    LoginLogQueryImpl::new(Box::new(LoginLogRepositoryImpl {}))
+}
@@ -1,4 +1,7 @@
+pub mod dept_query;
 pub mod job_domain;
+pub mod login_log_domain;
+pub mod oper_log_writer;
 pub mod operater_log_domain;
 pub mod sys_domain;
 pub mod user_domain;
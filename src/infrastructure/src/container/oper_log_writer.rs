@@ -0,0 +1,350 @@
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Local};
+use commonx::config::APP_CONFIG;
+use commonx::config::config::{OperLogWriterConfig, OverflowPolicy};
+use commonx::error::AppError;
+use commonx::{web_error, web_info};
+use once_cell::sync::OnceCell;
+use operater_log_domain::{entity::OperaterLog, repository::OperaterLogRepositoryTrait};
+use serde::Serialize;
+use tokio::sync::mpsc::{self, Receiver, Sender, error::TrySendError};
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+
+use crate::container::operater_log_domain::OperaterLogRepositoryImpl;
+use crate::persistence::entities::sys_oper_log::Model as OperaterLogModel;
+use crate::persistence::tenant::tenant_or;
+
+static OPER_LOG_WRITER: OnceCell<OperLogWriter> = OnceCell::new();
+
+/// 异步写入计数
+#[derive(Debug, Default)]
+struct WriterStats {
+    enqueued: AtomicU64,
+    written: AtomicU64,
+    dropped: AtomicU64,
+    failed_batches: AtomicU64,
+}
+
+/// 异步写入状态快照
+#[derive(Debug, Clone, Serialize)]
+pub struct OperLogWriterMetrics {
+    /// 队列中等待写入的条数
+    pub queued: usize,
+    pub capacity: usize,
+    /// 累计入队条数
+    pub enqueued: u64,
+    /// 累计写库条数
+    pub written: u64,
+    /// 累计丢弃条数(队列已满或重试后仍写库失败)
+    pub dropped: u64,
+    /// 累计写库失败的批次
+    pub failed_batches: u64,
+}
+
+/// 操作日志异步写入器, 日志进入有界队列后由后台任务批量写库
+pub struct OperLogWriter {
+    tx: Sender<OperaterLog>,
+    stats: Arc<WriterStats>,
+    capacity: usize,
+    overflow: OverflowPolicy,
+    shutdown: CancellationToken,
+    task: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl OperLogWriter {
+    fn start<F, Fut>(config: &OperLogWriterConfig, sink: F) -> Self
+    where
+        F: Fn(Vec<OperaterLog>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<u64, AppError>> + Send + 'static,
+    {
+        let capacity = config.capacity.max(1);
+        let (tx, rx) = mpsc::channel(capacity);
+        let stats = Arc::new(WriterStats::default());
+        let shutdown = CancellationToken::new();
+        let task = tokio::spawn(run_flush_loop(
+            rx,
+            config.clone(),
+            stats.clone(),
+            shutdown.clone(),
+            sink,
+        ));
+        Self {
+            tx,
+            stats,
+            capacity,
+            overflow: config.overflow,
+            shutdown,
+            task: Mutex::new(Some(task)),
+        }
+    }
+
+    /// 关闭队列并等待后台任务写完剩余日志, 关闭后提交的日志直接丢弃
+    pub async fn close(&self) {
+        self.shutdown.cancel();
+        let task = self.task.lock().ok().and_then(|mut task| task.take());
+        if let Some(task) = task
+            && let Err(e) = task.await
+        {
+            web_error!(" -- 操作日志写入任务异常退出: {:?}", e);
+        }
+    }
+
+    /// 日志入队, 队列已满时按配置丢弃或等待
+    pub async fn submit(&self, log: OperaterLog) {
+        let sent = match self.overflow {
+            OverflowPolicy::Drop => match self.tx.try_send(log) {
+                Ok(()) => true,
+                Err(TrySendError::Full(_)) => {
+                    let dropped = self.stats.dropped.fetch_add(1, Ordering::Relaxed) + 1;
+                    // 避免队列持续满载时刷屏
+                    if dropped.is_power_of_two() {
+                        web_error!(" -- 操作日志队列已满, 累计丢弃{}条", dropped);
+                    }
+                    false
+                }
+                Err(TrySendError::Closed(_)) => false,
+            },
+            OverflowPolicy::Block => self.tx.send(log).await.is_ok(),
+        };
+        if sent {
+            self.stats.enqueued.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    pub fn metrics(&self) -> OperLogWriterMetrics {
+        OperLogWriterMetrics {
+            queued: self.capacity - self.tx.capacity(),
+            capacity: self.capacity,
+            enqueued: self.stats.enqueued.load(Ordering::Relaxed),
+            written: self.stats.written.load(Ordering::Relaxed),
+            dropped: self.stats.dropped.load(Ordering::Relaxed),
+            failed_batches: self.stats.failed_batches.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// 全局写入器, 首次使用时启动后台任务
+pub fn oper_log_writer() -> &'static OperLogWriter {
+    OPER_LOG_WRITER.get_or_init(|| {
+        let config = &APP_CONFIG.oper_log.writer;
+        web_info!(
+            " -- 启动操作日志异步写入: capacity:{} batch_size:{} overflow:{:?}",
+            config.capacity,
+            config.batch_size,
+            config.overflow
+        );
+        OperLogWriter::start(config, |logs| async move {
+            OperaterLogModel::create_many(logs)
+                .await
+                .map_err(AppError::from)
+        })
+    })
+}
+
+/// 服务停止时关闭全局写入器, 未启动时直接返回
+pub async fn close_oper_log_writer() {
+    if let Some(writer) = OPER_LOG_WRITER.get() {
+        writer.close().await;
+        web_info!(" -- 操作日志异步写入已关闭: {:?}", writer.metrics());
+    }
+}
+
+/// 未满一批时按间隔刷新, 队列关闭时写完剩余日志后退出
+async fn run_flush_loop<F, Fut>(
+    mut rx: Receiver<OperaterLog>,
+    config: OperLogWriterConfig,
+    stats: Arc<WriterStats>,
+    shutdown: CancellationToken,
+    sink: F,
+) where
+    F: Fn(Vec<OperaterLog>) -> Fut + Sync,
+    Fut: Future<Output = Result<u64, AppError>> + Send,
+{
+    let batch_size = config.batch_size.max(1);
+    let mut interval =
+        tokio::time::interval(Duration::from_millis(config.flush_interval_ms.max(1)));
+    let mut batch = Vec::with_capacity(batch_size);
+    loop {
+        tokio::select! {
+            log = rx.recv() => match log {
+                Some(log) => {
+                    batch.push(log);
+                    if batch.len() >= batch_size {
+                        flush(&mut batch, &config, &stats, &sink).await;
+                    }
+                }
+                None => {
+                    flush(&mut batch, &config, &stats, &sink).await;
+                    break;
+                }
+            },
+            _ = interval.tick() => {
+                flush(&mut batch, &config, &stats, &sink).await;
+            }
+            _ = shutdown.cancelled() => {
+                // 不再接收新日志, 队列中已有的日志仍会被取出
+                rx.close();
+                while let Some(log) = rx.recv().await {
+                    batch.push(log);
+                    if batch.len() >= batch_size {
+                        flush(&mut batch, &config, &stats, &sink).await;
+                    }
+                }
+                flush(&mut batch, &config, &stats, &sink).await;
+                break;
+            }
+        }
+    }
+}
+
+/// 写库失败时按次数退避重试, 仍失败则丢弃该批
+async fn flush<F, Fut>(
+    batch: &mut Vec<OperaterLog>,
+    config: &OperLogWriterConfig,
+    stats: &WriterStats,
+    sink: &F,
+) where
+    F: Fn(Vec<OperaterLog>) -> Fut + Sync,
+    Fut: Future<Output = Result<u64, AppError>> + Send,
+{
+    if batch.is_empty() {
+        return;
+    }
+    let logs = std::mem::take(batch);
+    let count = logs.len() as u64;
+    let mut attempt = 0;
+    loop {
+        match sink(logs.clone()).await {
+            Ok(written) => {
+                stats.written.fetch_add(written, Ordering::Relaxed);
+                return;
+            }
+            Err(e) if attempt < config.max_retries => {
+                attempt += 1;
+                web_error!(" -- 操作日志批量写入失败, 第{}次重试: {:?}", attempt, e);
+                tokio::time::sleep(Duration::from_millis(100 * 2u64.pow(attempt))).await;
+            }
+            Err(e) => {
+                stats.failed_batches.fetch_add(1, Ordering::Relaxed);
+                stats.dropped.fetch_add(count, Ordering::Relaxed);
+                web_error!(" -- 操作日志批量写入失败, 丢弃{}条: {:?}", count, e);
+                return;
+            }
+        }
+    }
+}
+
+/// 异步写入的仓储实现, 入队即返回, 其余操作直接访问数据库
+#[derive(Default)]
+pub struct AsyncOperaterLogRepositoryImpl {
+    inner: OperaterLogRepositoryImpl,
+}
+
+impl AsyncOperaterLogRepositoryImpl {
+    pub fn new() -> Self {
+        Self {
+            inner: OperaterLogRepositoryImpl {},
+        }
+    }
+}
+
+#[async_trait]
+impl OperaterLogRepositoryTrait for AsyncOperaterLogRepositoryImpl {
+    async fn create(&self, mut log: OperaterLog) -> Result<(), AppError> {
+        // 后台任务没有租户上下文, 入队前确定所属租户
+        log.tenant_id = tenant_or(log.tenant_id);
+        oper_log_writer().submit(log).await;
+        Ok(())
+    }
+
+    async fn delete_before(&self, before: DateTime<Local>) -> Result<u64, AppError> {
+        self.inner.delete_before(before).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    fn config(batch_size: usize, max_retries: u32) -> OperLogWriterConfig {
+        OperLogWriterConfig {
+            capacity: 2,
+            batch_size,
+            flush_interval_ms: 20,
+            max_retries,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn writes_in_batches_and_drops_on_overflow() {
+        let batches = Arc::new(Mutex::new(vec![]));
+        let sink_batches = batches.clone();
+        let writer = OperLogWriter::start(&config(2, 0), move |logs: Vec<OperaterLog>| {
+            let batches = sink_batches.clone();
+            async move {
+                batches.lock().unwrap().push(logs.len());
+                Ok(logs.len() as u64)
+            }
+        });
+        // 队列容量为2, 后台任务尚未消费时第3条被丢弃
+        for _ in 0..3 {
+            writer.submit(OperaterLog::default()).await;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        writer.submit(OperaterLog::default()).await;
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        let metrics = writer.metrics();
+        assert_eq!(metrics.enqueued, 3);
+        assert_eq!(metrics.written, 3);
+        assert_eq!(metrics.dropped, 1);
+        assert_eq!(metrics.queued, 0);
+        assert_eq!(*batches.lock().unwrap(), vec![2, 1]);
+    }
+
+    #[tokio::test]
+    async fn close_drains_queue() {
+        let writer = OperLogWriter::start(
+            &OperLogWriterConfig {
+                capacity: 10,
+                batch_size: 100,
+                flush_interval_ms: 60_000,
+                ..Default::default()
+            },
+            |logs: Vec<OperaterLog>| async move { Ok(logs.len() as u64) },
+        );
+        for _ in 0..3 {
+            writer.submit(OperaterLog::default()).await;
+        }
+        writer.close().await;
+        writer.submit(OperaterLog::default()).await;
+
+        let metrics = writer.metrics();
+        assert_eq!(metrics.enqueued, 3);
+        assert_eq!(metrics.written, 3);
+        assert_eq!(metrics.queued, 0);
+    }
+
+    #[tokio::test]
+    async fn drops_batch_after_retries() {
+        let writer = OperLogWriter::start(&config(1, 1), |_logs: Vec<OperaterLog>| async {
+            Err::<u64, _>(AppError::from("db down".to_string()))
+        });
+        writer.submit(OperaterLog::default()).await;
+        tokio::time::sleep(Duration::from_millis(400)).await;
+
+        let metrics = writer.metrics();
+        assert_eq!(metrics.written, 0);
+        assert_eq!(metrics.dropped, 1);
+        assert_eq!(metrics.failed_batches, 1);
+    }
+}
@@ -1,34 +1,99 @@
+use crate::container::oper_log_writer::AsyncOperaterLogRepositoryImpl;
 use crate::persistence::entities::sys_oper_log::Model as OperaterLogModel;
 use async_trait::async_trait;
+use chrono::{DateTime, Local, TimeZone};
+use commonx::config::APP_CONFIG;
 use commonx::error::AppError;
 use operater_log_domain::{
     OperaterLogDomainImpl, entity::OperaterLog, new_operater_log_domain,
     repository::OperaterLogRepositoryTrait,
 };
+use queryx::oper_log::{
+    api::OperLogQueryTrait,
+    entity::{ListOperLogQo, OperLogPageVo, OperLogVo},
+    services::OperLogQueryImpl,
+};
 
+#[derive(Default)]
 pub struct OperaterLogRepositoryImpl {}
 
+impl From<OperaterLogModel> for OperLogVo {
+    fn from(model: OperaterLogModel) -> Self {
+        Self {
+            id: model.id,
+            oper_id: model.oper_id,
+            oper_name: model.oper_name,
+            dept_id: model.dept_id,
+            api_name: model.api_name,
+            title: model.title,
+            business_type: model.business_type,
+            request_method: model.request_method,
+            oper_url: model.oper_url,
+            oper_ip: model.oper_ip,
+            oper_location: model.oper_location,
+            oper_param: model.oper_param,
+            json_result: model.json_result,
+            status: model.status,
+            oper_query: model.oper_query,
+            http_status: model.http_status,
+            biz_code: model.biz_code,
+            error_msg: model.error_msg,
+            user_agent: model.user_agent,
+            request_id: model.request_id,
+            oper_time: Local
+                .from_local_datetime(&model.oper_time)
+                .single()
+                .unwrap_or_default(),
+            cost_time: model.cost_time,
+        }
+    }
+}
+
 #[async_trait]
 impl OperaterLogRepositoryTrait for OperaterLogRepositoryImpl {
     async fn create(&self, log: OperaterLog) -> Result<(), AppError> {
-        OperaterLogModel::create(
-            log.api_name,
-            log.request_method,
-            log.oper_id,
-            log.oper_name,
-            log.oper_url,
-            log.oper_ip,
-            log.oper_location,
-            log.oper_param,
-            log.json_result,
-            log.cost_time,
-        )
-        .await
-        .map_err(|e| AppError::from(e))?;
+        OperaterLogModel::create(log)
+            .await
+            .map_err(|e| AppError::from(e))?;
         Ok(())
     }
+
+    async fn delete_before(&self, before: DateTime<Local>) -> Result<u64, AppError> {
+        OperaterLogModel::delete_before(before)
+            .await
+            .map_err(|e| e.into())
+    }
 }
 
+#[async_trait]
+impl OperLogQueryTrait for OperaterLogRepositoryImpl {
+    async fn get_by_id(&self, id: i64) -> Result<Option<OperLogVo>, AppError> {
+        OperaterLogModel::find_by_id(id)
+            .await
+            .map_err(|e| e.into())
+            .map(|model| model.map(OperLogVo::from))
+    }
+
+    async fn list(&self, query: ListOperLogQo) -> Result<OperLogPageVo, AppError> {
+        OperaterLogModel::list(&query)
+            .await
+            .map_err(|e| e.into())
+            .map(|(models, total)| OperLogPageVo {
+                list: models.into_iter().map(OperLogVo::from).collect(),
+                total,
+            })
+    }
+}
+
+/// 启用异步写入时, 新增日志只入队不等待写库
 pub fn new_operater_log_domain_service() -> OperaterLogDomainImpl {
-    new_operater_log_domain(Box::new(OperaterLogRepositoryImpl {}))
+    if APP_CONFIG.oper_log.writer.enable {
+        new_operater_log_domain(Box::new(AsyncOperaterLogRepositoryImpl::new()))
+    } else {
+        new_operater_log_domain(Box::new(OperaterLogRepositoryImpl {}))
+    }
+}
+
+pub fn new_oper_log_query_service() -> OperLogQueryImpl {
+    OperLogQueryImpl::new(Box::new(OperaterLogRepositoryImpl {}))
 }
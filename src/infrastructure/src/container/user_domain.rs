@@ -1,16 +1,37 @@
+use crate::auth_provider::{ldap::LdapProviderImpl, oidc::OidcProviderImpl};
 use crate::cache::CacheManager;
-use crate::encrypt::pwd_encrypt::PwdEncryptImpl;
-use crate::persistence::entities::users::Model as UserModel;
+use crate::encrypt::{
+    pwd_encrypt::PwdEncryptImpl, secret_encrypt::SecretEncryptImpl, totp::TotpImpl,
+};
+use crate::persistence::entities::{
+    user_api_keys::Model as ApiKeyModel, user_identities::Model as IdentityModel,
+    user_password_history::Model as PasswordHistoryModel, user_sessions::Model as SessionModel,
+    users::Model as UserModel,
+};
+use crate::processor::{
+    wokers::mail_worker::{Email, MailerWorker},
+    worker::AppWorker,
+};
 use async_trait::async_trait;
-use chrono::{Local, TimeZone};
+use chrono::{DateTime, Local, TimeZone};
+use commonx::config::APP_CONFIG;
 use commonx::error::AppError;
 use commonx::{web_error, web_info};
 use user_domain::{
     UserDomainImpl,
     commons::error::UserDomainError,
-    entity::captcha::CaptchaCacheInfo,
+    entity::{
+        api_key::ApiKey, captcha::CaptchaCacheInfo, identity::AuthorizeRequest,
+        password_reset::PasswordResetToken, register::EmailVerifyToken, session::UserSession,
+        totp::LoginChallenge,
+    },
     new_user_domain,
-    repository::{cache::CacheRepositoryTrait, user::UserRepositoryTrait},
+    repository::{
+        auth_provider::{AuthProviderTrait, DirectoryProviderTrait},
+        cache::CacheRepositoryTrait,
+        mailer::MailerRepositoryTrait,
+        user::UserRepositoryTrait,
+    },
 };
 
 pub struct UserDomainRepositoryImpl {}
@@ -29,6 +50,16 @@ impl UserRepositoryTrait for UserDomainRepositoryImpl {
             )
     }
 
+    async fn get_by_email(
+        &self,
+        email: String,
+    ) -> Result<Option<user_domain::entity::user::User>, UserDomainError> {
+        UserModel::find_by_email(email.as_str()).await.map_or_else(
+            |e| Err(UserDomainError::DbError(e.to_string())),
+            |user| Ok(user.map(|u| u.into())),
+        )
+    }
+
     async fn get_by_id(
         &self,
         id: i64,
@@ -61,6 +92,206 @@ impl UserRepositoryTrait for UserDomainRepositoryImpl {
             .await
             .map_or_else(|e| Err(UserDomainError::DbError(e.to_string())), |_| Ok(()))
     }
+
+    async fn update_login_lock(
+        &self,
+        id: i64,
+        login_fail_count: i32,
+        locked_until: Option<DateTime<Local>>,
+    ) -> Result<(), UserDomainError> {
+        UserModel::update_login_lock(id, login_fail_count, locked_until)
+            .await
+            .map_or_else(|e| Err(UserDomainError::DbError(e.to_string())), |_| Ok(()))
+    }
+
+    async fn update_last_login(
+        &self,
+        id: i64,
+        ip: String,
+        login_time: DateTime<Local>,
+    ) -> Result<(), UserDomainError> {
+        UserModel::update_last_login(id, ip, login_time)
+            .await
+            .map_or_else(|e| Err(UserDomainError::DbError(e.to_string())), |_| Ok(()))
+    }
+
+    async fn update_status(&self, id: i64, status: String) -> Result<(), UserDomainError> {
+        UserModel::update_status(id, status)
+            .await
+            .map_or_else(|e| Err(UserDomainError::DbError(e.to_string())), |_| Ok(()))
+    }
+
+    async fn update_password(
+        &self,
+        id: i64,
+        password: String,
+        changed_at: Option<DateTime<Local>>,
+    ) -> Result<(), UserDomainError> {
+        UserModel::update_password(id, password, changed_at)
+            .await
+            .map_or_else(|e| Err(UserDomainError::DbError(e.to_string())), |_| Ok(()))
+    }
+
+    async fn get_password_history(
+        &self,
+        user_id: i64,
+        limit: u64,
+    ) -> Result<Vec<String>, UserDomainError> {
+        PasswordHistoryModel::find_recent(user_id, limit)
+            .await
+            .map_err(|e| UserDomainError::DbError(e.to_string()))
+    }
+
+    async fn add_password_history(
+        &self,
+        user_id: i64,
+        password: String,
+    ) -> Result<(), UserDomainError> {
+        PasswordHistoryModel::create(user_id, password)
+            .await
+            .map_or_else(|e| Err(UserDomainError::DbError(e.to_string())), |_| Ok(()))
+    }
+
+    async fn update_profile(
+        &self,
+        id: i64,
+        name: Option<String>,
+        email: Option<String>,
+        phone: Option<String>,
+        role_id: Option<i64>,
+    ) -> Result<(), UserDomainError> {
+        UserModel::update_profile(id, name, email, phone, role_id)
+            .await
+            .map_err(|e| UserDomainError::DbError(e.to_string()))
+    }
+
+    async fn get_api_key_by_prefix(
+        &self,
+        prefix: String,
+    ) -> Result<Option<ApiKey>, UserDomainError> {
+        ApiKeyModel::find_by_prefix(&prefix)
+            .await
+            .map(|key| key.map(|k| k.into()))
+            .map_err(|e| UserDomainError::DbError(e.to_string()))
+    }
+
+    async fn list_api_keys(&self, user_id: i64) -> Result<Vec<ApiKey>, UserDomainError> {
+        ApiKeyModel::find_by_user(user_id)
+            .await
+            .map(|keys| keys.into_iter().map(|k| k.into()).collect())
+            .map_err(|e| UserDomainError::DbError(e.to_string()))
+    }
+
+    async fn create_api_key(&self, api_key: ApiKey) -> Result<i64, UserDomainError> {
+        ApiKeyModel::create(api_key)
+            .await
+            .map_err(|e| UserDomainError::DbError(e.to_string()))
+    }
+
+    async fn revoke_api_key(&self, id: i64, user_id: i64) -> Result<bool, UserDomainError> {
+        ApiKeyModel::revoke(id, user_id)
+            .await
+            .map_err(|e| UserDomainError::DbError(e.to_string()))
+    }
+
+    async fn touch_api_key(&self, id: i64) -> Result<(), UserDomainError> {
+        ApiKeyModel::touch(id)
+            .await
+            .map_err(|e| UserDomainError::DbError(e.to_string()))
+    }
+
+    async fn create_session(&self, session: UserSession) -> Result<(), UserDomainError> {
+        SessionModel::create(
+            session.token_id,
+            session.user_id,
+            session.tenant_id,
+            session.username,
+            session.ip,
+            session.user_agent,
+            session.expires_at.unwrap_or_else(Local::now),
+        )
+        .await
+        .map_err(|e| UserDomainError::DbError(e.to_string()))
+    }
+
+    async fn get_session(&self, token_id: i64) -> Result<Option<UserSession>, UserDomainError> {
+        SessionModel::find_by_token_id(token_id)
+            .await
+            .map(|session| session.map(|s| s.into()))
+            .map_err(|e| UserDomainError::DbError(e.to_string()))
+    }
+
+    async fn list_sessions(
+        &self,
+        user_id: Option<i64>,
+        page: u64,
+        page_size: u64,
+    ) -> Result<Vec<UserSession>, UserDomainError> {
+        SessionModel::list_active(user_id, page, page_size)
+            .await
+            .map(|sessions| sessions.into_iter().map(|s| s.into()).collect())
+            .map_err(|e| UserDomainError::DbError(e.to_string()))
+    }
+
+    async fn revoke_session(&self, token_id: i64) -> Result<bool, UserDomainError> {
+        SessionModel::revoke(token_id)
+            .await
+            .map_err(|e| UserDomainError::DbError(e.to_string()))
+    }
+
+    async fn revoke_user_sessions(&self, user_id: i64) -> Result<Vec<i64>, UserDomainError> {
+        SessionModel::revoke_by_user(user_id)
+            .await
+            .map_err(|e| UserDomainError::DbError(e.to_string()))
+    }
+
+    async fn touch_session(&self, token_id: i64) -> Result<(), UserDomainError> {
+        SessionModel::touch(token_id)
+            .await
+            .map_err(|e| UserDomainError::DbError(e.to_string()))
+    }
+
+    async fn get_identity_user_id(
+        &self,
+        provider: String,
+        subject: String,
+    ) -> Result<Option<i64>, UserDomainError> {
+        IdentityModel::find_by_subject(&provider, &subject)
+            .await
+            .map(|identity| identity.map(|i| i.user_id))
+            .map_err(|e| UserDomainError::DbError(e.to_string()))
+    }
+
+    async fn add_identity(
+        &self,
+        user_id: i64,
+        provider: String,
+        subject: String,
+        email: Option<String>,
+    ) -> Result<(), UserDomainError> {
+        IdentityModel::create(user_id, provider, subject, email)
+            .await
+            .map(|_| ())
+            .map_err(|e| UserDomainError::DbError(e.to_string()))
+    }
+
+    async fn update_totp(
+        &self,
+        id: i64,
+        totp_secret: Option<String>,
+        totp_enabled: bool,
+        totp_recovery_codes: Option<String>,
+    ) -> Result<(), UserDomainError> {
+        UserModel::update_totp(id, totp_secret, totp_enabled, totp_recovery_codes)
+            .await
+            .map_or_else(|e| Err(UserDomainError::DbError(e.to_string())), |_| Ok(()))
+    }
+
+    async fn consume_totp_step(&self, id: i64, step: u64) -> Result<bool, UserDomainError> {
+        UserModel::consume_totp_step(id, step as i64)
+            .await
+            .map_err(|e| UserDomainError::DbError(e.to_string()))
+    }
 }
 
 impl From<UserModel> for user_domain::entity::user::User {
@@ -92,6 +323,70 @@ impl From<UserModel> for user_domain::entity::user::User {
                 .deleted_at
                 .map(|naive| Local.from_local_datetime(&naive).single())
                 .unwrap_or_default(),
+            login_fail_count: user.login_fail_count,
+            locked_until: user
+                .locked_until
+                .map(|naive| Local.from_local_datetime(&naive).single())
+                .unwrap_or_default(),
+            totp_secret: user.totp_secret,
+            totp_enabled: user.totp_enabled,
+            totp_recovery_codes: user.totp_recovery_codes,
+            password_changed_at: user
+                .password_changed_at
+                .map(|naive| Local.from_local_datetime(&naive).single())
+                .unwrap_or_default(),
+            auth_source: user.auth_source,
+            tenant_id: user.tenant_id,
+            dept_id: user.dept_id,
+            last_login_ip: user.last_login_ip,
+            last_login_time: user
+                .last_login_time
+                .map(|naive| Local.from_local_datetime(&naive).single())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+impl From<SessionModel> for UserSession {
+    fn from(session: SessionModel) -> Self {
+        Self {
+            token_id: session.token_id,
+            user_id: session.user_id,
+            tenant_id: session.tenant_id,
+            username: session.username,
+            ip: session.ip,
+            user_agent: session.user_agent,
+            created_at: Local.from_local_datetime(&session.created_at).single(),
+            last_seen_at: session
+                .last_seen_at
+                .and_then(|naive| Local.from_local_datetime(&naive).single()),
+            expires_at: Local.from_local_datetime(&session.expires_at).single(),
+            revoked_at: session
+                .revoked_at
+                .and_then(|naive| Local.from_local_datetime(&naive).single()),
+        }
+    }
+}
+
+impl From<ApiKeyModel> for ApiKey {
+    fn from(key: ApiKeyModel) -> Self {
+        Self {
+            id: key.id,
+            user_id: key.user_id,
+            name: key.name,
+            prefix: key.prefix,
+            key_hash: key.key_hash,
+            scopes: serde_json::from_str(&key.scopes).unwrap_or_default(),
+            expires_at: key
+                .expires_at
+                .and_then(|naive| Local.from_local_datetime(&naive).single()),
+            last_used_at: key
+                .last_used_at
+                .and_then(|naive| Local.from_local_datetime(&naive).single()),
+            revoked_at: key
+                .revoked_at
+                .and_then(|naive| Local.from_local_datetime(&naive).single()),
+            created_at: Local.from_local_datetime(&key.created_at).single(),
         }
     }
 }
@@ -104,10 +399,19 @@ impl CacheRepositoryTrait for UserDomainCacheRepositoryImpl {
         &self,
         key: String,
         value: CaptchaCacheInfo,
+        ttl: u64,
     ) -> Result<bool, UserDomainError> {
         CacheManager::instance()
-            .set_value_ex(&key, &value, 300)
+            .set_value_ex(&key, &value, ttl as i32)
+            .await
+            .map_err(|e| UserDomainError::CaptchaError(e.to_string()))
+    }
+
+    async fn incr_captcha_count(&self, key: String, ttl: u64) -> Result<u64, UserDomainError> {
+        CacheManager::instance()
+            .incr_ex(&key, ttl as usize)
             .await
+            .map(|count| count.max(0) as u64)
             .map_err(|e| UserDomainError::CaptchaError(e.to_string()))
     }
 
@@ -124,12 +428,229 @@ impl CacheRepositoryTrait for UserDomainCacheRepositoryImpl {
                 _ => UserDomainError::CaptchaError(e.to_string()),
             })
     }
+
+    async fn incr_login_failure(&self, key: String, ttl: u64) -> Result<u64, UserDomainError> {
+        CacheManager::instance()
+            .incr_ex(&key, ttl as usize)
+            .await
+            .map(|count| count.max(0) as u64)
+            .map_err(|e| UserDomainError::InternalError(e.to_string()))
+    }
+
+    async fn get_login_failure(&self, key: String) -> Result<u64, UserDomainError> {
+        match CacheManager::instance().get_string(&key).await {
+            Ok(count) => Ok(count.parse::<u64>().unwrap_or_default()),
+            Err(AppError::CacheNotFoundError(_)) => Ok(0),
+            Err(e) => Err(UserDomainError::InternalError(e.to_string())),
+        }
+    }
+
+    async fn clear_login_failure(&self, key: String) -> Result<(), UserDomainError> {
+        CacheManager::instance()
+            .remove(&key)
+            .await
+            .map(|_| ())
+            .map_err(|e| UserDomainError::InternalError(e.to_string()))
+    }
+
+    async fn set_login_challenge(
+        &self,
+        key: String,
+        value: LoginChallenge,
+        ttl: u64,
+    ) -> Result<(), UserDomainError> {
+        CacheManager::instance()
+            .set_value_ex(&key, &value, ttl as i32)
+            .await
+            .map(|_| ())
+            .map_err(|e| UserDomainError::InternalError(e.to_string()))
+    }
+
+    async fn take_login_challenge(
+        &self,
+        key: String,
+    ) -> Result<Option<LoginChallenge>, UserDomainError> {
+        match CacheManager::instance()
+            .get_oneuse_value::<LoginChallenge>(&key)
+            .await
+        {
+            Ok(challenge) => Ok(Some(challenge)),
+            Err(AppError::CacheNotFoundError(_)) => Ok(None),
+            Err(e) => Err(UserDomainError::InternalError(e.to_string())),
+        }
+    }
+
+    async fn set_password_reset(
+        &self,
+        key: String,
+        value: PasswordResetToken,
+        ttl: u64,
+    ) -> Result<(), UserDomainError> {
+        CacheManager::instance()
+            .set_value_ex(&key, &value, ttl as i32)
+            .await
+            .map(|_| ())
+            .map_err(|e| UserDomainError::InternalError(e.to_string()))
+    }
+
+    async fn get_password_reset(
+        &self,
+        key: String,
+    ) -> Result<Option<PasswordResetToken>, UserDomainError> {
+        match CacheManager::instance().get_string(&key).await {
+            Ok(value) => serde_json::from_str(&value)
+                .map(Some)
+                .map_err(|e| UserDomainError::InternalError(e.to_string())),
+            Err(AppError::CacheNotFoundError(_)) => Ok(None),
+            Err(e) => Err(UserDomainError::InternalError(e.to_string())),
+        }
+    }
+
+    async fn take_password_reset(
+        &self,
+        key: String,
+    ) -> Result<Option<PasswordResetToken>, UserDomainError> {
+        match CacheManager::instance()
+            .get_oneuse_value::<PasswordResetToken>(&key)
+            .await
+        {
+            Ok(reset) => Ok(Some(reset)),
+            Err(AppError::CacheNotFoundError(_)) => Ok(None),
+            Err(e) => Err(UserDomainError::InternalError(e.to_string())),
+        }
+    }
+
+    async fn set_email_verify(
+        &self,
+        key: String,
+        value: EmailVerifyToken,
+        ttl: u64,
+    ) -> Result<(), UserDomainError> {
+        CacheManager::instance()
+            .set_value_ex(&key, &value, ttl as i32)
+            .await
+            .map(|_| ())
+            .map_err(|e| UserDomainError::InternalError(e.to_string()))
+    }
+
+    async fn take_email_verify(
+        &self,
+        key: String,
+    ) -> Result<Option<EmailVerifyToken>, UserDomainError> {
+        match CacheManager::instance()
+            .get_oneuse_value::<EmailVerifyToken>(&key)
+            .await
+        {
+            Ok(verify) => Ok(Some(verify)),
+            Err(AppError::CacheNotFoundError(_)) => Ok(None),
+            Err(e) => Err(UserDomainError::InternalError(e.to_string())),
+        }
+    }
+
+    async fn set_authorize_request(
+        &self,
+        key: String,
+        value: AuthorizeRequest,
+        ttl: u64,
+    ) -> Result<(), UserDomainError> {
+        CacheManager::instance()
+            .set_value_ex(&key, &value, ttl as i32)
+            .await
+            .map(|_| ())
+            .map_err(|e| UserDomainError::InternalError(e.to_string()))
+    }
+
+    async fn take_authorize_request(
+        &self,
+        key: String,
+    ) -> Result<Option<AuthorizeRequest>, UserDomainError> {
+        match CacheManager::instance()
+            .get_oneuse_value::<AuthorizeRequest>(&key)
+            .await
+        {
+            Ok(request) => Ok(Some(request)),
+            Err(AppError::CacheNotFoundError(_)) => Ok(None),
+            Err(e) => Err(UserDomainError::InternalError(e.to_string())),
+        }
+    }
+
+    async fn set_session_revoked(&self, key: String, ttl: u64) -> Result<(), UserDomainError> {
+        CacheManager::instance()
+            .set_value_ex(&key, &true, ttl as i32)
+            .await
+            .map(|_| ())
+            .map_err(|e| UserDomainError::InternalError(e.to_string()))
+    }
+
+    async fn is_session_revoked(&self, key: String) -> Result<bool, UserDomainError> {
+        match CacheManager::instance().get_string(&key).await {
+            Ok(_) => Ok(true),
+            Err(AppError::CacheNotFoundError(_)) => Ok(false),
+            Err(e) => Err(UserDomainError::InternalError(e.to_string())),
+        }
+    }
+
+    async fn mark_session_seen(&self, key: String, ttl: u64) -> Result<bool, UserDomainError> {
+        CacheManager::instance()
+            .set_nx_ex(&key, 1, ttl as usize)
+            .await
+            .map_err(|e| UserDomainError::InternalError(e.to_string()))
+    }
+}
+
+pub struct UserDomainMailerRepositoryImpl {}
+
+#[async_trait]
+impl MailerRepositoryTrait for UserDomainMailerRepositoryImpl {
+    async fn send_mail(
+        &self,
+        from: Option<String>,
+        to: String,
+        text: String,
+    ) -> Result<(), UserDomainError> {
+        let email = serde_json::to_value(Email { from, to, text })
+            .map_err(|e| UserDomainError::InternalError(e.to_string()))?;
+        MailerWorker::enqueue_async(email)
+            .await
+            .map_err(|e| UserDomainError::InternalError(e.to_string()))
+    }
 }
 
 pub fn new_user_domain_service() -> UserDomainImpl {
+    if APP_CONFIG.auth.totp.encrypt_key.trim().is_empty() {
+        web_error!("未配置TOTP加密口令(auth.totp.encrypt_key), 两步验证绑定已禁用");
+    }
     new_user_domain(
         Box::new(UserDomainCacheRepositoryImpl {}),
         Box::new(UserDomainRepositoryImpl {}),
         Box::new(PwdEncryptImpl {}),
+        Box::new(SecretEncryptImpl::new(&APP_CONFIG.auth.totp.encrypt_key)),
+        Box::new(TotpImpl {}),
+        Box::new(UserDomainMailerRepositoryImpl {}),
+        auth_providers(),
+        directory_provider(),
+        APP_CONFIG.auth.clone(),
     )
 }
+
+fn auth_providers() -> Vec<Box<dyn AuthProviderTrait + Sync + Send>> {
+    let oidc = &APP_CONFIG.auth.oidc;
+    if !oidc.enable {
+        return vec![];
+    }
+    oidc.providers
+        .iter()
+        .map(|p| {
+            Box::new(OidcProviderImpl::new(p.clone(), oidc.http_timeout))
+                as Box<dyn AuthProviderTrait + Sync + Send>
+        })
+        .collect()
+}
+
+fn directory_provider() -> Option<Box<dyn DirectoryProviderTrait + Sync + Send>> {
+    let ldap = &APP_CONFIG.auth.ldap;
+    if !ldap.enable {
+        return None;
+    }
+    Some(Box::new(LdapProviderImpl::new(ldap.clone())))
+}
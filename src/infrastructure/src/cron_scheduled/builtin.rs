@@ -0,0 +1,49 @@
+use std::sync::Arc;
+
+use commonx::config::APP_CONFIG;
+use commonx::error::AppError;
+use commonx::{web_error, web_info};
+use tokio_cron_scheduler::JobScheduler;
+
+use crate::cron_scheduled::GLOBAL_SCHEDULER;
+use crate::cron_scheduled::cron_scheduled::CronScheduled;
+use crate::processor::wokers::oper_log_retention_worker::OperLogRetentionWorker;
+use crate::processor::worker::{AppWorker, Worker};
+
+/// 操作日志保留策略
+pub const OPER_LOG_RETENTION_JOB: &str = "oper_log_retention";
+
+/// 注册系统内置定时任务, 到期后将对应worker加入任务队列执行
+pub async fn init_builtin_jobs() -> Result<(), AppError> {
+    let scheduler = JobScheduler::new().await.map_err(AppError::from)?;
+    let mut cron_scheduled = CronScheduled::new(scheduler);
+
+    let retention = &APP_CONFIG.oper_log.retention;
+    if retention.enable {
+        cron_scheduled
+            .add_job(OPER_LOG_RETENTION_JOB.to_string(), &retention.cron, || {
+                tokio::spawn(async {
+                    if let Err(e) =
+                        OperLogRetentionWorker::enqueue_async(serde_json::Value::Null).await
+                    {
+                        web_error!(
+                            " -- 内置任务加入队列失败: {}: {:?}",
+                            OPER_LOG_RETENTION_JOB,
+                            e
+                        );
+                    }
+                });
+            })
+            .await?;
+        web_info!(
+            " -- 注册内置任务: {} cron:{} worker:{}",
+            OPER_LOG_RETENTION_JOB,
+            retention.cron,
+            OperLogRetentionWorker::class_name()
+        );
+    }
+
+    cron_scheduled.start().await?;
+    let _ = GLOBAL_SCHEDULER.set(Arc::new(cron_scheduled));
+    Ok(())
+}
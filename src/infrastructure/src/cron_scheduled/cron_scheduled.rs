@@ -1,7 +1,7 @@
 use commonx::error::AppError;
 use std::collections::HashMap;
 use tokio::sync::Mutex;
-use tokio_cron_scheduler::JobScheduler;
+use tokio_cron_scheduler::{Job, JobScheduler};
 
 /// 定时任务状态
 #[derive(Debug, Clone)]
@@ -19,7 +19,7 @@ pub struct CronScheduled {
     /// 任务调度器
     scheduler: Mutex<JobScheduler>,
     /// 任务句柄映射
-    jobs: Mutex<HashMap<String, ()>>,
+    jobs: Mutex<HashMap<String, Job>>,
     /// 是否已启动
     has_start: bool,
 }
@@ -76,23 +76,19 @@ impl CronScheduled {
         cron_expression: &str,
         job_func: impl Fn() + Send + Sync + 'static,
     ) -> Result<(), AppError> {
-        // let mut scheduler = self.scheduler.lock().await;
-        // let mut jobs = self.jobs.lock().await;
+        let scheduler = self.scheduler.lock().await;
+        let mut jobs = self.jobs.lock().await;
 
-        // // 创建定时任务
-        // let job = scheduler
-        //     .new_cron_job(cron_expression)
-        //     .map_err(|e| AppError::from(e))?
-        //     .do_job(move |_uuid, _l| {
-        //         job_func();
-        //     })
-        //     .await
-        //     .map_err(|e| AppError::from(e))?;
+        // 创建定时任务, cron按本地时区解析
+        let job = Job::new_tz(cron_expression, chrono::Local, move |_uuid, _l| {
+            job_func();
+        })
+        .map_err(AppError::from)?;
+        scheduler.add(job.clone()).await.map_err(AppError::from)?;
 
-        // // 保存任务句柄
-        // jobs.insert(job_id, job);
-        // Ok(())
-        todo!()
+        // 保存任务句柄
+        jobs.insert(job_id, job);
+        Ok(())
     }
 
     /// 删除定时任务
@@ -104,15 +100,17 @@ impl CronScheduled {
     /// - 成功：返回是否删除成功
     /// - 失败：返回应用错误
     pub async fn remove_job(&self, job_id: &str) -> Result<bool, AppError> {
-        // let mut jobs = self.jobs.lock().await;
-        // if let Some(job_handle) = jobs.remove(job_id) {
-        //     job_handle.abort().await.map_err(|e| AppError::from(e))?;
-        //     Ok(true)
-        // } else {
-        //     Ok(false)
-        // }
-
-        todo!()
+        let mut jobs = self.jobs.lock().await;
+        if let Some(job_handle) = jobs.remove(job_id) {
+            let scheduler = self.scheduler.lock().await;
+            scheduler
+                .remove(&job_handle.guid())
+                .await
+                .map_err(AppError::from)?;
+            Ok(true)
+        } else {
+            Ok(false)
+        }
     }
 
     /// 罗列所有定时任务及其状态
@@ -1,3 +1,4 @@
+pub mod builtin;
 pub mod cron_scheduled;
 
 use std::sync::{Arc, OnceLock};
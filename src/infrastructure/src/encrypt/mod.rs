@@ -1 +1,3 @@
 pub mod pwd_encrypt;
+pub mod secret_encrypt;
+pub mod totp;
@@ -1,5 +1,5 @@
 use argon2::{
-    Argon2, PasswordHash, PasswordHasher, PasswordVerifier,
+    Algorithm, Argon2, Params, PasswordHash, PasswordHasher, PasswordVerifier, Version,
     password_hash::{SaltString, rand_core::OsRng},
 };
 use commonx::web_info;
@@ -21,7 +21,7 @@ impl PwdEncryptTrait for PwdEncryptImpl {
         let parsed_hash = match PasswordHash::new(encrypted_pwd) {
             Ok(h) => h,
             Err(e) => {
-                web_info!("密码校验:密码:{} 校验结果:解析密码哈希失败:{}", password, e);
+                web_info!("密码校验:解析密码哈希失败:{}", e);
                 return false;
             }
         };
@@ -29,4 +29,25 @@ impl PwdEncryptTrait for PwdEncryptImpl {
             .verify_password(password.as_bytes(), &parsed_hash)
             .is_ok()
     }
+
+    fn needs_rehash(&self, encrypted_pwd: &String) -> bool {
+        let Ok(parsed_hash) = PasswordHash::new(encrypted_pwd) else {
+            return false;
+        };
+        if parsed_hash.algorithm != Algorithm::default().ident()
+            || parsed_hash.version != Some(Version::default().into())
+        {
+            return true;
+        }
+        let argon2 = Argon2::default();
+        let current = argon2.params();
+        match Params::try_from(&parsed_hash) {
+            Ok(params) => {
+                params.m_cost() != current.m_cost()
+                    || params.t_cost() != current.t_cost()
+                    || params.p_cost() != current.p_cost()
+            }
+            Err(_) => true,
+        }
+    }
 }
@@ -0,0 +1,59 @@
+use aes_gcm::{
+    Aes256Gcm, Key, Nonce,
+    aead::{Aead, AeadCore, KeyInit, OsRng},
+};
+use base64::{Engine, engine::general_purpose::STANDARD};
+use data_encoding::HEXLOWER;
+use sha2::{Digest, Sha256};
+use user_domain::{commons::error::UserDomainError, repository::encrypt::SecretEncryptTrait};
+
+const NONCE_LEN: usize = 12;
+
+/// AES-256-GCM 加密, 密钥由配置的口令经 SHA-256 派生,
+/// 密文格式为 base64(nonce || ciphertext)
+pub struct SecretEncryptImpl {
+    cipher: Aes256Gcm,
+}
+
+impl SecretEncryptImpl {
+    pub fn new(passphrase: &str) -> Self {
+        let key = Sha256::digest(passphrase.as_bytes());
+        Self {
+            cipher: Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key)),
+        }
+    }
+}
+
+impl SecretEncryptTrait for SecretEncryptImpl {
+    fn encrypt(&self, plain: &str) -> Result<String, UserDomainError> {
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .cipher
+            .encrypt(&nonce, plain.as_bytes())
+            .map_err(|e| UserDomainError::InternalError(format!("加密失败: {}", e)))?;
+        let mut data = nonce.to_vec();
+        data.extend_from_slice(&ciphertext);
+        Ok(STANDARD.encode(data))
+    }
+
+    fn decrypt(&self, cipher: &str) -> Result<String, UserDomainError> {
+        let data = STANDARD
+            .decode(cipher)
+            .map_err(|e| UserDomainError::InternalError(format!("解密失败: {}", e)))?;
+        if data.len() <= NONCE_LEN {
+            return Err(UserDomainError::InternalError(
+                "解密失败: 密文格式错误".to_string(),
+            ));
+        }
+        let (nonce, ciphertext) = data.split_at(NONCE_LEN);
+        let plain = self
+            .cipher
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|e| UserDomainError::InternalError(format!("解密失败: {}", e)))?;
+        String::from_utf8(plain).map_err(|e| UserDomainError::InternalError(e.to_string()))
+    }
+
+    fn digest(&self, plain: &str) -> String {
+        HEXLOWER.encode(&Sha256::digest(plain.as_bytes()))
+    }
+}
@@ -1,3 +1,4 @@
+pub mod auth_provider;
 pub mod cache;
 pub mod container;
 pub mod cron_scheduled;
@@ -1,7 +1,11 @@
+use crate::persistence::data_scope::DataScoped;
 use crate::persistence::entities::corn_job;
 use crate::persistence::id_gen::next_id;
 use crate::persistence::init::get_db;
+use crate::persistence::tenant::{TenantScoped, tenant_or_default};
 use chrono::Local;
+use job_domain::entity::job::CreateJobDto;
+use queryx::entity::DataScope;
 use sea_orm::ActiveValue::Set;
 use sea_orm::{ColumnTrait, DbErr, EntityTrait, QueryFilter, QuerySelect};
 
@@ -9,42 +13,32 @@ impl corn_job::Model {
     /// 创建定时任务
     ///
     /// # 参数
-    /// - `name`: 任务名称
-    /// - `class`: 任务执行类
-    /// - `cron`: Cron表达式
-    /// - `queue`: 任务队列（可选）
-    /// - `args`: 任务参数（可选）
-    /// - `retry`: 是否重试（可选）
+    /// - `job`: 任务信息, 含创建人及其所属部门
     ///
     /// # 返回
     /// - 成功：返回创建的任务ID
     /// - 失败：返回数据库错误
-    pub async fn create(
-        name: String,
-        class: String,
-        cron: String,
-        queue: Option<String>,
-        args: Option<String>,
-        retry: Option<bool>,
-    ) -> Result<i64, DbErr> {
+    pub async fn create(job: CreateJobDto) -> Result<i64, DbErr> {
         let db = get_db().await;
         let now = Local::now().naive_local();
         let id = next_id();
 
-        let job = corn_job::ActiveModel {
+        let model = corn_job::ActiveModel {
             id: Set(id),
-            name: Set(name),
-            class: Set(class),
-            cron: Set(cron),
-            queue: Set(queue),
-            args: Set(args),
-            retry: Set(retry),
+            name: Set(job.name),
+            class: Set(job.class),
+            cron: Set(job.cron),
+            queue: Set(job.queue),
+            args: Set(job.args),
+            retry: Set(job.retry),
             created_at: Set(now),
             updated_at: Set(now),
-            ..Default::default()
+            tenant_id: Set(tenant_or_default()),
+            dept_id: Set(job.dept_id),
+            create_by: Set(job.create_by),
         };
 
-        corn_job::Entity::insert(job).exec(db).await?;
+        corn_job::Entity::insert(model).exec(db).await?;
         Ok(id)
     }
 
@@ -58,7 +52,10 @@ impl corn_job::Model {
     /// - 失败：返回数据库错误
     pub async fn delete_by_id(id: i64) -> Result<(), DbErr> {
         let db = get_db().await;
-        corn_job::Entity::delete_by_id(id).exec(db).await?;
+        corn_job::Entity::delete_by_id(id)
+            .tenant_scoped(corn_job::Column::TenantId)
+            .exec(db)
+            .await?;
         Ok(())
     }
 
@@ -114,6 +111,7 @@ impl corn_job::Model {
 
         corn_job::Entity::update(job)
             .filter(corn_job::Column::Id.eq(id))
+            .tenant_scoped(corn_job::Column::TenantId)
             .exec(db)
             .await?;
         Ok(())
@@ -129,7 +127,10 @@ impl corn_job::Model {
     /// - 失败：返回数据库错误
     pub async fn find_by_id(id: i64) -> Result<Option<Self>, DbErr> {
         let db = get_db().await;
-        corn_job::Entity::find_by_id(id).one(db).await
+        corn_job::Entity::find_by_id(id)
+            .tenant_scoped(corn_job::Column::TenantId)
+            .one(db)
+            .await
     }
 
     /// 查询定时任务列表（支持分页）
@@ -137,13 +138,20 @@ impl corn_job::Model {
     /// # 参数
     /// - `page`: 页码（从1开始）
     /// - `page_size`: 每页大小
+    /// - `scope`: 数据权限
     ///
     /// # 返回
     /// - 成功：返回任务列表
     /// - 失败：返回数据库错误
-    pub async fn list(page: Option<u64>, page_size: Option<u64>) -> Result<Vec<Self>, DbErr> {
+    pub async fn list(
+        page: Option<u64>,
+        page_size: Option<u64>,
+        scope: &DataScope,
+    ) -> Result<Vec<Self>, DbErr> {
         let db = get_db().await;
-        let mut query = corn_job::Entity::find();
+        let mut query = corn_job::Entity::find()
+            .tenant_scoped(corn_job::Column::TenantId)
+            .data_scoped(scope, corn_job::Column::DeptId, corn_job::Column::CreateBy);
 
         // 实现真正的分页
         if let (Some(page), Some(page_size)) = (page, page_size) {
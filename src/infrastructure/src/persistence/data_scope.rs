@@ -0,0 +1,20 @@
+use queryx::entity::DataScope;
+use sea_orm::{ColumnTrait, QueryFilter};
+
+/// 按数据权限追加过滤条件, 部门范围按部门列过滤, 仅本人按创建人列过滤
+pub trait DataScoped: QueryFilter + Sized {
+    fn data_scoped<D: ColumnTrait, O: ColumnTrait>(
+        self,
+        scope: &DataScope,
+        dept_column: D,
+        owner_column: O,
+    ) -> Self {
+        match scope {
+            DataScope::All => self,
+            DataScope::Depts(dept_ids) => self.filter(dept_column.is_in(dept_ids.clone())),
+            DataScope::Own(user_id) => self.filter(owner_column.eq(*user_id)),
+        }
+    }
+}
+
+impl<T: QueryFilter + Sized> DataScoped for T {}
@@ -15,6 +15,9 @@ pub struct Model {
     pub retry: Option<bool>,
     pub created_at: DateTime,
     pub updated_at: DateTime,
+    pub tenant_id: i64,
+    pub dept_id: Option<i64>,
+    pub create_by: i64,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -3,5 +3,11 @@
 pub mod prelude;
 
 pub mod corn_job;
+pub mod sys_dept;
+pub mod sys_login_log;
 pub mod sys_oper_log;
+pub mod user_api_keys;
+pub mod user_identities;
+pub mod user_password_history;
+pub mod user_sessions;
 pub mod users;
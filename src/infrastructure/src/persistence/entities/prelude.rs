@@ -1,5 +1,11 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.19
 
 pub use super::corn_job::Entity as CornJob;
+pub use super::sys_dept::Entity as SysDept;
+pub use super::sys_login_log::Entity as SysLoginLog;
 pub use super::sys_oper_log::Entity as SysOperLog;
+pub use super::user_api_keys::Entity as UserApiKeys;
+pub use super::user_identities::Entity as UserIdentities;
+pub use super::user_password_history::Entity as UserPasswordHistory;
+pub use super::user_sessions::Entity as UserSessions;
 pub use super::users::Entity as Users;
@@ -20,6 +20,19 @@ pub struct Model {
     pub json_result: String,
     pub oper_time: DateTime,
     pub cost_time: i64,
+    pub tenant_id: i64,
+    pub dept_id: Option<i64>,
+    pub status: i16,
+    #[sea_orm(column_type = "Text")]
+    pub oper_query: String,
+    pub http_status: i32,
+    pub biz_code: Option<i32>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub error_msg: Option<String>,
+    pub user_agent: String,
+    pub request_id: String,
+    pub title: String,
+    pub business_type: i16,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
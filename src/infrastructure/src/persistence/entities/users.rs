@@ -24,6 +24,18 @@ pub struct Model {
     pub updated_at: Option<DateTime>,
     pub update_by: i64,
     pub deleted_at: Option<DateTime>,
+    pub login_fail_count: i32,
+    pub locked_until: Option<DateTime>,
+    pub totp_secret: Option<String>,
+    pub totp_enabled: bool,
+    pub totp_recovery_codes: Option<String>,
+    pub totp_last_step: Option<i64>,
+    pub password_changed_at: Option<DateTime>,
+    pub auth_source: Option<String>,
+    pub tenant_id: i64,
+    pub dept_id: Option<i64>,
+    pub last_login_ip: Option<String>,
+    pub last_login_time: Option<DateTime>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -1,6 +1,14 @@
 pub mod corn_job;
+pub mod data_scope;
 pub mod entities;
 pub mod id_gen;
 pub mod init;
+pub mod sys_dept_repo;
+pub mod sys_login_log_repo;
 pub mod sys_oper_log_repo;
+pub mod tenant;
+pub mod user_api_key_repo;
+pub mod user_identity_repo;
+pub mod user_password_history_repo;
 pub mod user_repo;
+pub mod user_session_repo;
@@ -0,0 +1,16 @@
+use crate::persistence::entities::sys_dept;
+use crate::persistence::init::get_db;
+use crate::persistence::tenant::TenantScoped;
+use sea_orm::{DbErr, EntityTrait, QueryOrder};
+
+impl sys_dept::Model {
+    /// 当前租户的全部部门, 按排序号排列
+    pub async fn list() -> Result<Vec<Self>, DbErr> {
+        let db = get_db().await;
+        sys_dept::Entity::find()
+            .tenant_scoped(sys_dept::Column::TenantId)
+            .order_by_asc(sys_dept::Column::OrderNum)
+            .all(db)
+            .await
+    }
+}
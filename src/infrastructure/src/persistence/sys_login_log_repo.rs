@@ -0,0 +1,81 @@
+use crate::persistence::data_scope::DataScoped;
+use crate::persistence::entities::sys_login_log;
+use crate::persistence::id_gen::next_id;
+use crate::persistence::init::get_db;
+use crate::persistence::tenant::{TenantScoped, tenant_or};
+use login_log_domain::entity::LoginLog;
+use queryx::login_log::entity::ListLoginLogQo;
+use sea_orm::ActiveValue::Set;
+use sea_orm::{
+    ColumnTrait, DbErr, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, Select,
+};
+
+impl sys_login_log::Model {
+    pub async fn create(log: LoginLog) -> Result<i64, DbErr> {
+        let db = get_db().await;
+        let id = next_id();
+        let model = sys_login_log::ActiveModel {
+            id: Set(id),
+            user_id: Set(log.user_id),
+            username: Set(log.username),
+            login_type: Set(log.login_type),
+            ip: Set(log.ip),
+            user_agent: Set(log.user_agent),
+            status: Set(log.status),
+            fail_reason: Set(log.fail_reason),
+            captcha_used: Set(log.captcha_used),
+            two_factor_used: Set(log.two_factor_used),
+            request_id: Set(log.request_id),
+            tenant_id: Set(tenant_or(log.tenant_id)),
+            dept_id: Set(log.dept_id),
+            login_time: Set(log.login_time.naive_local()),
+        };
+        let _ = sys_login_log::Entity::insert(model).exec(db).await?;
+        Ok(id)
+    }
+
+    /// 按条件分页查询, 按登录时间倒序, 返回当前页及总数
+    pub async fn list(query: &ListLoginLogQo) -> Result<(Vec<Self>, u64), DbErr> {
+        let db = get_db().await;
+        let select = Self::filtered(query);
+        let total = select.clone().count(db).await?;
+
+        let mut select = select
+            .order_by_desc(sys_login_log::Column::LoginTime)
+            .order_by_desc(sys_login_log::Column::Id);
+        if let (Some(page), Some(page_size)) = (query.page_req.page, query.page_req.page_size) {
+            let offset = (page.max(1) - 1) * page_size;
+            select = select.offset(offset).limit(page_size);
+        }
+        Ok((select.all(db).await?, total))
+    }
+
+    fn filtered(query: &ListLoginLogQo) -> Select<sys_login_log::Entity> {
+        let mut select = sys_login_log::Entity::find()
+            .tenant_scoped(sys_login_log::Column::TenantId)
+            .data_scoped(
+                &query.data_scope,
+                sys_login_log::Column::DeptId,
+                sys_login_log::Column::UserId,
+            );
+        if let Some(username) = query.username.as_deref().filter(|s| !s.is_empty()) {
+            select = select.filter(sys_login_log::Column::Username.contains(username));
+        }
+        if let Some(user_id) = query.user_id {
+            select = select.filter(sys_login_log::Column::UserId.eq(user_id));
+        }
+        if let Some(ip) = query.ip.as_deref().filter(|s| !s.is_empty()) {
+            select = select.filter(sys_login_log::Column::Ip.eq(ip));
+        }
+        if let Some(status) = query.status {
+            select = select.filter(sys_login_log::Column::Status.eq(status));
+        }
+        if let Some(begin) = query.begin_time {
+            select = select.filter(sys_login_log::Column::LoginTime.gte(begin.naive_local()));
+        }
+        if let Some(end) = query.end_time {
+            select = select.filter(sys_login_log::Column::LoginTime.lt(end.naive_local()));
+        }
+        select
+    }
+}
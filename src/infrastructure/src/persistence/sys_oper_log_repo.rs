@@ -1,51 +1,147 @@
+use crate::persistence::data_scope::DataScoped;
 use crate::persistence::entities::sys_oper_log;
 use crate::persistence::id_gen::next_id;
 use crate::persistence::init::get_db;
-use chrono::Local;
+use crate::persistence::tenant::{TenantScoped, tenant_or};
+use chrono::{DateTime, Local};
+use operater_log_domain::entity::OperaterLog;
+use queryx::oper_log::entity::ListOperLogQo;
 use sea_orm::ActiveValue::Set;
-use sea_orm::{DbErr, EntityTrait};
+use sea_orm::{
+    ColumnTrait, DbErr, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, Select,
+};
 impl sys_oper_log::Model {
-    pub async fn create(
-        api_name: String,
-        request_method: String,
-        oper_id: i64,
-        oper_name: String,
-        oper_url: String,
-        oper_ip: String,
-        oper_location: String,
-        oper_param: String,
-        json_result: String,
-        cost_time: i64,
-    ) -> Result<i64, DbErr> {
+    pub async fn create(log: OperaterLog) -> Result<i64, DbErr> {
         let db = get_db().await;
         let id = next_id();
-        let log = sys_oper_log::ActiveModel {
-            id: Set(id),
-            api_name: Set(api_name),
-            request_method: Set(request_method),
-            oper_id: Set(oper_id),
-            oper_name: Set(oper_name),
-            oper_url: Set(oper_url),
-            oper_ip: Set(oper_ip),
-            oper_location: Set(oper_location),
-            oper_param: Set(oper_param),
-            json_result: Set(json_result),
-            oper_time: Set(Local::now().naive_local()),
-            cost_time: Set(cost_time),
-            ..Default::default()
-        };
-        let _ = sys_oper_log::Entity::insert(log).exec(db).await?;
+        let _ = sys_oper_log::Entity::insert(Self::active_model(id, log))
+            .exec(db)
+            .await?;
         Ok(id)
     }
 
+    /// 批量写入, 返回写入条数
+    pub async fn create_many(logs: Vec<OperaterLog>) -> Result<u64, DbErr> {
+        if logs.is_empty() {
+            return Ok(0);
+        }
+        let db = get_db().await;
+        let count = logs.len() as u64;
+        let models = logs.into_iter().map(|log| Self::active_model(next_id(), log));
+        sys_oper_log::Entity::insert_many(models).exec(db).await?;
+        Ok(count)
+    }
+
+    fn active_model(id: i64, log: OperaterLog) -> sys_oper_log::ActiveModel {
+        sys_oper_log::ActiveModel {
+            id: Set(id),
+            api_name: Set(log.api_name),
+            request_method: Set(log.request_method),
+            oper_id: Set(log.oper_id),
+            oper_name: Set(log.oper_name),
+            oper_url: Set(log.oper_url),
+            oper_ip: Set(log.oper_ip),
+            oper_location: Set(log.oper_location),
+            oper_param: Set(log.oper_param),
+            json_result: Set(log.json_result),
+            oper_time: Set(log.oper_time.naive_local()),
+            cost_time: Set(log.cost_time),
+            tenant_id: Set(tenant_or(log.tenant_id)),
+            dept_id: Set(log.dept_id),
+            status: Set(log.status),
+            oper_query: Set(log.oper_query),
+            http_status: Set(log.http_status),
+            biz_code: Set(log.biz_code),
+            error_msg: Set(log.error_msg),
+            user_agent: Set(log.user_agent),
+            request_id: Set(log.request_id),
+            title: Set(log.title),
+            business_type: Set(log.business_type.code()),
+        }
+    }
+
     pub async fn delete_by_id(id: i64) -> Result<(), DbErr> {
         let db = get_db().await;
-        sys_oper_log::Entity::delete_by_id(id).exec(db).await?;
+        sys_oper_log::Entity::delete_by_id(id)
+            .tenant_scoped(sys_oper_log::Column::TenantId)
+            .exec(db)
+            .await?;
         Ok(())
     }
 
-    pub async fn list() -> Result<Vec<Self>, DbErr> {
+    /// 删除指定时间之前的日志, 返回删除条数
+    pub async fn delete_before(before: DateTime<Local>) -> Result<u64, DbErr> {
+        let db = get_db().await;
+        let res = sys_oper_log::Entity::delete_many()
+            .filter(sys_oper_log::Column::OperTime.lt(before.naive_local()))
+            .tenant_scoped(sys_oper_log::Column::TenantId)
+            .exec(db)
+            .await?;
+        Ok(res.rows_affected)
+    }
+
+    pub async fn find_by_id(id: i64) -> Result<Option<Self>, DbErr> {
+        let db = get_db().await;
+        sys_oper_log::Entity::find_by_id(id)
+            .tenant_scoped(sys_oper_log::Column::TenantId)
+            .one(db)
+            .await
+    }
+
+    /// 按条件分页查询, 按操作时间倒序, 返回当前页及总数
+    pub async fn list(query: &ListOperLogQo) -> Result<(Vec<Self>, u64), DbErr> {
         let db = get_db().await;
-        sys_oper_log::Entity::find().all(db).await
+        let select = Self::filtered(query);
+        let total = select.clone().count(db).await?;
+
+        let mut select = select
+            .order_by_desc(sys_oper_log::Column::OperTime)
+            .order_by_desc(sys_oper_log::Column::Id);
+        if let (Some(page), Some(page_size)) = (query.page_req.page, query.page_req.page_size) {
+            let offset = (page.max(1) - 1) * page_size;
+            select = select.offset(offset).limit(page_size);
+        }
+        Ok((select.all(db).await?, total))
+    }
+
+    fn filtered(query: &ListOperLogQo) -> Select<sys_oper_log::Entity> {
+        let mut select = sys_oper_log::Entity::find()
+            .tenant_scoped(sys_oper_log::Column::TenantId)
+            .data_scoped(
+                &query.data_scope,
+                sys_oper_log::Column::DeptId,
+                sys_oper_log::Column::OperId,
+            );
+        if let Some(oper_id) = query.oper_id {
+            select = select.filter(sys_oper_log::Column::OperId.eq(oper_id));
+        }
+        if let Some(api_name) = query.api_name.as_deref().filter(|s| !s.is_empty()) {
+            select = select.filter(sys_oper_log::Column::ApiName.contains(api_name));
+        }
+        if let Some(title) = query.title.as_deref().filter(|s| !s.is_empty()) {
+            select = select.filter(sys_oper_log::Column::Title.contains(title));
+        }
+        if let Some(business_type) = query.business_type {
+            select = select.filter(sys_oper_log::Column::BusinessType.eq(business_type));
+        }
+        if let Some(method) = query.request_method.as_deref().filter(|s| !s.is_empty()) {
+            select = select.filter(sys_oper_log::Column::RequestMethod.eq(method.to_uppercase()));
+        }
+        if let Some(begin) = query.begin_time {
+            select = select.filter(sys_oper_log::Column::OperTime.gte(begin.naive_local()));
+        }
+        if let Some(end) = query.end_time {
+            select = select.filter(sys_oper_log::Column::OperTime.lt(end.naive_local()));
+        }
+        if let Some(status) = query.status {
+            select = select.filter(sys_oper_log::Column::Status.eq(status));
+        }
+        if let Some(request_id) = query.request_id.as_deref().filter(|s| !s.is_empty()) {
+            select = select.filter(sys_oper_log::Column::RequestId.eq(request_id));
+        }
+        if let Some(min_cost_time) = query.min_cost_time {
+            select = select.filter(sys_oper_log::Column::CostTime.gte(min_cost_time));
+        }
+        select
     }
 }
@@ -0,0 +1,24 @@
+use commonx::tenant::{DEFAULT_TENANT_ID, current_tenant};
+use sea_orm::{ColumnTrait, QueryFilter};
+
+/// 处于租户上下文时追加租户过滤条件, 查询、更新、删除都需要调用
+pub trait TenantScoped: QueryFilter + Sized {
+    fn tenant_scoped<C: ColumnTrait>(self, column: C) -> Self {
+        match current_tenant() {
+            Some(tenant_id) => self.filter(column.eq(tenant_id)),
+            None => self,
+        }
+    }
+}
+
+impl<T: QueryFilter + Sized> TenantScoped for T {}
+
+/// 新增数据所属的租户, 优先使用当前租户上下文
+pub fn tenant_or(tenant_id: i64) -> i64 {
+    current_tenant().unwrap_or(tenant_id)
+}
+
+/// 新增数据所属的租户, 没有租户上下文时归属默认租户
+pub fn tenant_or_default() -> i64 {
+    tenant_or(DEFAULT_TENANT_ID)
+}
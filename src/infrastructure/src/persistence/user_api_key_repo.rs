@@ -0,0 +1,74 @@
+use chrono::Local;
+use sea_orm::ActiveValue::Set;
+use sea_orm::{ColumnTrait, DbErr, EntityTrait, QueryFilter, QueryOrder};
+use user_domain::entity::api_key::ApiKey;
+
+use crate::persistence::entities::user_api_keys;
+use crate::persistence::id_gen::next_id;
+use crate::persistence::init::get_db;
+
+impl user_api_keys::Model {
+    pub async fn find_by_prefix(prefix: &str) -> Result<Option<Self>, DbErr> {
+        let db = get_db().await;
+        user_api_keys::Entity::find()
+            .filter(user_api_keys::Column::Prefix.eq(prefix))
+            .one(db)
+            .await
+    }
+
+    pub async fn find_by_user(user_id: i64) -> Result<Vec<Self>, DbErr> {
+        let db = get_db().await;
+        user_api_keys::Entity::find()
+            .filter(user_api_keys::Column::UserId.eq(user_id))
+            .order_by_desc(user_api_keys::Column::CreatedAt)
+            .all(db)
+            .await
+    }
+
+    pub async fn create(api_key: ApiKey) -> Result<i64, DbErr> {
+        let db = get_db().await;
+        let id = next_id();
+        let model = user_api_keys::ActiveModel {
+            id: Set(id),
+            user_id: Set(api_key.user_id),
+            name: Set(api_key.name),
+            prefix: Set(api_key.prefix),
+            key_hash: Set(api_key.key_hash),
+            scopes: Set(serde_json::to_string(&api_key.scopes).unwrap_or_default()),
+            expires_at: Set(api_key.expires_at.map(|t| t.naive_local())),
+            last_used_at: Set(None),
+            revoked_at: Set(None),
+            created_at: Set(Local::now().naive_local()),
+        };
+        user_api_keys::Entity::insert(model).exec(db).await?;
+        Ok(id)
+    }
+
+    /// 吊销未吊销的key, 返回是否有记录被更新
+    pub async fn revoke(id: i64, user_id: i64) -> Result<bool, DbErr> {
+        let db = get_db().await;
+        let model = user_api_keys::ActiveModel {
+            revoked_at: Set(Some(Local::now().naive_local())),
+            ..Default::default()
+        };
+        let ret = user_api_keys::Entity::update_many()
+            .set(model)
+            .filter(user_api_keys::Column::Id.eq(id))
+            .filter(user_api_keys::Column::UserId.eq(user_id))
+            .filter(user_api_keys::Column::RevokedAt.is_null())
+            .exec(db)
+            .await?;
+        Ok(ret.rows_affected > 0)
+    }
+
+    pub async fn touch(id: i64) -> Result<(), DbErr> {
+        let db = get_db().await;
+        let model = user_api_keys::ActiveModel {
+            id: Set(id),
+            last_used_at: Set(Some(Local::now().naive_local())),
+            ..Default::default()
+        };
+        user_api_keys::Entity::update(model).exec(db).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,38 @@
+use chrono::Local;
+use sea_orm::ActiveValue::Set;
+use sea_orm::{ColumnTrait, DbErr, EntityTrait, QueryFilter};
+
+use crate::persistence::entities::user_identities;
+use crate::persistence::id_gen::next_id;
+use crate::persistence::init::get_db;
+
+impl user_identities::Model {
+    pub async fn find_by_subject(provider: &str, subject: &str) -> Result<Option<Self>, DbErr> {
+        let db = get_db().await;
+        user_identities::Entity::find()
+            .filter(user_identities::Column::Provider.eq(provider))
+            .filter(user_identities::Column::Subject.eq(subject))
+            .one(db)
+            .await
+    }
+
+    pub async fn create(
+        user_id: i64,
+        provider: String,
+        subject: String,
+        email: Option<String>,
+    ) -> Result<i64, DbErr> {
+        let db = get_db().await;
+        let id = next_id();
+        let identity = user_identities::ActiveModel {
+            id: Set(id),
+            user_id: Set(user_id),
+            provider: Set(provider),
+            subject: Set(subject),
+            email: Set(email),
+            created_at: Set(Local::now().naive_local()),
+        };
+        user_identities::Entity::insert(identity).exec(db).await?;
+        Ok(id)
+    }
+}
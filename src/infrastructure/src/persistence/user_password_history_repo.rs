@@ -0,0 +1,36 @@
+use chrono::Local;
+use sea_orm::ActiveValue::Set;
+use sea_orm::{ColumnTrait, DbErr, EntityTrait, QueryFilter, QueryOrder, QuerySelect};
+
+use crate::persistence::entities::user_password_history;
+use crate::persistence::id_gen::next_id;
+use crate::persistence::init::get_db;
+
+impl user_password_history::Model {
+    /// 获取用户最近使用过的密码哈希
+    pub async fn find_recent(user_id: i64, limit: u64) -> Result<Vec<String>, DbErr> {
+        let db = get_db().await;
+        let list = user_password_history::Entity::find()
+            .filter(user_password_history::Column::UserId.eq(user_id))
+            .order_by_desc(user_password_history::Column::CreatedAt)
+            .limit(limit)
+            .all(db)
+            .await?;
+        Ok(list.into_iter().map(|h| h.password).collect())
+    }
+
+    pub async fn create(user_id: i64, password: String) -> Result<i64, DbErr> {
+        let db = get_db().await;
+        let id = next_id();
+        let history = user_password_history::ActiveModel {
+            id: Set(id),
+            user_id: Set(user_id),
+            password: Set(password),
+            created_at: Set(Local::now().naive_local()),
+        };
+        user_password_history::Entity::insert(history)
+            .exec(db)
+            .await?;
+        Ok(id)
+    }
+}
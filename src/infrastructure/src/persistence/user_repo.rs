@@ -1,23 +1,37 @@
-use chrono::Local;
+use chrono::{DateTime, Local};
 use sea_orm::ActiveValue::Set;
-use sea_orm::{ColumnTrait, DbErr, EntityTrait, QueryFilter};
+use sea_orm::{ColumnTrait, Condition, DbErr, EntityTrait, QueryFilter};
 
 use crate::persistence::entities::users;
 use crate::persistence::id_gen::next_id;
 use crate::persistence::init::get_db;
+use crate::persistence::tenant::{TenantScoped, tenant_or};
 
 impl users::Model {
     pub async fn find_by_username(username: &str) -> Result<Option<Self>, DbErr> {
         let db = get_db().await;
         users::Entity::find()
             .filter(users::Column::Username.eq(username))
+            .tenant_scoped(users::Column::TenantId)
+            .one(db)
+            .await
+    }
+
+    pub async fn find_by_email(email: &str) -> Result<Option<Self>, DbErr> {
+        let db = get_db().await;
+        users::Entity::find()
+            .filter(users::Column::Email.eq(email))
+            .tenant_scoped(users::Column::TenantId)
             .one(db)
             .await
     }
 
     pub async fn find_by_id(id: i64) -> Result<Option<Self>, DbErr> {
         let db = get_db().await;
-        users::Entity::find_by_id(id).one(db).await
+        users::Entity::find_by_id(id)
+            .tenant_scoped(users::Column::TenantId)
+            .one(db)
+            .await
     }
 
     pub async fn create(user: user_domain::entity::user::User) -> Result<i64, DbErr> {
@@ -40,7 +54,10 @@ impl users::Model {
             updated_at: Set(Option::Some(Local::now().naive_local())),
             create_by: Set(user.create_by.unwrap_or_default()),
             update_by: Set(user.update_by.unwrap_or_default()),
-
+            password_changed_at: Set(user.password_changed_at.map(|t| t.naive_local())),
+            auth_source: Set(user.auth_source),
+            tenant_id: Set(tenant_or(user.tenant_id)),
+            dept_id: Set(user.dept_id),
             ..Default::default()
         };
         let ret = users::Entity::insert(u).exec(db).await?;
@@ -67,14 +84,174 @@ impl users::Model {
         };
         let _ = users::Entity::update(u)
             .filter(users::Column::Id.eq(id))
+            .tenant_scoped(users::Column::TenantId)
+            .exec(db)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn update_login_lock(
+        id: i64,
+        login_fail_count: i32,
+        locked_until: Option<DateTime<Local>>,
+    ) -> Result<(), DbErr> {
+        let db = get_db().await;
+        let u = users::ActiveModel {
+            id: Set(id),
+            login_fail_count: Set(login_fail_count),
+            locked_until: Set(locked_until.map(|t| t.naive_local())),
+            ..Default::default()
+        };
+        let _ = users::Entity::update(u)
+            .filter(users::Column::Id.eq(id))
+            .tenant_scoped(users::Column::TenantId)
+            .exec(db)
+            .await?;
+        Ok(())
+    }
+
+    /// 更新最近一次登录的IP及时间
+    pub async fn update_last_login(
+        id: i64,
+        ip: String,
+        login_time: DateTime<Local>,
+    ) -> Result<(), DbErr> {
+        let db = get_db().await;
+        let u = users::ActiveModel {
+            id: Set(id),
+            last_login_ip: Set(Some(ip)),
+            last_login_time: Set(Some(login_time.naive_local())),
+            ..Default::default()
+        };
+        let _ = users::Entity::update(u)
+            .filter(users::Column::Id.eq(id))
+            .tenant_scoped(users::Column::TenantId)
+            .exec(db)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn update_status(id: i64, status: String) -> Result<(), DbErr> {
+        let db = get_db().await;
+        let u = users::ActiveModel {
+            id: Set(id),
+            status: Set(Some(status)),
+            updated_at: Set(Some(Local::now().naive_local())),
+            ..Default::default()
+        };
+        let _ = users::Entity::update(u)
+            .filter(users::Column::Id.eq(id))
+            .tenant_scoped(users::Column::TenantId)
             .exec(db)
             .await?;
         Ok(())
     }
 
+    pub async fn update_profile(
+        id: i64,
+        name: Option<String>,
+        email: Option<String>,
+        phone: Option<String>,
+        role_id: Option<i64>,
+    ) -> Result<(), DbErr> {
+        let db = get_db().await;
+        let mut u = users::ActiveModel {
+            id: Set(id),
+            updated_at: Set(Some(Local::now().naive_local())),
+            ..Default::default()
+        };
+        if let Some(name) = name {
+            u.name = Set(name);
+        }
+        if email.is_some() {
+            u.email = Set(email);
+        }
+        if phone.is_some() {
+            u.phone = Set(phone);
+        }
+        if role_id.is_some() {
+            u.role_id = Set(role_id);
+        }
+        let _ = users::Entity::update(u)
+            .filter(users::Column::Id.eq(id))
+            .tenant_scoped(users::Column::TenantId)
+            .exec(db)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn update_password(
+        id: i64,
+        password: String,
+        changed_at: Option<DateTime<Local>>,
+    ) -> Result<(), DbErr> {
+        let db = get_db().await;
+        let mut u = users::ActiveModel {
+            id: Set(id),
+            password: Set(password),
+            ..Default::default()
+        };
+        if let Some(changed_at) = changed_at {
+            u.password_changed_at = Set(Some(changed_at.naive_local()));
+            u.updated_at = Set(Some(changed_at.naive_local()));
+        }
+        let _ = users::Entity::update(u)
+            .filter(users::Column::Id.eq(id))
+            .tenant_scoped(users::Column::TenantId)
+            .exec(db)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn update_totp(
+        id: i64,
+        totp_secret: Option<String>,
+        totp_enabled: bool,
+        totp_recovery_codes: Option<String>,
+    ) -> Result<(), DbErr> {
+        let db = get_db().await;
+        let u = users::ActiveModel {
+            id: Set(id),
+            totp_secret: Set(totp_secret),
+            totp_enabled: Set(totp_enabled),
+            totp_recovery_codes: Set(totp_recovery_codes),
+            ..Default::default()
+        };
+        let _ = users::Entity::update(u)
+            .filter(users::Column::Id.eq(id))
+            .tenant_scoped(users::Column::TenantId)
+            .exec(db)
+            .await?;
+        Ok(())
+    }
+
+    /// 记录通过校验的TOTP时间窗口, 窗口不晚于已记录的窗口时返回false
+    pub async fn consume_totp_step(id: i64, step: i64) -> Result<bool, DbErr> {
+        let db = get_db().await;
+        let model = users::ActiveModel {
+            totp_last_step: Set(Some(step)),
+            ..Default::default()
+        };
+        let ret = users::Entity::update_many()
+            .set(model)
+            .filter(users::Column::Id.eq(id))
+            .filter(
+                Condition::any()
+                    .add(users::Column::TotpLastStep.is_null())
+                    .add(users::Column::TotpLastStep.lt(step)),
+            )
+            .tenant_scoped(users::Column::TenantId)
+            .exec(db)
+            .await?;
+        Ok(ret.rows_affected > 0)
+    }
+
     pub async fn delete_by_id(id: i64) -> Result<(), DbErr> {
         let db = get_db().await;
-        users::Entity::delete_by_id(id).exec(db).await?;
+        users::Entity::delete_by_id(id)
+            .tenant_scoped(users::Column::TenantId)
+            .exec(db)
+            .await?;
         Ok(())
     }
 }
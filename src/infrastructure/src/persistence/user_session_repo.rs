@@ -0,0 +1,128 @@
+use chrono::{DateTime, Local};
+use sea_orm::ActiveValue::Set;
+use sea_orm::{ColumnTrait, DbErr, EntityTrait, QueryFilter, QueryOrder, QuerySelect};
+
+use crate::persistence::entities::user_sessions;
+use crate::persistence::init::get_db;
+use crate::persistence::tenant::{TenantScoped, tenant_or};
+
+impl user_sessions::Model {
+    pub async fn create(
+        token_id: i64,
+        user_id: i64,
+        tenant_id: i64,
+        username: String,
+        ip: String,
+        user_agent: String,
+        expires_at: DateTime<Local>,
+    ) -> Result<(), DbErr> {
+        let db = get_db().await;
+        let now = Local::now().naive_local();
+        let model = user_sessions::ActiveModel {
+            token_id: Set(token_id),
+            user_id: Set(user_id),
+            username: Set(username),
+            ip: Set(ip),
+            // 超长的User-Agent截断保存
+            user_agent: Set(user_agent.chars().take(512).collect()),
+            created_at: Set(now),
+            last_seen_at: Set(Some(now)),
+            expires_at: Set(expires_at.naive_local()),
+            revoked_at: Set(None),
+            tenant_id: Set(tenant_or(tenant_id)),
+        };
+        user_sessions::Entity::insert(model).exec(db).await?;
+        Ok(())
+    }
+
+    pub async fn find_by_token_id(token_id: i64) -> Result<Option<Self>, DbErr> {
+        let db = get_db().await;
+        user_sessions::Entity::find_by_id(token_id)
+            .tenant_scoped(user_sessions::Column::TenantId)
+            .one(db)
+            .await
+    }
+
+    /// 分页查询未吊销且未过期的会话, user_id为空时查询所有用户
+    pub async fn list_active(
+        user_id: Option<i64>,
+        page: u64,
+        page_size: u64,
+    ) -> Result<Vec<Self>, DbErr> {
+        let db = get_db().await;
+        let mut query = user_sessions::Entity::find()
+            .filter(user_sessions::Column::RevokedAt.is_null())
+            .filter(user_sessions::Column::ExpiresAt.gt(Local::now().naive_local()))
+            .tenant_scoped(user_sessions::Column::TenantId);
+        if let Some(user_id) = user_id {
+            query = query.filter(user_sessions::Column::UserId.eq(user_id));
+        }
+        query
+            .order_by_desc(user_sessions::Column::LastSeenAt)
+            .offset((page.max(1) - 1) * page_size)
+            .limit(page_size)
+            .all(db)
+            .await
+    }
+
+    /// 吊销未吊销的会话, 返回是否有记录被更新
+    pub async fn revoke(token_id: i64) -> Result<bool, DbErr> {
+        let db = get_db().await;
+        let model = user_sessions::ActiveModel {
+            revoked_at: Set(Some(Local::now().naive_local())),
+            ..Default::default()
+        };
+        let ret = user_sessions::Entity::update_many()
+            .set(model)
+            .filter(user_sessions::Column::TokenId.eq(token_id))
+            .filter(user_sessions::Column::RevokedAt.is_null())
+            .tenant_scoped(user_sessions::Column::TenantId)
+            .exec(db)
+            .await?;
+        Ok(ret.rows_affected > 0)
+    }
+
+    /// 吊销用户全部未吊销且未过期的会话, 返回被吊销的token_id
+    pub async fn revoke_by_user(user_id: i64) -> Result<Vec<i64>, DbErr> {
+        let db = get_db().await;
+        let token_ids: Vec<i64> = user_sessions::Entity::find()
+            .select_only()
+            .column(user_sessions::Column::TokenId)
+            .filter(user_sessions::Column::UserId.eq(user_id))
+            .filter(user_sessions::Column::RevokedAt.is_null())
+            .filter(user_sessions::Column::ExpiresAt.gt(Local::now().naive_local()))
+            .tenant_scoped(user_sessions::Column::TenantId)
+            .into_tuple()
+            .all(db)
+            .await?;
+        if token_ids.is_empty() {
+            return Ok(token_ids);
+        }
+        let model = user_sessions::ActiveModel {
+            revoked_at: Set(Some(Local::now().naive_local())),
+            ..Default::default()
+        };
+        user_sessions::Entity::update_many()
+            .set(model)
+            .filter(user_sessions::Column::TokenId.is_in(token_ids.clone()))
+            .filter(user_sessions::Column::RevokedAt.is_null())
+            .tenant_scoped(user_sessions::Column::TenantId)
+            .exec(db)
+            .await?;
+        Ok(token_ids)
+    }
+
+    pub async fn touch(token_id: i64) -> Result<(), DbErr> {
+        let db = get_db().await;
+        let model = user_sessions::ActiveModel {
+            token_id: Set(token_id),
+            last_seen_at: Set(Some(Local::now().naive_local())),
+            ..Default::default()
+        };
+        user_sessions::Entity::update(model)
+            .tenant_scoped(user_sessions::Column::TenantId)
+            .exec(db)
+            .await?;
+        Ok(())
+    }
+}
@@ -1,6 +1,8 @@
+use crate::cron_scheduled::builtin::init_builtin_jobs;
 use crate::processor::processor::Processor;
 use crate::processor::wokers::job_worker::JobWorker;
 use crate::processor::wokers::mail_worker::MailerWorker;
+use crate::processor::wokers::oper_log_retention_worker::OperLogRetentionWorker;
 use crate::processor::worker::AppWorker;
 use commonx::config::APP_CONFIG;
 use commonx::error::AppError;
@@ -10,7 +12,7 @@ pub const DEFAULT_QUEUE: &[&str] = &["default"];
 
 pub async fn init_worker() -> Result<(), AppError> {
     init_base_worker().await?;
-    // init_corn_schedule().await?;
+    init_builtin_jobs().await?;
     Ok(())
 }
 
@@ -29,6 +31,7 @@ pub async fn init_base_worker() -> Result<(), AppError> {
     let mut processor = Processor::new(queues, sched_queues, worker_config.num_workers);
     processor.register(JobWorker::new());
     processor.register(MailerWorker::new());
+    processor.register(OperLogRetentionWorker::new());
 
     tokio::spawn(async move {
         processor.run().await;
@@ -15,6 +15,9 @@ pub struct Job {
     pub error_message: Option<String>,
     pub retry_count: Option<usize>,
     pub retried_at: Option<f64>,
+    /// 加入队列时所在请求的请求ID, 用于关联日志
+    #[serde(default)]
+    pub request_id: Option<String>,
 
     #[serde(skip)]
     pub unique_for: Option<Duration>,
@@ -3,7 +3,7 @@ mod job;
 mod processor;
 mod scheduled;
 mod unit_of_work;
-mod wokers;
-mod worker;
+pub(crate) mod wokers;
+pub(crate) mod worker;
 
 static MODULE_NAME: &str = "[processor]";
@@ -4,11 +4,13 @@ use crate::processor::scheduled::SortedScheduledWork;
 use crate::processor::unit_of_work::UnitOfWork;
 use crate::processor::worker::{Worker, WorkerRef};
 use commonx::error::AppError;
+use commonx::request_id::with_request_id;
 use commonx::{web_error, web_info};
 use std::collections::BTreeMap;
 use std::sync::Arc;
 use tokio::select;
 use tokio_util::sync::CancellationToken;
+use tracing::Instrument;
 
 // 循环任务??
 const SCHEDULE_SECONDS: u64 = 5;
@@ -142,13 +144,24 @@ impl Processor {
         let work = work.unwrap();
         if let Some(worker) = self.workers.get(&work.job.class) {
             let worker = worker.clone();
-            match worker.call(work.job.args).await {
+            // 沿用加入队列时的请求ID, 任务日志可与请求关联
+            let result = match work.job.request_id.clone() {
+                Some(request_id) => {
+                    let span = tracing::info_span!("job", request_id = %request_id);
+                    with_request_id(request_id, worker.call(work.job.args))
+                        .instrument(span)
+                        .await
+                }
+                None => worker.call(work.job.args).await,
+            };
+            match result {
                 Ok(_) => {}
                 Err(err) => {
                     web_error!({
                         "status" = "fail",
                         "class"  = &work.job.class,
                         "queue"  = &work.job.queue,
+                        "request_id" = work.job.request_id.as_deref().unwrap_or(""),
                         "err"    = format!("{:?}", err)
                     }," -- 进程 {} 处理失败: {:?}", work.job.class, err);
                 }
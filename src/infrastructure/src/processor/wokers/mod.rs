@@ -1,2 +1,3 @@
 pub mod job_worker;
 pub mod mail_worker;
+pub mod oper_log_retention_worker;
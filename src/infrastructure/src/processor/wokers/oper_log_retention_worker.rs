@@ -0,0 +1,88 @@
+use std::path::Path;
+
+use async_trait::async_trait;
+use chrono::{Duration, Local};
+use commonx::config::APP_CONFIG;
+use commonx::error::AppError;
+use commonx::web_info;
+use operater_log_domain::api::traits::OperaterLogDomainTrait;
+use queryx::entity::PageReq;
+use queryx::oper_log::api::OperLogQueryTrait;
+use queryx::oper_log::entity::ListOperLogQo;
+use queryx::oper_log::services::to_csv;
+use tokio::io::AsyncWriteExt;
+
+use crate::container::operater_log_domain::{
+    new_oper_log_query_service, new_operater_log_domain_service,
+};
+use crate::processor::worker::{AppWorker, Worker};
+
+/// 每批归档的日志条数
+const ARCHIVE_BATCH: u64 = 1000;
+
+/// 操作日志保留策略, 由内置定时任务触发, 清理超过保留天数的日志
+pub struct OperLogRetentionWorker {}
+
+impl AppWorker for OperLogRetentionWorker {
+    fn new() -> Self {
+        Self {}
+    }
+}
+
+#[async_trait]
+impl Worker for OperLogRetentionWorker {
+    async fn perform(&self, _args: serde_json::Value) -> Result<(), AppError> {
+        let config = &APP_CONFIG.oper_log.retention;
+        let before = Local::now() - Duration::days(config.days);
+        if config.archive {
+            let archived = archive_before(before, &config.archive_dir).await?;
+            web_info!(" -- 操作日志归档: {}条", archived);
+        }
+        let count = new_operater_log_domain_service()
+            .clean_before(before)
+            .await?;
+        web_info!(" -- 操作日志清理: 保留{}天, 删除{}条", config.days, count);
+        Ok(())
+    }
+}
+
+/// 将过期日志分批追加写入归档目录下按日期命名的CSV文件
+async fn archive_before(before: chrono::DateTime<Local>, dir: &str) -> Result<u64, AppError> {
+    let query = new_oper_log_query_service();
+    tokio::fs::create_dir_all(dir).await?;
+    let path = Path::new(dir).join(format!("sys_oper_log_{}.csv", before.format("%Y%m%d")));
+    let with_header = !tokio::fs::try_exists(&path).await?;
+    let mut file = tokio::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .await?;
+
+    let mut archived = 0;
+    let mut page = 1;
+    loop {
+        let res = query
+            .list(ListOperLogQo {
+                page_req: PageReq {
+                    page: Some(page),
+                    page_size: Some(ARCHIVE_BATCH),
+                },
+                end_time: Some(before),
+                ..Default::default()
+            })
+            .await?;
+        let n = res.list.len() as u64;
+        if n == 0 {
+            break;
+        }
+        file.write_all(to_csv(&res.list, with_header && page == 1).as_bytes())
+            .await?;
+        archived += n;
+        if n < ARCHIVE_BATCH {
+            break;
+        }
+        page += 1;
+    }
+    file.flush().await?;
+    Ok(archived)
+}
@@ -1,7 +1,9 @@
 use async_trait::async_trait;
-use commonx::error::AppError;
+use commonx::{error::AppError, request_id::current_request_id};
 use serde_json::Value as JsonValue;
 
+use crate::processor::{init::DEFAULT_QUEUE, job::Job, unit_of_work::UnitOfWork};
+
 #[async_trait]
 pub trait Worker: Send + Sync {
     fn disable_argument_coercion(&self) -> bool {
@@ -34,8 +36,25 @@ pub trait AppWorker: Worker {
         todo!()
     }
     // 异步加入队列
-    async fn enqueue_async(args: JsonValue) -> Result<(), AppError> {
-        todo!()
+    async fn enqueue_async(args: JsonValue) -> Result<(), AppError>
+    where
+        Self: Sized,
+    {
+        let job = Job {
+            queue: DEFAULT_QUEUE[0].to_string(),
+            args,
+            retry: false,
+            class: Self::class_name(),
+            created_at: chrono::Local::now().timestamp() as f64,
+            enqueued_at: None,
+            failed_at: None,
+            error_message: None,
+            retry_count: None,
+            retried_at: None,
+            request_id: current_request_id(),
+            unique_for: None,
+        };
+        UnitOfWork::from(job).enqueue().await
     }
     // 异步执行
     async fn execute_async(args: JsonValue) -> Result<(), AppError> {
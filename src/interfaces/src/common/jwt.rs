@@ -1,10 +1,11 @@
 use chrono::Local;
 use commonx::config::APP_CONFIG;
 use commonx::error::AppError;
-use jsonwebtoken::{Header, encode};
+use hyper::header::HeaderValue;
+use jsonwebtoken::encode;
 use serde::{Deserialize, Serialize};
 
-use crate::types::{auth_jwt::Claims, user_info::KEYS};
+use crate::{common::jwt_keys::KEYS, types::auth_jwt::Claims};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct AuthBody {
@@ -30,7 +31,7 @@ pub async fn authorize(mut payload: Claims) -> Result<AuthBody, AppError> {
     let exp = now.timestamp() + APP_CONFIG.auth.jwt.expiration;
     payload.exp = exp;
     // Create the authorization token
-    let token = encode(&Header::default(), &payload, &KEYS.encoding)
+    let token = encode(&KEYS.header(), &payload, KEYS.encoding())
         .map_err(|_| AppError::AuthError("授权错误".to_string()))?;
     // Send the authorized token
     Ok(AuthBody::new(
@@ -39,3 +40,26 @@ pub async fn authorize(mut payload: Claims) -> Result<AuthBody, AppError> {
         APP_CONFIG.auth.jwt.expiration,
     ))
 }
+
+/// 配置了Cookie认证时, 生成写入token的Set-Cookie头
+pub fn auth_cookie(token: &str) -> Option<HeaderValue> {
+    let jwt_config = &APP_CONFIG.auth.jwt;
+    let name = jwt_config.cookie_name()?;
+    http_only_cookie(&name, token, jwt_config.expiration)
+}
+
+/// 生成HttpOnly的Set-Cookie头, 路径、域名等属性与token的Cookie一致, max_age为0时删除Cookie
+pub fn http_only_cookie(name: &str, value: &str, max_age: i64) -> Option<HeaderValue> {
+    let cookie_config = &APP_CONFIG.auth.jwt.cookie;
+    let mut cookie = format!(
+        "{}={}; Path={}; Max-Age={}; HttpOnly; SameSite={}",
+        name, value, cookie_config.path, max_age, cookie_config.same_site
+    );
+    if let Some(domain) = cookie_config.domain.as_ref() {
+        cookie.push_str(&format!("; Domain={}", domain));
+    }
+    if cookie_config.secure {
+        cookie.push_str("; Secure");
+    }
+    HeaderValue::from_str(&cookie).ok()
+}
@@ -0,0 +1,385 @@
+use std::{collections::HashMap, fs};
+
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use chrono::Local;
+use commonx::config::{
+    APP_CONFIG,
+    config::{JWT, JWTAlgorithm},
+};
+use commonx::error::AppError;
+use jsonwebtoken::{
+    Algorithm, DecodingKey, EncodingKey, Header, Validation, decode, decode_header, encode,
+    jwk::{
+        AlgorithmParameters, CommonParameters, EllipticCurve, EllipticCurveKeyParameters,
+        EllipticCurveKeyType, Jwk, JwkSet, KeyAlgorithm, OctetKeyPairParameters, OctetKeyPairType,
+        PublicKeyUse, RSAKeyParameters, RSAKeyType,
+    },
+};
+use once_cell::sync::Lazy;
+
+pub static KEYS: Lazy<Keys> =
+    Lazy::new(|| Keys::from_config(&APP_CONFIG.auth.jwt).expect("加载JWT密钥失败"));
+
+/// JWT签名及校验密钥
+///
+/// HS256 只有一个共享密钥; 非对称算法按 `kid` 保存多个校验公钥,
+/// 轮换密钥时旧公钥继续保留, 已签发的token不会失效
+pub struct Keys {
+    algorithm: Algorithm,
+    signing_kid: Option<String>,
+    encoding: EncodingKey,
+    decoding: HashMap<String, DecodingKey>,
+    default_decoding: Option<DecodingKey>,
+    jwks: JwkSet,
+}
+
+impl Keys {
+    pub fn from_config(config: &JWT) -> Result<Self, AppError> {
+        let keys = match config.algorithm {
+            JWTAlgorithm::HS256 => Self::from_secret(config.secret.as_bytes()),
+            _ => Self::from_pem_files(config)?,
+        };
+        keys.probe()?;
+        Ok(keys)
+    }
+
+    fn from_secret(secret: &[u8]) -> Self {
+        Self {
+            algorithm: Algorithm::HS256,
+            signing_kid: None,
+            encoding: EncodingKey::from_secret(secret),
+            decoding: HashMap::new(),
+            default_decoding: Some(DecodingKey::from_secret(secret)),
+            jwks: JwkSet { keys: vec![] },
+        }
+    }
+
+    fn from_pem_files(config: &JWT) -> Result<Self, AppError> {
+        let signing_key = match config.signing_kid.as_ref() {
+            Some(kid) => config.keys.iter().find(|k| &k.kid == kid),
+            None => config.keys.iter().find(|k| k.private_key.is_some()),
+        }
+        .ok_or_else(|| AppError::ConfigError("未找到JWT签名密钥".to_string()))?;
+        let private_key_path = signing_key.private_key.as_ref().ok_or_else(|| {
+            AppError::ConfigError(format!("JWT签名密钥{}未配置私钥", signing_key.kid))
+        })?;
+
+        let private_pem = fs::read(private_key_path)?;
+        let encoding = match config.algorithm {
+            JWTAlgorithm::RS256 => EncodingKey::from_rsa_pem(&private_pem),
+            JWTAlgorithm::ES256 => EncodingKey::from_ec_pem(&private_pem),
+            _ => EncodingKey::from_ed_pem(&private_pem),
+        }
+        .map_err(|e| {
+            AppError::ConfigError(format!("解析JWT私钥{}失败: {}", private_key_path, e))
+        })?;
+
+        let mut decoding = HashMap::new();
+        let mut jwks = JwkSet { keys: vec![] };
+        for key in &config.keys {
+            let public_pem = fs::read(&key.public_key)?;
+            let decoding_key = match config.algorithm {
+                JWTAlgorithm::RS256 => DecodingKey::from_rsa_pem(&public_pem),
+                JWTAlgorithm::ES256 => DecodingKey::from_ec_pem(&public_pem),
+                _ => DecodingKey::from_ed_pem(&public_pem),
+            }
+            .map_err(|e| {
+                AppError::ConfigError(format!("解析JWT公钥{}失败: {}", key.public_key, e))
+            })?;
+            decoding.insert(key.kid.clone(), decoding_key);
+            jwks.keys
+                .push(public_jwk(&key.kid, config.algorithm, &public_pem)?);
+        }
+
+        Ok(Self {
+            algorithm: to_algorithm(config.algorithm),
+            signing_kid: Some(signing_key.kid.clone()),
+            encoding,
+            decoding,
+            default_decoding: None,
+            jwks,
+        })
+    }
+
+    /// 签发一个临时token, 分别用同一kid的校验公钥及JWKS中公布的公钥校验,
+    /// 私钥与公钥不匹配时启动即失败, 而不是签发无法校验的token
+    fn probe(&self) -> Result<(), AppError> {
+        let kid = self.signing_kid.as_deref().unwrap_or("secret");
+        let mismatch = |e: jsonwebtoken::errors::Error| {
+            AppError::ConfigError(format!("JWT签名私钥与公钥{}不匹配: {}", kid, e))
+        };
+        let claims = serde_json::json!({ "sub": "probe", "exp": Local::now().timestamp() + 60 });
+        let token = encode(&self.header(), &claims, &self.encoding).map_err(|e| {
+            AppError::ConfigError(format!("JWT签名密钥{}无法签发token: {}", kid, e))
+        })?;
+        let decoding = self
+            .decoding(&token)
+            .map_err(|_| AppError::ConfigError(format!("JWT签名密钥{}未配置公钥", kid)))?;
+        decode::<serde_json::Value>(&token, decoding, &self.validation()).map_err(mismatch)?;
+        if let Some(jwk) = self
+            .signing_kid
+            .as_deref()
+            .and_then(|kid| self.jwks.find(kid))
+        {
+            let decoding = DecodingKey::from_jwk(jwk).map_err(mismatch)?;
+            decode::<serde_json::Value>(&token, &decoding, &self.validation()).map_err(mismatch)?;
+        }
+        Ok(())
+    }
+
+    /// 签发token使用的header, 非对称算法带上kid
+    pub fn header(&self) -> Header {
+        let mut header = Header::new(self.algorithm);
+        header.kid = self.signing_kid.clone();
+        header
+    }
+
+    pub fn encoding(&self) -> &EncodingKey {
+        &self.encoding
+    }
+
+    pub fn validation(&self) -> Validation {
+        Validation::new(self.algorithm)
+    }
+
+    /// 根据token header中的kid选择校验密钥
+    pub fn decoding(&self, token: &str) -> Result<&DecodingKey, AppError> {
+        let header = decode_header(token)
+            .map_err(|_| AppError::AuthError("token错误,请重新登录".to_string()))?;
+        let key = match header.kid {
+            Some(kid) => self.decoding.get(&kid),
+            None => self.default_decoding.as_ref(),
+        };
+        key.ok_or_else(|| AppError::AuthError("token错误,请重新登录".to_string()))
+    }
+
+    /// 公开的校验公钥集合, HS256 时为空
+    pub fn jwks(&self) -> &JwkSet {
+        &self.jwks
+    }
+}
+
+fn to_algorithm(algorithm: JWTAlgorithm) -> Algorithm {
+    match algorithm {
+        JWTAlgorithm::HS256 => Algorithm::HS256,
+        JWTAlgorithm::RS256 => Algorithm::RS256,
+        JWTAlgorithm::ES256 => Algorithm::ES256,
+        JWTAlgorithm::EdDSA => Algorithm::EdDSA,
+    }
+}
+
+// 由PEM公钥生成JWK
+fn public_jwk(kid: &str, algorithm: JWTAlgorithm, public_pem: &[u8]) -> Result<Jwk, AppError> {
+    let pem = pem::parse(public_pem)
+        .map_err(|e| AppError::ConfigError(format!("解析JWT公钥{}失败: {}", kid, e)))?;
+    let invalid = || AppError::ConfigError(format!("JWT公钥{}格式错误", kid));
+
+    let (key_algorithm, params) = match algorithm {
+        JWTAlgorithm::RS256 => {
+            let rsa_key = if pem.tag() == "RSA PUBLIC KEY" {
+                pem.contents()
+            } else {
+                spki_public_key(pem.contents()).ok_or_else(invalid)?
+            };
+            let (n, e) = rsa_components(rsa_key).ok_or_else(invalid)?;
+            (
+                KeyAlgorithm::RS256,
+                AlgorithmParameters::RSA(RSAKeyParameters {
+                    key_type: RSAKeyType::RSA,
+                    n: URL_SAFE_NO_PAD.encode(n),
+                    e: URL_SAFE_NO_PAD.encode(e),
+                }),
+            )
+        }
+        JWTAlgorithm::ES256 => {
+            // 未压缩的椭圆曲线点: 0x04 || x || y
+            let point = spki_public_key(pem.contents()).ok_or_else(invalid)?;
+            if point.len() != 65 || point[0] != 0x04 {
+                return Err(invalid());
+            }
+            (
+                KeyAlgorithm::ES256,
+                AlgorithmParameters::EllipticCurve(EllipticCurveKeyParameters {
+                    key_type: EllipticCurveKeyType::EC,
+                    curve: EllipticCurve::P256,
+                    x: URL_SAFE_NO_PAD.encode(&point[1..33]),
+                    y: URL_SAFE_NO_PAD.encode(&point[33..]),
+                }),
+            )
+        }
+        _ => {
+            let point = spki_public_key(pem.contents()).ok_or_else(invalid)?;
+            (
+                KeyAlgorithm::EdDSA,
+                AlgorithmParameters::OctetKeyPair(OctetKeyPairParameters {
+                    key_type: OctetKeyPairType::OctetKeyPair,
+                    curve: EllipticCurve::Ed25519,
+                    x: URL_SAFE_NO_PAD.encode(point),
+                }),
+            )
+        }
+    };
+
+    Ok(Jwk {
+        common: CommonParameters {
+            public_key_use: Some(PublicKeyUse::Signature),
+            key_algorithm: Some(key_algorithm),
+            key_id: Some(kid.to_string()),
+            ..Default::default()
+        },
+        algorithm: params,
+    })
+}
+
+const DER_SEQUENCE: u8 = 0x30;
+const DER_INTEGER: u8 = 0x02;
+const DER_BIT_STRING: u8 = 0x03;
+
+// 读取一个DER TLV, 返回 (tag, value, 剩余数据)
+fn read_der(data: &[u8]) -> Option<(u8, &[u8], &[u8])> {
+    let (&tag, rest) = data.split_first()?;
+    let (&first, rest) = rest.split_first()?;
+    let (len, rest) = if first & 0x80 == 0 {
+        (first as usize, rest)
+    } else {
+        let num = (first & 0x7f) as usize;
+        if num == 0 || num > 4 || rest.len() < num {
+            return None;
+        }
+        let len = rest[..num]
+            .iter()
+            .fold(0usize, |acc, b| (acc << 8) | *b as usize);
+        (len, &rest[num..])
+    };
+    if rest.len() < len {
+        return None;
+    }
+    Some((tag, &rest[..len], &rest[len..]))
+}
+
+// SubjectPublicKeyInfo ::= SEQUENCE { algorithm, subjectPublicKey BIT STRING }
+fn spki_public_key(der: &[u8]) -> Option<&[u8]> {
+    let (tag, spki, _) = read_der(der)?;
+    if tag != DER_SEQUENCE {
+        return None;
+    }
+    let (_, _, rest) = read_der(spki)?;
+    let (tag, bits, _) = read_der(rest)?;
+    if tag != DER_BIT_STRING {
+        return None;
+    }
+    // 第一个字节为未使用的bit数
+    bits.split_first().map(|(_, key)| key)
+}
+
+// RSAPublicKey ::= SEQUENCE { modulus INTEGER, publicExponent INTEGER }
+fn rsa_components(der: &[u8]) -> Option<(&[u8], &[u8])> {
+    let (tag, body, _) = read_der(der)?;
+    if tag != DER_SEQUENCE {
+        return None;
+    }
+    let (tag, n, rest) = read_der(body)?;
+    if tag != DER_INTEGER {
+        return None;
+    }
+    let (tag, e, _) = read_der(rest)?;
+    if tag != DER_INTEGER {
+        return None;
+    }
+    Some((strip_leading_zero(n), strip_leading_zero(e)))
+}
+
+fn strip_leading_zero(value: &[u8]) -> &[u8] {
+    match value.split_first() {
+        Some((0, rest)) if !rest.is_empty() => rest,
+        _ => value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use commonx::config::config::JWTKey;
+
+    use super::*;
+
+    fn jwt_config(algorithm: JWTAlgorithm, private_key: &str, public_key: &str) -> JWT {
+        let fixture =
+            |name: &str| format!("{}/tests/fixtures/jwt/{}", env!("CARGO_MANIFEST_DIR"), name);
+        JWT {
+            location: None,
+            secret: String::new(),
+            expiration: 60,
+            algorithm,
+            keys: vec![JWTKey {
+                kid: "k1".to_string(),
+                private_key: Some(fixture(private_key)),
+                public_key: fixture(public_key),
+            }],
+            signing_kid: Some("k1".to_string()),
+            cookie: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_sign_verify_round_trip() {
+        for (algorithm, name) in [
+            (JWTAlgorithm::RS256, "rsa"),
+            (JWTAlgorithm::ES256, "ec"),
+            (JWTAlgorithm::EdDSA, "ed25519"),
+        ] {
+            let config = jwt_config(
+                algorithm,
+                &format!("{name}_private.pem"),
+                &format!("{name}_public.pem"),
+            );
+            let keys = Keys::from_config(&config).unwrap();
+            let claims = serde_json::json!({ "sub": "1", "exp": Local::now().timestamp() + 60 });
+            let token = encode(&keys.header(), &claims, keys.encoding()).unwrap();
+            assert_eq!(decode_header(&token).unwrap().kid.as_deref(), Some("k1"));
+            let decoding = keys.decoding(&token).unwrap();
+            decode::<serde_json::Value>(&token, decoding, &keys.validation()).unwrap();
+
+            // JWKS中公布的公钥可供第三方校验
+            assert_eq!(keys.jwks().keys.len(), 1);
+            let jwk = keys.jwks().find("k1").unwrap();
+            assert_eq!(jwk.common.public_key_use, Some(PublicKeyUse::Signature));
+            let decoding = DecodingKey::from_jwk(jwk).unwrap();
+            decode::<serde_json::Value>(&token, &decoding, &keys.validation()).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_mismatched_key_pair_rejected() {
+        let config = jwt_config(
+            JWTAlgorithm::RS256,
+            "rsa_private.pem",
+            "rsa_other_public.pem",
+        );
+        assert!(matches!(
+            Keys::from_config(&config),
+            Err(AppError::ConfigError(_))
+        ));
+    }
+
+    #[test]
+    fn test_rsa_components_from_pkcs1() {
+        // SEQUENCE { INTEGER 0x00c5, INTEGER 0x010001 }
+        let der = [
+            0x30, 0x09, 0x02, 0x02, 0x00, 0xc5, 0x02, 0x03, 0x01, 0x00, 0x01,
+        ];
+        let (n, e) = rsa_components(&der).unwrap();
+        assert_eq!(n, &[0xc5]);
+        assert_eq!(e, &[0x01, 0x00, 0x01]);
+    }
+
+    #[test]
+    fn test_spki_public_key() {
+        // SEQUENCE { SEQUENCE { OID 1.3.101.112 }, BIT STRING 00 || key }
+        let mut der = vec![
+            0x30, 0x2a, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70, 0x03, 0x21, 0x00,
+        ];
+        der.extend_from_slice(&[7u8; 32]);
+        assert_eq!(spki_public_key(&der).unwrap(), &[7u8; 32]);
+        assert!(spki_public_key(&der[..20]).is_none());
+    }
+}
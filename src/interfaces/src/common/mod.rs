@@ -1,11 +1,19 @@
-use infrastructurex::container::operater_log_domain::new_operater_log_domain_service;
+use infrastructurex::container::{
+    login_log_domain::new_login_log_domain_service,
+    operater_log_domain::new_operater_log_domain_service,
+};
+use loginLogDomain::LoginLogDomainImpl;
 use once_cell::sync::Lazy;
 use operaterLogDomain::OperaterLogDomainImpl;
 
 pub mod jwt;
+pub mod jwt_keys;
 mod validated_form;
 pub mod validated_json;
 pub mod validated_query;
 
 pub static OPERATOR_LOG_DOMAIN: Lazy<OperaterLogDomainImpl> =
     Lazy::new(|| new_operater_log_domain_service());
+
+pub static LOGIN_LOG_DOMAIN: Lazy<LoginLogDomainImpl> =
+    Lazy::new(new_login_log_domain_service);
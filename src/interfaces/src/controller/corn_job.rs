@@ -2,80 +2,103 @@
 //! 
 //! 定时任务控制器，处理定时任务相关的HTTP请求
 
-use axum::response::IntoResponse;
+use axum::{Extension, response::IntoResponse};
 use commonx::error::AppError;
 use jobDomain::JobDomainTrait;
+use jobDomain::entity::job::CreateJobDto;
 use queryx::corn_job::api::JobQueryTrait;
+use queryx::corn_job::entity::{JobVo, ListJobQo};
 
 use crate::common::validated_json::VJson;
-use crate::controller::CORN_JOB_CONTROLLER;
+use crate::controller::{CORN_JOB_CONTROLLER, DEPT_CONTROLLER};
 use crate::resp::ApiResponse;
 use crate::types::corn_job::{CreateReq, JobInfoRes, ListReq, ListRes, UpdateReq};
 use crate::types::GetByIdReq;
+use crate::types::user_info::CtxUserInfo;
 
 /// 创建定时任务
 /// 
 /// # 参数
+/// - `user`: 当前用户, 作为任务的创建人及所属部门
 /// - `arg`: 创建定时任务的请求参数
 /// 
 /// # 返回
 /// - 成功：返回创建的任务ID
 /// - 失败：返回错误信息
 #[must_use]
-pub async fn create(VJson(arg): VJson<CreateReq>) -> impl IntoResponse {
-    ApiResponse::from_result(CORN_JOB_CONTROLLER.create(arg).await)
+pub async fn create(
+    Extension(user): Extension<CtxUserInfo>,
+    VJson(arg): VJson<CreateReq>,
+) -> impl IntoResponse {
+    ApiResponse::from_result(CORN_JOB_CONTROLLER.create(&user, arg).await)
 }
 
 /// 更新定时任务
 /// 
 /// # 参数
+/// - `user`: 当前用户, 只能更新数据权限范围内的任务
 /// - `arg`: 更新定时任务的请求参数
 /// 
 /// # 返回
 /// - 成功：返回空
 /// - 失败：返回错误信息
 #[must_use]
-pub async fn update_by_id(VJson(arg): VJson<UpdateReq>) -> impl IntoResponse {
-    ApiResponse::from_result(CORN_JOB_CONTROLLER.update_by_id(arg).await)
+pub async fn update_by_id(
+    Extension(user): Extension<CtxUserInfo>,
+    VJson(arg): VJson<UpdateReq>,
+) -> impl IntoResponse {
+    ApiResponse::from_result(CORN_JOB_CONTROLLER.update_by_id(&user, arg).await)
 }
 
 /// 删除定时任务
 /// 
 /// # 参数
+/// - `user`: 当前用户, 只能删除数据权限范围内的任务
 /// - `arg`: 删除定时任务的请求参数，包含任务ID
 /// 
 /// # 返回
 /// - 成功：返回空
 /// - 失败：返回错误信息
 #[must_use]
-pub async fn delete_by_id(VJson(arg): VJson<GetByIdReq>) -> impl IntoResponse {
-    ApiResponse::from_result(CORN_JOB_CONTROLLER.delete_by_id(arg).await)
+pub async fn delete_by_id(
+    Extension(user): Extension<CtxUserInfo>,
+    VJson(arg): VJson<GetByIdReq>,
+) -> impl IntoResponse {
+    ApiResponse::from_result(CORN_JOB_CONTROLLER.delete_by_id(&user, arg).await)
 }
 
 /// 获取定时任务详情
 /// 
 /// # 参数
+/// - `user`: 当前用户, 超出数据权限范围的任务视为不存在
 /// - `arg`: 获取定时任务的请求参数，包含任务ID
 /// 
 /// # 返回
 /// - 成功：返回任务详情
 /// - 失败：返回错误信息
 #[must_use]
-pub async fn get_by_id(VJson(arg): VJson<GetByIdReq>) -> impl IntoResponse {
-    ApiResponse::from_result(CORN_JOB_CONTROLLER.get_by_id(arg).await)
+pub async fn get_by_id(
+    Extension(user): Extension<CtxUserInfo>,
+    VJson(arg): VJson<GetByIdReq>,
+) -> impl IntoResponse {
+    ApiResponse::from_result(CORN_JOB_CONTROLLER.get_by_id(&user, arg).await)
 }
 
 /// 获取定时任务列表
 /// 
 /// # 参数
+/// - `user`: 当前用户, 按其数据权限过滤
 /// - `arg`: 获取定时任务列表的请求参数，包含分页等信息
 /// 
 /// # 返回
 /// - 成功：返回任务列表
 /// - 失败：返回错误信息
 #[must_use]
-pub async fn list(VJson(arg): VJson<ListReq>) -> impl IntoResponse {
-    ApiResponse::from_result(CORN_JOB_CONTROLLER.list(arg).await)
+pub async fn list(
+    Extension(user): Extension<CtxUserInfo>,
+    VJson(arg): VJson<ListReq>,
+) -> impl IntoResponse {
+    ApiResponse::from_result(CORN_JOB_CONTROLLER.list(&user, arg).await)
 }
 
 /// 定时任务控制器接口
@@ -85,52 +108,62 @@ pub trait CornJobControllerTrait: Send + Sync {
     /// 创建定时任务
     /// 
     /// # 参数
+    /// - `user`: 当前用户
     /// - `job`: 创建定时任务的请求数据
     /// 
     /// # 返回
     /// - 成功：返回创建的任务ID
     /// - 失败：返回错误信息
-    async fn create(&self, job: CreateReq) -> Result<i64, AppError>;
+    async fn create(&self, user: &CtxUserInfo, job: CreateReq) -> Result<i64, AppError>;
 
     /// 更新定时任务
     /// 
     /// # 参数
+    /// - `user`: 当前用户
     /// - `update_job`: 更新定时任务的请求数据
     /// 
     /// # 返回
     /// - 成功：返回空
     /// - 失败：返回错误信息
-    async fn update_by_id(&self, update_job: UpdateReq) -> Result<(), AppError>;
+    async fn update_by_id(&self, user: &CtxUserInfo, update_job: UpdateReq)
+    -> Result<(), AppError>;
 
     /// 删除定时任务
     /// 
     /// # 参数
+    /// - `user`: 当前用户
     /// - `id`: 删除定时任务的请求数据，包含任务ID
     /// 
     /// # 返回
     /// - 成功：返回空
     /// - 失败：返回错误信息
-    async fn delete_by_id(&self, id: GetByIdReq) -> Result<(), AppError>;
+    async fn delete_by_id(&self, user: &CtxUserInfo, id: GetByIdReq) -> Result<(), AppError>;
 
     /// 获取定时任务详情
     /// 
     /// # 参数
+    /// - `user`: 当前用户
     /// - `id`: 获取定时任务的请求数据，包含任务ID
     /// 
     /// # 返回
     /// - 成功：返回任务详情
     /// - 失败：返回错误信息
-    async fn get_by_id(&self, id: GetByIdReq) -> Result<Option<JobInfoRes>, AppError>;
+    async fn get_by_id(
+        &self,
+        user: &CtxUserInfo,
+        id: GetByIdReq,
+    ) -> Result<Option<JobInfoRes>, AppError>;
 
     /// 获取定时任务列表
     /// 
     /// # 参数
+    /// - `user`: 当前用户
     /// - `req`: 获取定时任务列表的请求数据，包含分页等信息
     /// 
     /// # 返回
     /// - 成功：返回任务列表
     /// - 失败：返回错误信息
-    async fn list(&self, req: ListReq) -> Result<ListRes, AppError>;
+    async fn list(&self, user: &CtxUserInfo, req: ListReq) -> Result<ListRes, AppError>;
 }
 
 /// 定时任务控制器实现
@@ -147,15 +180,21 @@ impl<J: JobDomainTrait + Send + Sync, Q: JobQueryTrait + Sync + Send> CornJobCon
     for CornJobController<J, Q>
 {
     /// 创建定时任务
-    async fn create(&self, job: CreateReq) -> Result<i64, AppError> {
+    async fn create(&self, user: &CtxUserInfo, job: CreateReq) -> Result<i64, AppError> {
+        let mut job: CreateJobDto = job.into();
+        job.create_by = user.id;
+        job.dept_id = user.dept_id;
         self.job_domain
-            .create(job.into())
+            .create(job)
             .await
             .map_err(AppError::from)
     }
 
     /// 更新定时任务
-    async fn update_by_id(&self, req: UpdateReq) -> Result<(), AppError> {
+    async fn update_by_id(&self, user: &CtxUserInfo, req: UpdateReq) -> Result<(), AppError> {
+        self.find_in_scope(user, req.id)
+            .await?
+            .ok_or_else(|| AppError::E404("定时任务不存在".to_string()))?;
         self.job_domain
             .update_by_id(req.id, req.into())
             .await
@@ -163,7 +202,10 @@ impl<J: JobDomainTrait + Send + Sync, Q: JobQueryTrait + Sync + Send> CornJobCon
     }
 
     /// 删除定时任务
-    async fn delete_by_id(&self, req: GetByIdReq) -> Result<(), AppError> {
+    async fn delete_by_id(&self, user: &CtxUserInfo, req: GetByIdReq) -> Result<(), AppError> {
+        self.find_in_scope(user, req.id)
+            .await?
+            .ok_or_else(|| AppError::E404("定时任务不存在".to_string()))?;
         self.job_domain
             .delete_by_id(req.id)
             .await
@@ -171,18 +213,20 @@ impl<J: JobDomainTrait + Send + Sync, Q: JobQueryTrait + Sync + Send> CornJobCon
     }
 
     /// 获取定时任务详情
-    async fn get_by_id(&self, id: GetByIdReq) -> Result<Option<JobInfoRes>, AppError> {
-        self.job_query
-            .get_by_id(id.id)
-            .await
-            .map(|job| job.map(JobInfoRes::from))
-            .map_err(AppError::from)
+    async fn get_by_id(
+        &self,
+        user: &CtxUserInfo,
+        id: GetByIdReq,
+    ) -> Result<Option<JobInfoRes>, AppError> {
+        Ok(self.find_in_scope(user, id.id).await?.map(JobInfoRes::from))
     }
 
     /// 获取定时任务列表
-    async fn list(&self, req: ListReq) -> Result<ListRes, AppError> {
+    async fn list(&self, user: &CtxUserInfo, req: ListReq) -> Result<ListRes, AppError> {
+        let mut query: ListJobQo = req.into();
+        query.data_scope = DEPT_CONTROLLER.data_scope(user).await?;
         self.job_query
-            .list(req.into())
+            .list(query)
             .await
             .map(|jobs| ListRes {
                 jobs: jobs.into_iter().map(JobInfoRes::from).collect(),
@@ -207,4 +251,14 @@ impl<J: JobDomainTrait + Send + Sync, Q: JobQueryTrait + Sync + Send> CornJobCon
             job_query,
         }
     }
+
+    /// 按ID查询数据权限范围内的任务, 超出范围时视为不存在
+    async fn find_in_scope(&self, user: &CtxUserInfo, id: i64) -> Result<Option<JobVo>, AppError> {
+        let scope = DEPT_CONTROLLER.data_scope(user).await?;
+        Ok(self
+            .job_query
+            .get_by_id(id)
+            .await?
+            .filter(|job| scope.allows(job.dept_id, job.create_by)))
+    }
 }
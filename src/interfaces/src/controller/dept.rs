@@ -0,0 +1,52 @@
+//! Dept Controller
+//!
+//! 部门控制器, 提供部门树查询及数据权限解析
+
+use axum::response::IntoResponse;
+use commonx::error::AppError;
+use queryx::{
+    dept::{entity::ScopeSubject, services::DeptQueryImpl},
+    entity::DataScope,
+};
+
+use crate::{
+    controller::DEPT_CONTROLLER,
+    resp::ApiResponse,
+    types::{dept::DeptTreeRes, user_info::CtxUserInfo},
+};
+
+/// 获取部门树
+#[must_use]
+pub async fn tree() -> impl IntoResponse {
+    ApiResponse::from_result(DEPT_CONTROLLER.tree().await)
+}
+
+pub struct DeptController {
+    dept_query: DeptQueryImpl,
+}
+
+impl DeptController {
+    #[must_use]
+    pub fn new(dept_query: DeptQueryImpl) -> Self {
+        Self { dept_query }
+    }
+
+    /// 部门树
+    pub async fn tree(&self) -> Result<Vec<DeptTreeRes>, AppError> {
+        self.dept_query
+            .tree()
+            .await
+            .map(|nodes| nodes.into_iter().map(DeptTreeRes::from).collect())
+    }
+
+    /// 当前用户的数据权限
+    pub async fn data_scope(&self, user: &CtxUserInfo) -> Result<DataScope, AppError> {
+        self.dept_query
+            .data_scope(&ScopeSubject {
+                user_id: user.id,
+                role_id: user.role,
+                dept_id: user.dept_id,
+            })
+            .await
+    }
+}
@@ -0,0 +1,52 @@
+//! Login Log Controller
+//!
+//! 登录日志控制器, 提供登录日志的分页查询, 按调用者的数据权限过滤
+
+use axum::{Extension, response::IntoResponse};
+use commonx::error::AppError;
+use queryx::login_log::{
+    api::LoginLogQueryTrait, entity::ListLoginLogQo, services::LoginLogQueryImpl,
+};
+
+use crate::{
+    common::validated_query::VQuery,
+    controller::{DEPT_CONTROLLER, LOGIN_LOG_CONTROLLER},
+    resp::ApiResponse,
+    types::{
+        login_log::{LoginLogListReq, LoginLogListRes},
+        user_info::CtxUserInfo,
+    },
+};
+
+/// 分页查询登录日志
+pub async fn list(
+    Extension(user): Extension<CtxUserInfo>,
+    VQuery(arg): VQuery<LoginLogListReq>,
+) -> impl IntoResponse {
+    ApiResponse::from_result(LOGIN_LOG_CONTROLLER.list(&user, arg).await)
+}
+
+pub struct LoginLogController {
+    login_log_query: LoginLogQueryImpl,
+}
+
+impl LoginLogController {
+    #[must_use]
+    pub fn new(login_log_query: LoginLogQueryImpl) -> Self {
+        Self { login_log_query }
+    }
+
+    /// 无法确定账号的失败记录不属于任何部门, 只有全部数据权限可见
+    pub async fn list(
+        &self,
+        user: &CtxUserInfo,
+        req: LoginLogListReq,
+    ) -> Result<LoginLogListRes, AppError> {
+        let mut query: ListLoginLogQo = req.into();
+        query.data_scope = DEPT_CONTROLLER.data_scope(user).await?;
+        self.login_log_query
+            .list(query)
+            .await
+            .map(LoginLogListRes::from)
+    }
+}
@@ -1,5 +1,8 @@
 use infrastructurex::container::{
+    dept_query::new_dept_query_service,
     job_domain::{new_job_domain_service, new_job_query_service},
+    login_log_domain::new_login_log_query_service,
+    operater_log_domain::new_oper_log_query_service,
     user_domain::new_user_domain_service,
 };
 use jobDomain::JobDomainImpl;
@@ -7,9 +10,15 @@ use once_cell::sync::Lazy;
 use queryx::corn_job::services::JobQueryImpl;
 use userDomain::UserDomainImpl;
 
-use crate::controller::{corn_job::CornJobController, sys::SysController, user::UserController};
+use crate::controller::{
+    corn_job::CornJobController, dept::DeptController, login_log::LoginLogController,
+    oper_log::OperLogController, sys::SysController, user::UserController,
+};
 
 pub mod corn_job;
+pub mod dept;
+pub mod login_log;
+pub mod oper_log;
 pub mod sys;
 pub mod user;
 
@@ -23,3 +32,12 @@ pub static SYS_CONTROLLER: Lazy<SysController> = Lazy::new(|| SysController::new
 
 pub static CORN_JOB_CONTROLLER: Lazy<CornJobController<JobDomainImpl, JobQueryImpl>> =
     Lazy::new(|| CornJobController::new(new_job_domain_service(), new_job_query_service()));
+
+pub static DEPT_CONTROLLER: Lazy<DeptController> =
+    Lazy::new(|| DeptController::new(new_dept_query_service()));
+
+pub static OPER_LOG_CONTROLLER: Lazy<OperLogController> =
+    Lazy::new(|| OperLogController::new(new_oper_log_query_service()));
+
+pub static LOGIN_LOG_CONTROLLER: Lazy<LoginLogController> =
+    Lazy::new(|| LoginLogController::new(new_login_log_query_service()));
@@ -0,0 +1,136 @@
+//! Oper Log Controller
+//!
+//! 操作日志控制器, 提供操作日志的查询及导出, 按调用者的数据权限过滤
+
+use axum::{
+    Extension,
+    http::header::{CONTENT_DISPOSITION, CONTENT_TYPE},
+    response::{IntoResponse, Response},
+};
+use chrono::Local;
+use commonx::{config::APP_CONFIG, error::AppError};
+use infrastructurex::container::oper_log_writer::{OperLogWriterMetrics, oper_log_writer};
+use queryx::oper_log::{api::OperLogQueryTrait, entity::ListOperLogQo, services::OperLogQueryImpl};
+
+use crate::{
+    common::validated_query::VQuery,
+    controller::{DEPT_CONTROLLER, OPER_LOG_CONTROLLER},
+    resp::ApiResponse,
+    types::{
+        GetByIdReq,
+        oper_log::{OperLogListReq, OperLogListRes, OperLogRes},
+        user_info::CtxUserInfo,
+    },
+};
+
+/// 分页查询操作日志
+pub async fn list(
+    Extension(user): Extension<CtxUserInfo>,
+    VQuery(arg): VQuery<OperLogListReq>,
+) -> impl IntoResponse {
+    ApiResponse::from_result(OPER_LOG_CONTROLLER.list(&user, arg).await)
+}
+
+/// 操作日志详情
+pub async fn detail(
+    Extension(user): Extension<CtxUserInfo>,
+    VQuery(arg): VQuery<GetByIdReq>,
+) -> impl IntoResponse {
+    ApiResponse::from_result(OPER_LOG_CONTROLLER.detail(&user, arg.id).await)
+}
+
+/// 按查询条件导出CSV
+pub async fn export(
+    Extension(user): Extension<CtxUserInfo>,
+    VQuery(arg): VQuery<OperLogListReq>,
+) -> Response {
+    match OPER_LOG_CONTROLLER.export(&user, arg).await {
+        Ok(csv) => {
+            let filename = format!(
+                "attachment; filename=\"oper_log_{}.csv\"",
+                Local::now().format("%Y%m%d%H%M%S")
+            );
+            (
+                [
+                    (CONTENT_TYPE, "text/csv;charset=UTF-8".to_string()),
+                    (CONTENT_DISPOSITION, filename),
+                ],
+                // 带BOM, 便于Excel识别UTF-8编码
+                format!("\u{feff}{}", csv),
+            )
+                .into_response()
+        }
+        Err(e) => ApiResponse::<()>::from(e).into_response(),
+    }
+}
+
+/// 异步写入队列状态
+pub async fn writer_stats() -> impl IntoResponse {
+    ApiResponse::from_result(OPER_LOG_CONTROLLER.writer_stats())
+}
+
+pub struct OperLogController {
+    oper_log_query: OperLogQueryImpl,
+}
+
+impl OperLogController {
+    #[must_use]
+    pub fn new(oper_log_query: OperLogQueryImpl) -> Self {
+        Self { oper_log_query }
+    }
+
+    async fn scoped_query(
+        &self,
+        user: &CtxUserInfo,
+        req: OperLogListReq,
+    ) -> Result<ListOperLogQo, AppError> {
+        let mut query: ListOperLogQo = req.into();
+        query.data_scope = DEPT_CONTROLLER.data_scope(user).await?;
+        Ok(query)
+    }
+
+    pub async fn list(
+        &self,
+        user: &CtxUserInfo,
+        req: OperLogListReq,
+    ) -> Result<OperLogListRes, AppError> {
+        let query = self.scoped_query(user, req).await?;
+        self.oper_log_query
+            .list(query)
+            .await
+            .map(OperLogListRes::from)
+    }
+
+    /// 详情同样受数据权限限制, 不在权限范围内的按不存在处理
+    pub async fn detail(
+        &self,
+        user: &CtxUserInfo,
+        id: i64,
+    ) -> Result<Option<OperLogRes>, AppError> {
+        let scope = DEPT_CONTROLLER.data_scope(user).await?;
+        Ok(self
+            .oper_log_query
+            .get_by_id(id)
+            .await?
+            .filter(|log| scope.allows(log.dept_id, log.oper_id))
+            .map(OperLogRes::from))
+    }
+
+    pub async fn export(
+        &self,
+        user: &CtxUserInfo,
+        req: OperLogListReq,
+    ) -> Result<String, AppError> {
+        let query = self.scoped_query(user, req).await?;
+        self.oper_log_query.export_csv(query).await
+    }
+
+    /// 未启用异步写入时返回空
+    pub fn writer_stats(&self) -> Result<Option<OperLogWriterMetrics>, AppError> {
+        Ok(APP_CONFIG
+            .oper_log
+            .writer
+            .enable
+            .then(|| oper_log_writer().metrics()))
+    }
+}
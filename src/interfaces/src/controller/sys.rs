@@ -1,15 +1,28 @@
-use axum::response::IntoResponse;
+use axum::{Json, response::IntoResponse};
 use commonx::error::AppError;
 use infrastructurex::container::sys_domain::SysDomainRepositoryImpl;
 
-use crate::{controller::SYS_CONTROLLER, resp::ApiResponse};
+use crate::{
+    common::jwt_keys::KEYS, controller::SYS_CONTROLLER, middlewares::ip_filter, resp::ApiResponse,
+};
 
 pub async fn init_all() -> impl IntoResponse {
     ApiResponse::from_result(SYS_CONTROLLER.init_all().await)
 }
 
+/// 重新加载配置文件中的IP访问控制规则
+pub async fn reload_ip_filter() -> impl IntoResponse {
+    ApiResponse::from_result(SYS_CONTROLLER.reload_ip_filter().await)
+}
+
+/// JWT校验公钥, 供其他服务校验Vela签发的token, 按JWKS标准格式返回
+pub async fn jwks() -> impl IntoResponse {
+    Json(KEYS.jwks().clone())
+}
+
 pub trait SysControllerTrait {
     async fn init_all(&self) -> Result<(), AppError>;
+    async fn reload_ip_filter(&self) -> Result<(), AppError>;
 }
 
 pub struct SysController {}
@@ -24,4 +37,8 @@ impl SysControllerTrait for SysController {
     async fn init_all(&self) -> Result<(), AppError> {
         Ok(())
     }
+
+    async fn reload_ip_filter(&self) -> Result<(), AppError> {
+        ip_filter::reload_ip_filter()
+    }
 }
@@ -1,51 +1,320 @@
 // pub async fn gen_captcha(arg: ClientInfo) -> CaptchaImage {}
 
-use std::time::Instant;
-
-use axum::{Extension, response::IntoResponse};
-use chrono::Local;
-use commonx::error::AppError;
+use axum::{
+    Extension,
+    response::{IntoResponse, Response},
+};
+use axum_extra::TypedHeader;
+use chrono::{Local, TimeZone};
+use commonx::{config::APP_CONFIG, error::AppError, web_error};
+use headers::Cookie;
+use hyper::{StatusCode, header::SET_COOKIE};
 use infrastructurex::persistence::id_gen::next_id;
-use operaterLogDomain::{api::traits::OperaterLogDomainTrait, entity::OperaterLog};
+use loginLogDomain::{
+    api::traits::LoginLogDomainTrait,
+    entity::{
+        LOGIN_STATUS_FAIL, LOGIN_STATUS_SUCCESS, LOGIN_TYPE_2FA, LOGIN_TYPE_EXTERNAL,
+        LOGIN_TYPE_PASSWORD, LoginLog,
+    },
+};
 use userDomain::{
     api::{
         dto::{
-            auth::{AuthDto, AuthDtoWithCaptcha},
+            auth::{
+                ApiKeyPrincipal, AuthDto, AuthDtoWithCaptcha, ChangePasswordDto, CreateApiKeyDto,
+                ExternalLoginDto, ForgotPasswordDto, Login2faDto, LoginOutcome, LoginSubject,
+                RegisterDto, ResendEmailVerifyDto, ResetPasswordDto,
+            },
             user_info::UserInfoDto,
         },
         traits::UserDomainTrait,
     },
-    entity::{captcha::CaptchaImage, user::User},
+    commons::error::UserDomainError,
+    entity::{
+        api_key::{ApiKey, ApiKeyCreated},
+        captcha::CaptchaImage,
+        session::UserSession,
+        totp::TotpEnrollment,
+        user::User,
+    },
 };
 
 use crate::{
-    common::{OPERATOR_LOG_DOMAIN, jwt::authorize, validated_json::VJson, validated_query::VQuery},
+    common::{
+        LOGIN_LOG_DOMAIN,
+        jwt::{AuthBody, auth_cookie, authorize, http_only_cookie},
+        validated_json::VJson,
+        validated_query::VQuery,
+    },
     controller::USER_CONTROLLER,
     middlewares::ReqCtx,
     resp::ApiResponse,
     types::{
         GetByIdReq,
         auth_jwt::Claims,
-        user_info::{ClientInfoReq, GetByUsernameReq, LoginReq, LoginResp, LoginWithCaptchaReq},
+        user_info::{
+            ChangePasswordReq, ClientInfoReq, CtxUserInfo, ForgotPasswordReq, GetByUsernameReq,
+            Login2faReq, LoginReq, LoginResp, LoginResult, LoginWithCaptchaReq, OidcAuthorizeReq,
+            OidcAuthorizeResp, OidcCallbackReq, RegisterReq, ResendEmailVerifyReq,
+            ResetPasswordReq, TotpCodeReq, TwoFactorChallengeResp, VerifyEmailReq,
+        },
+        user_info::{CreateApiKeyReq, SessionListReq, SessionRes},
     },
 };
 
-pub async fn get_captcha(VQuery(arg): VQuery<ClientInfoReq>) -> impl IntoResponse {
-    ApiResponse::from_result(USER_CONTROLLER.gen_captcha(arg).await)
+/// 外部认证授权请求的state
+const OIDC_STATE_COOKIE: &str = "oidc_state";
+
+pub async fn get_captcha(
+    Extension(req_ctx): Extension<ReqCtx>,
+    VQuery(arg): VQuery<ClientInfoReq>,
+) -> impl IntoResponse {
+    ApiResponse::from_result(USER_CONTROLLER.gen_captcha(req_ctx, arg).await)
 }
 
 pub async fn login(
     Extension(req_ctx): Extension<ReqCtx>,
     VJson(arg): VJson<LoginReq>,
 ) -> impl IntoResponse {
-    ApiResponse::from_result(USER_CONTROLLER.login(req_ctx, arg).await)
+    with_auth_cookie(USER_CONTROLLER.login(req_ctx, arg).await)
 }
 
 pub async fn login_with_captcha(
     Extension(req_ctx): Extension<ReqCtx>,
     VJson(arg): VJson<LoginWithCaptchaReq>,
 ) -> impl IntoResponse {
-    ApiResponse::from_result(USER_CONTROLLER.login_with_captcha(req_ctx, arg).await)
+    with_auth_cookie(USER_CONTROLLER.login_with_captcha(req_ctx, arg).await)
+}
+
+pub async fn login_2fa(
+    Extension(req_ctx): Extension<ReqCtx>,
+    VJson(arg): VJson<Login2faReq>,
+) -> impl IntoResponse {
+    with_auth_cookie(
+        USER_CONTROLLER
+            .login_2fa(req_ctx, arg)
+            .await
+            .map(|resp| LoginResult::Token(Box::new(resp))),
+    )
+}
+
+pub async fn change_password(
+    Extension(req_ctx): Extension<ReqCtx>,
+    Extension(user): Extension<CtxUserInfo>,
+    VJson(arg): VJson<ChangePasswordReq>,
+) -> impl IntoResponse {
+    with_auth_cookie(
+        USER_CONTROLLER
+            .change_password(req_ctx, user.id, arg)
+            .await
+            .map(|resp| LoginResult::Token(Box::new(resp))),
+    )
+}
+
+// state同时写入短期Cookie, 回调时比对, 防止登录CSRF
+pub async fn oidc_authorize(VQuery(arg): VQuery<OidcAuthorizeReq>) -> impl IntoResponse {
+    let result = USER_CONTROLLER.oidc_authorize(arg).await;
+    let cookie = result.as_ref().ok().and_then(|resp| {
+        http_only_cookie(
+            OIDC_STATE_COOKIE,
+            &resp.state,
+            APP_CONFIG.auth.oidc.state_ttl as i64,
+        )
+    });
+    let mut response = ApiResponse::from_result(result);
+    if let Some(cookie) = cookie {
+        response.headers_mut().append(SET_COOKIE, cookie);
+    }
+    response
+}
+
+pub async fn oidc_callback(
+    Extension(req_ctx): Extension<ReqCtx>,
+    cookie: Option<TypedHeader<Cookie>>,
+    VJson(arg): VJson<OidcCallbackReq>,
+) -> impl IntoResponse {
+    let state_cookie =
+        cookie.and_then(|TypedHeader(cookie)| cookie.get(OIDC_STATE_COOKIE).map(str::to_string));
+    let mut response = with_auth_cookie(
+        USER_CONTROLLER
+            .oidc_callback(req_ctx, state_cookie, arg)
+            .await,
+    );
+    // state只能使用一次, 回调后删除
+    if let Some(cookie) = http_only_cookie(OIDC_STATE_COOKIE, "", 0) {
+        response.headers_mut().append(SET_COOKIE, cookie);
+    }
+    response
+}
+
+pub async fn register(
+    Extension(req_ctx): Extension<ReqCtx>,
+    VJson(arg): VJson<RegisterReq>,
+) -> impl IntoResponse {
+    ApiResponse::from_result(USER_CONTROLLER.register(req_ctx, arg).await)
+}
+
+pub async fn verify_email(VQuery(arg): VQuery<VerifyEmailReq>) -> impl IntoResponse {
+    ApiResponse::from_result(USER_CONTROLLER.verify_email(arg.token).await)
+}
+
+pub async fn resend_email_verify(
+    Extension(req_ctx): Extension<ReqCtx>,
+    VJson(arg): VJson<ResendEmailVerifyReq>,
+) -> impl IntoResponse {
+    ApiResponse::from_result(USER_CONTROLLER.resend_email_verify(req_ctx, arg).await)
+}
+
+pub async fn forgot_password(
+    Extension(req_ctx): Extension<ReqCtx>,
+    VJson(arg): VJson<ForgotPasswordReq>,
+) -> impl IntoResponse {
+    ApiResponse::from_result(USER_CONTROLLER.forgot_password(req_ctx, arg).await)
+}
+
+pub async fn reset_password(VJson(arg): VJson<ResetPasswordReq>) -> impl IntoResponse {
+    ApiResponse::from_result(USER_CONTROLLER.reset_password(arg).await)
+}
+
+pub async fn totp_enroll(Extension(user): Extension<CtxUserInfo>) -> impl IntoResponse {
+    ApiResponse::from_result(USER_CONTROLLER.totp_enroll(user.id).await)
+}
+
+pub async fn totp_activate(
+    Extension(user): Extension<CtxUserInfo>,
+    VJson(arg): VJson<TotpCodeReq>,
+) -> impl IntoResponse {
+    ApiResponse::from_result(USER_CONTROLLER.totp_activate(user.id, arg.code).await)
+}
+
+pub async fn totp_disable(
+    Extension(user): Extension<CtxUserInfo>,
+    VJson(arg): VJson<TotpCodeReq>,
+) -> impl IntoResponse {
+    ApiResponse::from_result(USER_CONTROLLER.totp_disable(user.id, arg.code).await)
+}
+
+pub async fn totp_recovery_codes(
+    Extension(user): Extension<CtxUserInfo>,
+    VJson(arg): VJson<TotpCodeReq>,
+) -> impl IntoResponse {
+    ApiResponse::from_result(
+        USER_CONTROLLER
+            .totp_regenerate_recovery_codes(user.id, arg.code)
+            .await,
+    )
+}
+
+pub async fn list_api_keys(Extension(user): Extension<CtxUserInfo>) -> impl IntoResponse {
+    ApiResponse::from_result(USER_CONTROLLER.list_api_keys(user.id).await)
+}
+
+pub async fn create_api_key(
+    Extension(user): Extension<CtxUserInfo>,
+    VJson(arg): VJson<CreateApiKeyReq>,
+) -> impl IntoResponse {
+    ApiResponse::from_result(USER_CONTROLLER.create_api_key(user.id, arg).await)
+}
+
+pub async fn revoke_api_key(
+    Extension(user): Extension<CtxUserInfo>,
+    VJson(arg): VJson<GetByIdReq>,
+) -> impl IntoResponse {
+    ApiResponse::from_result(USER_CONTROLLER.revoke_api_key(user.id, arg.id).await)
+}
+
+pub async fn list_sessions(
+    Extension(user): Extension<CtxUserInfo>,
+    VQuery(arg): VQuery<SessionListReq>,
+) -> impl IntoResponse {
+    ApiResponse::from_result(
+        USER_CONTROLLER
+            .list_sessions(Some(user.id), arg)
+            .await
+            .map(|sessions| mark_current_session(sessions, user.token_id)),
+    )
+}
+
+pub async fn revoke_session(
+    Extension(user): Extension<CtxUserInfo>,
+    VJson(arg): VJson<GetByIdReq>,
+) -> impl IntoResponse {
+    ApiResponse::from_result(USER_CONTROLLER.revoke_session(Some(user.id), arg.id).await)
+}
+
+/// 管理员查看所有用户的会话
+pub async fn list_all_sessions(
+    Extension(user): Extension<CtxUserInfo>,
+    VQuery(arg): VQuery<SessionListReq>,
+) -> impl IntoResponse {
+    let result = match require_session_admin(&user) {
+        Ok(()) => USER_CONTROLLER
+            .list_sessions(None, arg)
+            .await
+            .map(|sessions| mark_current_session(sessions, user.token_id)),
+        Err(e) => Err(e),
+    };
+    ApiResponse::from_result(result)
+}
+
+/// 管理员吊销任意用户的会话
+pub async fn revoke_any_session(
+    Extension(user): Extension<CtxUserInfo>,
+    VJson(arg): VJson<GetByIdReq>,
+) -> impl IntoResponse {
+    let result = match require_session_admin(&user) {
+        Ok(()) => USER_CONTROLLER.revoke_session(None, arg.id).await,
+        Err(e) => Err(e),
+    };
+    ApiResponse::from_result(result)
+}
+
+fn require_session_admin(user: &CtxUserInfo) -> Result<(), AppError> {
+    if APP_CONFIG.auth.session.admin_role_ids.contains(&user.role) {
+        Ok(())
+    } else {
+        Err(AppError::WithStatus(
+            StatusCode::FORBIDDEN,
+            "无权管理其他用户的会话".to_string(),
+        ))
+    }
+}
+
+fn mark_current_session(sessions: Vec<UserSession>, token_id: i64) -> Vec<SessionRes> {
+    sessions
+        .into_iter()
+        .map(|session| SessionRes {
+            current: token_id != 0 && session.token_id == token_id,
+            session,
+        })
+        .collect()
+}
+
+// 登录成功且配置了Cookie认证时, 同时将token写入Cookie;
+// 登录用户写入响应扩展, 供操作日志中间件记录
+fn with_auth_cookie(result: Result<LoginResult, AppError>) -> Response {
+    let (cookie, login_user) = match &result {
+        Ok(LoginResult::Token(resp)) => (
+            auth_cookie(&resp.token),
+            Some(CtxUserInfo {
+                username: resp.user.username.clone(),
+                id: resp.user.id,
+                role: resp.user.role_id,
+                tenant_id: resp.user.tenant_id,
+                dept_id: resp.user.dept_id,
+                ..Default::default()
+            }),
+        ),
+        _ => (None, None),
+    };
+    let mut response = ApiResponse::from_result(result);
+    if let Some(cookie) = cookie {
+        response.headers_mut().append(SET_COOKIE, cookie);
+    }
+    if let Some(login_user) = login_user {
+        response.extensions_mut().insert(login_user);
+    }
+    response
 }
 
 pub async fn get_by_username(VQuery(arg): VQuery<GetByUsernameReq>) -> impl IntoResponse {
@@ -57,13 +326,69 @@ pub async fn get_by_id(VQuery(arg): VQuery<GetByIdReq>) -> impl IntoResponse {
 }
 
 pub trait UserControllerTrait {
-    async fn gen_captcha(&self, client_info: ClientInfoReq) -> Result<CaptchaImage, AppError>;
+    async fn gen_captcha(
+        &self,
+        req_ctx: ReqCtx,
+        client_info: ClientInfoReq,
+    ) -> Result<CaptchaImage, AppError>;
     async fn login_with_captcha(
         &self,
         req_ctx: ReqCtx,
         args: LoginWithCaptchaReq,
+    ) -> Result<LoginResult, AppError>;
+    async fn login(&self, req_ctx: ReqCtx, args: LoginReq) -> Result<LoginResult, AppError>;
+    async fn login_2fa(&self, req_ctx: ReqCtx, args: Login2faReq) -> Result<LoginResp, AppError>;
+    async fn oidc_authorize(&self, args: OidcAuthorizeReq) -> Result<OidcAuthorizeResp, AppError>;
+    async fn oidc_callback(
+        &self,
+        req_ctx: ReqCtx,
+        state_cookie: Option<String>,
+        args: OidcCallbackReq,
+    ) -> Result<LoginResult, AppError>;
+    async fn change_password(
+        &self,
+        req_ctx: ReqCtx,
+        user_id: i64,
+        args: ChangePasswordReq,
     ) -> Result<LoginResp, AppError>;
-    async fn login(&self, req_ctx: ReqCtx, args: LoginReq) -> Result<LoginResp, AppError>;
+    async fn register(&self, req_ctx: ReqCtx, args: RegisterReq) -> Result<(), AppError>;
+    async fn verify_email(&self, token: String) -> Result<(), AppError>;
+    async fn resend_email_verify(
+        &self,
+        req_ctx: ReqCtx,
+        args: ResendEmailVerifyReq,
+    ) -> Result<(), AppError>;
+    async fn forgot_password(
+        &self,
+        req_ctx: ReqCtx,
+        args: ForgotPasswordReq,
+    ) -> Result<(), AppError>;
+    async fn reset_password(&self, args: ResetPasswordReq) -> Result<(), AppError>;
+    async fn totp_enroll(&self, user_id: i64) -> Result<TotpEnrollment, AppError>;
+    async fn totp_activate(&self, user_id: i64, code: String) -> Result<Vec<String>, AppError>;
+    async fn totp_disable(&self, user_id: i64, code: String) -> Result<(), AppError>;
+    async fn totp_regenerate_recovery_codes(
+        &self,
+        user_id: i64,
+        code: String,
+    ) -> Result<Vec<String>, AppError>;
+    async fn create_api_key(
+        &self,
+        user_id: i64,
+        args: CreateApiKeyReq,
+    ) -> Result<ApiKeyCreated, AppError>;
+    async fn list_api_keys(&self, user_id: i64) -> Result<Vec<ApiKey>, AppError>;
+    async fn revoke_api_key(&self, user_id: i64, id: i64) -> Result<(), AppError>;
+    async fn authenticate_api_key(&self, key: String) -> Result<ApiKeyPrincipal, AppError>;
+    async fn create_session(&self, session: UserSession) -> Result<(), AppError>;
+    async fn update_last_login(&self, user_id: i64, ip: String) -> Result<(), AppError>;
+    async fn list_sessions(
+        &self,
+        user_id: Option<i64>,
+        args: SessionListReq,
+    ) -> Result<Vec<UserSession>, AppError>;
+    async fn revoke_session(&self, user_id: Option<i64>, token_id: i64) -> Result<(), AppError>;
+    async fn check_session(&self, token_id: i64) -> Result<(), AppError>;
     async fn get_by_username(&self, username: String) -> Result<Option<User>, AppError>;
     async fn get_by_id(&self, id: i64) -> Result<Option<User>, AppError>;
 }
@@ -73,45 +398,283 @@ pub struct UserController<T: UserDomainTrait + Sync + Send> {
 }
 
 impl<T: UserDomainTrait + Sync + Send> UserControllerTrait for UserController<T> {
-    async fn gen_captcha(&self, client_id: ClientInfoReq) -> Result<CaptchaImage, AppError> {
-        let width = client_id.width.unwrap_or(100);
-        let height = client_id.height.unwrap_or(40);
+    async fn gen_captcha(
+        &self,
+        req_ctx: ReqCtx,
+        client_id: ClientInfoReq,
+    ) -> Result<CaptchaImage, AppError> {
+        let width = client_id.width.unwrap_or(100).min(400);
+        let height = client_id.height.unwrap_or(40).min(200);
         self.user_domain
-            .gen_captcha(client_id.client_id, width, height)
+            .gen_captcha(client_id.client_id, req_ctx.ip, width, height)
             .await
             .map_err(|e| e.into())
     }
 
-    async fn login(&self, req_ctx: ReqCtx, args: LoginReq) -> Result<LoginResp, AppError> {
-        let start_time = Instant::now();
-        let user = self
+    async fn login(&self, req_ctx: ReqCtx, args: LoginReq) -> Result<LoginResult, AppError> {
+        let attempt = LoginAttempt::new(&args.username, LOGIN_TYPE_PASSWORD);
+        let outcome = self
             .user_domain
             .login(AuthDto {
                 username: args.username,
                 password: args.password,
                 client_id: args.client_id.unwrap_or_default(),
+                ip: req_ctx.ip.clone(),
             })
-            .await?;
+            .await;
+        let outcome = audit_login_failure(&req_ctx, &attempt, outcome).await?;
 
-        do_login(req_ctx, user, start_time).await
+        login_result(req_ctx, outcome, attempt).await
     }
     async fn login_with_captcha(
         &self,
         req_ctx: ReqCtx,
         args: LoginWithCaptchaReq,
-    ) -> Result<LoginResp, AppError> {
-        let start_time = Instant::now();
-        let user = self
+    ) -> Result<LoginResult, AppError> {
+        let attempt = LoginAttempt {
+            captcha_used: true,
+            ..LoginAttempt::new(&args.username, LOGIN_TYPE_PASSWORD)
+        };
+        let outcome = self
             .user_domain
             .login_with_captcha(AuthDtoWithCaptcha {
                 username: args.username,
                 password: args.password,
                 client_id: args.client_id,
                 captcha: args.captcha,
+                ip: req_ctx.ip.clone(),
             })
-            .await?;
+            .await;
+        let outcome = audit_login_failure(&req_ctx, &attempt, outcome).await?;
+
+        login_result(req_ctx, outcome, attempt).await
+    }
+    async fn login_2fa(&self, req_ctx: ReqCtx, args: Login2faReq) -> Result<LoginResp, AppError> {
+        // 挑战token对应的账号由领域服务在校验失败时返回
+        let attempt = LoginAttempt {
+            two_factor_used: true,
+            ..LoginAttempt::new("", LOGIN_TYPE_2FA)
+        };
+        let user = self
+            .user_domain
+            .login_2fa(Login2faDto {
+                challenge_token: args.challenge_token,
+                code: args.code,
+                ip: req_ctx.ip.clone(),
+            })
+            .await;
+        let user = audit_login_failure(&req_ctx, &attempt, user).await?;
+
+        do_login(req_ctx, user, attempt).await
+    }
+    async fn oidc_authorize(&self, args: OidcAuthorizeReq) -> Result<OidcAuthorizeResp, AppError> {
+        let authorize = self.user_domain.external_authorize(args.provider).await?;
+        Ok(OidcAuthorizeResp {
+            url: authorize.url,
+            state: authorize.state,
+        })
+    }
+    // 外部认证通过后与密码登录相同, 启用两步验证的账号先返回挑战token
+    async fn oidc_callback(
+        &self,
+        req_ctx: ReqCtx,
+        state_cookie: Option<String>,
+        args: OidcCallbackReq,
+    ) -> Result<LoginResult, AppError> {
+        let attempt = LoginAttempt::new("", LOGIN_TYPE_EXTERNAL);
+        // 回调必须来自发起授权的浏览器
+        let outcome = if state_cookie.as_deref() == Some(args.state.as_str()) {
+            self.user_domain
+                .external_login(ExternalLoginDto {
+                    provider: args.provider,
+                    code: args.code,
+                    state: args.state,
+                    ip: req_ctx.ip.clone(),
+                })
+                .await
+        } else {
+            Err(UserDomainError::AuthError(
+                "授权请求无效或已过期".to_string(),
+            ))
+        };
+        let outcome = audit_login_failure(&req_ctx, &attempt, outcome).await?;
 
-        do_login(req_ctx, user, start_time).await
+        login_result(req_ctx, outcome, attempt).await
+    }
+    // 修改密码后重新签发token, 清除密码过期标记
+    async fn change_password(
+        &self,
+        req_ctx: ReqCtx,
+        user_id: i64,
+        args: ChangePasswordReq,
+    ) -> Result<LoginResp, AppError> {
+        let user = self
+            .user_domain
+            .change_password(ChangePasswordDto {
+                user_id,
+                old_password: args.old_password,
+                new_password: args.new_password,
+            })
+            .await?;
+        let token = issue_token(&req_ctx, &user).await?;
+        Ok(LoginResp {
+            token: token.token,
+            user,
+        })
+    }
+    async fn register(&self, req_ctx: ReqCtx, args: RegisterReq) -> Result<(), AppError> {
+        self.user_domain
+            .register(RegisterDto {
+                username: args.username,
+                password: args.password,
+                email: args.email,
+                name: args.name,
+                client_id: args.client_id,
+                captcha: args.captcha,
+                ip: req_ctx.ip,
+            })
+            .await
+            .map_err(|e| e.into())
+    }
+    async fn verify_email(&self, token: String) -> Result<(), AppError> {
+        self.user_domain
+            .verify_email(token)
+            .await
+            .map_err(|e| e.into())
+    }
+    async fn resend_email_verify(
+        &self,
+        req_ctx: ReqCtx,
+        args: ResendEmailVerifyReq,
+    ) -> Result<(), AppError> {
+        self.user_domain
+            .resend_email_verify(ResendEmailVerifyDto {
+                email: args.email,
+                ip: req_ctx.ip,
+            })
+            .await
+            .map_err(|e| e.into())
+    }
+    async fn forgot_password(
+        &self,
+        req_ctx: ReqCtx,
+        args: ForgotPasswordReq,
+    ) -> Result<(), AppError> {
+        self.user_domain
+            .forgot_password(ForgotPasswordDto {
+                username: args.username,
+                ip: req_ctx.ip,
+            })
+            .await
+            .map_err(|e| e.into())
+    }
+    async fn reset_password(&self, args: ResetPasswordReq) -> Result<(), AppError> {
+        self.user_domain
+            .reset_password(ResetPasswordDto {
+                token: args.token,
+                new_password: args.new_password,
+            })
+            .await
+            .map_err(|e| e.into())
+    }
+    async fn totp_enroll(&self, user_id: i64) -> Result<TotpEnrollment, AppError> {
+        self.user_domain
+            .totp_enroll(user_id)
+            .await
+            .map_err(|e| e.into())
+    }
+    async fn totp_activate(&self, user_id: i64, code: String) -> Result<Vec<String>, AppError> {
+        self.user_domain
+            .totp_activate(user_id, code)
+            .await
+            .map_err(|e| e.into())
+    }
+    async fn totp_disable(&self, user_id: i64, code: String) -> Result<(), AppError> {
+        self.user_domain
+            .totp_disable(user_id, code)
+            .await
+            .map_err(|e| e.into())
+    }
+    async fn totp_regenerate_recovery_codes(
+        &self,
+        user_id: i64,
+        code: String,
+    ) -> Result<Vec<String>, AppError> {
+        self.user_domain
+            .totp_regenerate_recovery_codes(user_id, code)
+            .await
+            .map_err(|e| e.into())
+    }
+    async fn create_api_key(
+        &self,
+        user_id: i64,
+        args: CreateApiKeyReq,
+    ) -> Result<ApiKeyCreated, AppError> {
+        self.user_domain
+            .create_api_key(CreateApiKeyDto {
+                user_id,
+                name: args.name,
+                scopes: args.scopes,
+                expires_in_days: args.expires_in_days,
+            })
+            .await
+            .map_err(|e| e.into())
+    }
+    async fn list_api_keys(&self, user_id: i64) -> Result<Vec<ApiKey>, AppError> {
+        self.user_domain
+            .list_api_keys(user_id)
+            .await
+            .map_err(|e| e.into())
+    }
+    async fn revoke_api_key(&self, user_id: i64, id: i64) -> Result<(), AppError> {
+        self.user_domain
+            .revoke_api_key(user_id, id)
+            .await
+            .map_err(|e| e.into())
+    }
+    async fn authenticate_api_key(&self, key: String) -> Result<ApiKeyPrincipal, AppError> {
+        self.user_domain
+            .authenticate_api_key(key)
+            .await
+            .map_err(|e| e.into())
+    }
+    async fn create_session(&self, session: UserSession) -> Result<(), AppError> {
+        self.user_domain
+            .create_session(session)
+            .await
+            .map_err(|e| e.into())
+    }
+    async fn update_last_login(&self, user_id: i64, ip: String) -> Result<(), AppError> {
+        self.user_domain
+            .update_last_login(user_id, ip)
+            .await
+            .map_err(|e| e.into())
+    }
+    async fn list_sessions(
+        &self,
+        user_id: Option<i64>,
+        args: SessionListReq,
+    ) -> Result<Vec<UserSession>, AppError> {
+        self.user_domain
+            .list_sessions(
+                user_id,
+                args.page.unwrap_or(1),
+                args.page_size.unwrap_or(20),
+            )
+            .await
+            .map_err(|e| e.into())
+    }
+    async fn revoke_session(&self, user_id: Option<i64>, token_id: i64) -> Result<(), AppError> {
+        self.user_domain
+            .revoke_session(user_id, token_id)
+            .await
+            .map_err(|e| e.into())
+    }
+    async fn check_session(&self, token_id: i64) -> Result<(), AppError> {
+        self.user_domain
+            .check_session(token_id)
+            .await
+            .map_err(|e| e.into())
     }
     async fn get_by_username(&self, username: String) -> Result<Option<User>, AppError> {
         self.user_domain
@@ -124,43 +687,161 @@ impl<T: UserDomainTrait + Sync + Send> UserControllerTrait for UserController<T>
     }
 }
 
-async fn do_login(
+/// 登录审计信息
+struct LoginAttempt {
+    username: String,
+    login_type: &'static str,
+    captcha_used: bool,
+    two_factor_used: bool,
+}
+
+impl LoginAttempt {
+    fn new(username: &str, login_type: &'static str) -> Self {
+        Self {
+            username: username.to_string(),
+            login_type,
+            captcha_used: false,
+            two_factor_used: false,
+        }
+    }
+}
+
+// 认证失败(含账号锁定)时记录登录日志
+async fn audit_login_failure<T>(
+    req_ctx: &ReqCtx,
+    attempt: &LoginAttempt,
+    result: Result<T, UserDomainError>,
+) -> Result<T, AppError> {
+    if let Err(e) = &result {
+        let subject = match e {
+            UserDomainError::LoginFailed { subject, .. } => subject.clone(),
+            _ => failed_login_subject(&attempt.username).await,
+        };
+        record_login(req_ctx, attempt, subject, Some(e.to_string())).await;
+    }
+    result.map_err(|e| e.into())
+}
+
+// 按用户名查找登录失败的账号, 查不到时只记录用户名
+async fn failed_login_subject(username: &str) -> LoginSubject {
+    if username.is_empty() {
+        return LoginSubject::default();
+    }
+    match USER_CONTROLLER.get_by_username(username.to_string()).await {
+        Ok(Some(user)) => LoginSubject::from(&user),
+        _ => LoginSubject::username(username),
+    }
+}
+
+// 记录登录日志, 写入失败不影响登录结果
+async fn record_login(
+    req_ctx: &ReqCtx,
+    attempt: &LoginAttempt,
+    subject: LoginSubject,
+    fail_reason: Option<String>,
+) {
+    let log = LoginLog {
+        user_id: subject.user_id,
+        username: subject.username,
+        // 登录接口没有租户上下文, 日志归属登录用户的租户
+        tenant_id: subject.tenant_id,
+        dept_id: subject.dept_id,
+        status: if fail_reason.is_some() {
+            LOGIN_STATUS_FAIL
+        } else {
+            LOGIN_STATUS_SUCCESS
+        },
+        fail_reason,
+        login_type: attempt.login_type.to_string(),
+        ip: req_ctx.ip.clone(),
+        user_agent: req_ctx.user_agent.clone(),
+        captcha_used: attempt.captcha_used,
+        two_factor_used: attempt.two_factor_used,
+        request_id: req_ctx.request_id.clone(),
+        login_time: Local::now(),
+        ..Default::default()
+    };
+    if let Err(e) = LOGIN_LOG_DOMAIN.record(log).await {
+        web_error!(" -- 登录日志写入失败: {:?}", e);
+    }
+}
+
+// 启用两步验证的账号先返回挑战token, 否则直接签发token
+async fn login_result(
     req_ctx: ReqCtx,
-    user: UserInfoDto,
-    start_time: Instant,
-) -> Result<LoginResp, AppError> {
-    let auth_pyload = Claims {
+    outcome: LoginOutcome,
+    attempt: LoginAttempt,
+) -> Result<LoginResult, AppError> {
+    match outcome {
+        LoginOutcome::Authenticated(user) => do_login(req_ctx, *user, attempt)
+            .await
+            .map(|resp| LoginResult::Token(Box::new(resp))),
+        LoginOutcome::TwoFactorRequired {
+            challenge_token,
+            expires_in,
+        } => Ok(LoginResult::TwoFactor(TwoFactorChallengeResp {
+            challenge_token,
+            expires_in,
+        })),
+    }
+}
+
+fn user_claims(user: &UserInfoDto) -> Claims {
+    Claims {
         username: user.username.clone(),
         id: user.id,
         role: user.role_id,
+        tenant_id: user.tenant_id,
+        dept_id: user.dept_id,
         token_id: next_id(),
+        password_expired: user.password_expired,
         ..Default::default()
-    };
-    let token = authorize(auth_pyload).await?;
-    let res = LoginResp {
-        token: token.token,
-        user: user,
-    };
-    // 记录操作日志
-    OPERATOR_LOG_DOMAIN
-        .create(OperaterLog {
-            id: next_id(),
-            api_name: req_ctx.path.clone(),
-            oper_ip: req_ctx.ip.clone(),
-            oper_id: res.user.id,
-            oper_name: res.user.username.clone(),
-            oper_url: req_ctx.ori_uri.clone(),
-            oper_location: req_ctx.ori_uri.clone(),
-            request_method: req_ctx.method.clone(),
-            oper_param: req_ctx.path_params.clone(),
-            json_result: serde_json::to_string(&res).unwrap_or_default(),
-            cost_time: start_time.elapsed().as_millis() as i64,
-            oper_time: Local::now(),
+    }
+}
+
+// 签发token并记录登录会话
+async fn issue_token(req_ctx: &ReqCtx, user: &UserInfoDto) -> Result<AuthBody, AppError> {
+    let claims = user_claims(user);
+    let token = authorize(claims.clone()).await?;
+    USER_CONTROLLER
+        .create_session(UserSession {
+            token_id: claims.token_id,
+            user_id: user.id,
+            tenant_id: user.tenant_id,
+            username: user.username.clone(),
+            ip: req_ctx.ip.clone(),
+            user_agent: req_ctx.user_agent.clone(),
+            expires_at: Local.timestamp_opt(token.exp, 0).single(),
             ..Default::default()
         })
         .await?;
+    Ok(token)
+}
 
-    Ok(res)
+// 签发token, 记录登录日志并更新用户最近一次登录
+async fn do_login(
+    req_ctx: ReqCtx,
+    user: UserInfoDto,
+    attempt: LoginAttempt,
+) -> Result<LoginResp, AppError> {
+    let token = match issue_token(&req_ctx, &user).await {
+        Ok(token) => token,
+        Err(e) => {
+            record_login(&req_ctx, &attempt, (&user).into(), Some(e.to_string())).await;
+            return Err(e);
+        }
+    };
+    record_login(&req_ctx, &attempt, (&user).into(), None).await;
+    if let Err(e) = USER_CONTROLLER
+        .update_last_login(user.id, req_ctx.ip.clone())
+        .await
+    {
+        web_error!(" -- 更新最近登录信息失败: {:?}", e);
+    }
+    Ok(LoginResp {
+        token: token.token,
+        user,
+    })
 }
 
 impl<T: UserDomainTrait + Sync + Send> UserController<T> {
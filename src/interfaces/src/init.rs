@@ -2,14 +2,18 @@ use axum::Router;
 use axum_server::tls_rustls::RustlsConfig;
 use commonx::error::AppError;
 use commonx::{config::APP_CONFIG, web_info};
+use once_cell::sync::Lazy;
 use std::{net::SocketAddr, str::FromStr, time::Duration};
 use tokio::signal;
 
-use crate::routes::init_routes;
+use crate::{common::jwt_keys::KEYS, routes::init_routes};
 
 pub async fn start_server() -> Result<(), AppError> {
     let server_config = &APP_CONFIG.server;
     let addr = format!("{}:{}", server_config.host, server_config.port);
+    // 启动时加载并校验JWT密钥, 避免密钥配置错误时到首次登录才发现
+    Lazy::force(&KEYS);
+    web_info!("-3.0 加载JWT密钥...[ok]");
     let router = init_routes();
     web_info!("-3.1 加载路由...[ok]");
 
@@ -48,7 +52,7 @@ async fn start_https_server(route: Router, addr: &str) -> Result<(), Box<dyn std
     web_info!("启动https服务: {}", addr);
     axum_server::bind_rustls(socket_addr, config)
         .handle(handle)
-        .serve(route.into_make_service())
+        .serve(route.into_make_service_with_connect_info::<SocketAddr>())
         .await
         .map_err(|e| format!("HTTPS server error: {}", e))?;
     Ok(())
@@ -59,10 +63,13 @@ async fn start_http_server(app: Router, addr: &str) -> Result<(), Box<dyn std::e
         .await
         .map_err(|e| format!("Failed to bind to address: {}", e))?;
     tracing::info!("启动http服务: {}", addr);
-    axum::serve(listener, app.into_make_service())
-        .with_graceful_shutdown(shutdown_signal())
-        .await
-        .map_err(|e| format!("HTTP server error: {}", e))?;
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .with_graceful_shutdown(shutdown_signal())
+    .await
+    .map_err(|e| format!("HTTP server error: {}", e))?;
     Ok(())
 }
 
@@ -1,11 +1,36 @@
 use axum::{extract::Request, middleware::Next, response::Response};
 use hyper::StatusCode;
+use userDomain::entity::api_key::scopes_allow;
+
+use crate::{API_PATH_PRE, types::user_info::CtxUserInfo};
+
+// 密码过期后仍允许访问的接口
+const PASSWORD_EXPIRED_ALLOWED: &str = "/sys/user/change_password";
+
+// API Key 管理接口只允许登录用户访问, 防止API Key自我扩权
+const API_KEY_MANAGE_PATH: &str = "/sys/user/api_keys";
 
 pub async fn check_permission_mid(
     req: Request,
     next: Next,
 ) -> Result<Response, (StatusCode, String)> {
     // let ctx = req.extensions().get::<ReqCtx>().expect("ReqCtx not found");
-    // let user = req.extensions().get::<UserInfo>().expect("用户信息不存在");
+    if let Some(user) = req.extensions().get::<CtxUserInfo>() {
+        if user.password_expired && !req.uri().path().ends_with(PASSWORD_EXPIRED_ALLOWED) {
+            return Err((
+                StatusCode::FORBIDDEN,
+                "密码已过期, 请先修改密码".to_string(),
+            ));
+        }
+        if let Some(scopes) = &user.api_key_scopes {
+            let path = req.uri().path();
+            let path = path.strip_prefix(API_PATH_PRE).unwrap_or(path);
+            if path.starts_with(API_KEY_MANAGE_PATH)
+                || !scopes_allow(scopes, req.method().as_str(), path)
+            {
+                return Err((StatusCode::FORBIDDEN, "API Key无权访问该接口".to_string()));
+            }
+        }
+    }
     Ok(next.run(req).await)
 }
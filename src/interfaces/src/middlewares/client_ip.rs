@@ -0,0 +1,208 @@
+//! 客户端IP解析
+//!
+//! 以连接的对端地址为准, 只有对端是受信任的代理时才读取转发请求头。
+//! 转发链从右往左逐跳回溯, 遇到第一个不受信任的地址即为客户端IP。
+
+use std::net::{IpAddr, SocketAddr};
+
+use axum::{extract::ConnectInfo, http::HeaderMap};
+use commonx::{config::APP_CONFIG, web_warn};
+use ipnet::IpNet;
+use once_cell::sync::Lazy;
+
+static TRUSTED_PROXIES: Lazy<Vec<IpNet>> =
+    Lazy::new(|| parse_ip_nets(&APP_CONFIG.server.trusted_proxies));
+
+/// 解析IP或CIDR列表, 单个IP视为主机地址, 无效项记录警告后忽略
+pub fn parse_ip_nets(values: &[String]) -> Vec<IpNet> {
+    values
+        .iter()
+        .filter_map(|v| {
+            let v = v.trim();
+            let net = v
+                .parse::<IpNet>()
+                .ok()
+                .or_else(|| v.parse::<IpAddr>().ok().map(IpNet::from));
+            if net.is_none() {
+                web_warn!("忽略无效的IP地址配置:{}", v);
+            }
+            net
+        })
+        .collect()
+}
+
+/// 从请求中解析客户端IP, 需要以`into_make_service_with_connect_info`启动服务
+pub fn client_ip(extensions: &axum::http::Extensions, headers: &HeaderMap) -> Option<IpAddr> {
+    let peer = extensions
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip());
+    resolve_client_ip(peer, headers, &TRUSTED_PROXIES)
+}
+
+fn resolve_client_ip(
+    peer: Option<IpAddr>,
+    headers: &HeaderMap,
+    trusted: &[IpNet],
+) -> Option<IpAddr> {
+    let peer = normalize(peer?);
+    if !is_trusted(trusted, peer) {
+        return Some(peer);
+    }
+    // 优先使用RFC 7239 Forwarded, 其次X-Forwarded-For
+    let hops = forwarded_hops(headers).or_else(|| x_forwarded_for_hops(headers));
+    let Some(hops) = hops else {
+        return Some(x_real_ip(headers).unwrap_or(peer));
+    };
+    let mut client = peer;
+    for hop in hops.into_iter().rev() {
+        // 无法识别的地址(如unknown、混淆标识)不再继续回溯
+        let Some(ip) = hop else {
+            break;
+        };
+        client = ip;
+        if !is_trusted(trusted, ip) {
+            break;
+        }
+    }
+    Some(client)
+}
+
+fn is_trusted(trusted: &[IpNet], ip: IpAddr) -> bool {
+    trusted.iter().any(|net| net.contains(&ip))
+}
+
+/// IPv4映射的IPv6地址按IPv4处理
+fn normalize(ip: IpAddr) -> IpAddr {
+    match ip {
+        IpAddr::V6(v6) => v6.to_ipv4_mapped().map_or(IpAddr::V6(v6), IpAddr::V4),
+        v4 => v4,
+    }
+}
+
+fn header_values<'a>(headers: &'a HeaderMap, name: &str) -> Vec<&'a str> {
+    headers
+        .get_all(name)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .collect()
+}
+
+/// `Forwarded: for=192.0.2.60;proto=http, for="[2001:db8::1]:4711"`
+fn forwarded_hops(headers: &HeaderMap) -> Option<Vec<Option<IpAddr>>> {
+    let hops: Vec<Option<IpAddr>> = header_values(headers, "forwarded")
+        .into_iter()
+        .flat_map(|v| v.split(','))
+        .filter_map(|element| {
+            element.split(';').find_map(|pair| {
+                let (key, value) = pair.split_once('=')?;
+                key.trim()
+                    .eq_ignore_ascii_case("for")
+                    .then(|| parse_node(value))
+            })
+        })
+        .collect();
+    (!hops.is_empty()).then_some(hops)
+}
+
+fn x_forwarded_for_hops(headers: &HeaderMap) -> Option<Vec<Option<IpAddr>>> {
+    let hops: Vec<Option<IpAddr>> = header_values(headers, "x-forwarded-for")
+        .into_iter()
+        .flat_map(|v| v.split(','))
+        .map(|v| v.trim())
+        .filter(|v| !v.is_empty())
+        .map(parse_node)
+        .collect();
+    (!hops.is_empty()).then_some(hops)
+}
+
+fn x_real_ip(headers: &HeaderMap) -> Option<IpAddr> {
+    headers
+        .get("x-real-ip")
+        .and_then(|v| v.to_str().ok())
+        .and_then(parse_node)
+}
+
+/// 解析`ip`、`ip:port`、`[ipv6]:port`, 可带引号
+fn parse_node(value: &str) -> Option<IpAddr> {
+    let value = value.trim().trim_matches('"');
+    if let Ok(ip) = value.parse::<IpAddr>() {
+        return Some(normalize(ip));
+    }
+    if let Some(rest) = value.strip_prefix('[') {
+        return rest
+            .split_once(']')
+            .and_then(|(ip, _)| ip.parse::<IpAddr>().ok())
+            .map(normalize);
+    }
+    value
+        .parse::<SocketAddr>()
+        .ok()
+        .map(|addr| normalize(addr.ip()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(pairs: &[(&'static str, &'static str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.append(*name, value.parse().unwrap());
+        }
+        headers
+    }
+
+    fn ip(s: &str) -> Option<IpAddr> {
+        Some(s.parse().unwrap())
+    }
+
+    #[test]
+    fn untrusted_peer_ignores_headers() {
+        let trusted = parse_ip_nets(&["10.0.0.0/8".to_string()]);
+        let h = headers(&[("x-forwarded-for", "1.1.1.1"), ("x-real-ip", "2.2.2.2")]);
+        assert_eq!(
+            resolve_client_ip(ip("203.0.113.9"), &h, &trusted),
+            ip("203.0.113.9")
+        );
+        assert_eq!(resolve_client_ip(None, &h, &trusted), None);
+    }
+
+    #[test]
+    fn walks_forwarded_chain_from_right() {
+        let trusted = parse_ip_nets(&["10.0.0.0/8".to_string(), "192.168.1.1".to_string()]);
+        // 最左侧的地址可被客户端伪造, 取最右侧的不受信任地址
+        let h = headers(&[("x-forwarded-for", "6.6.6.6, 203.0.113.9, 10.0.0.2")]);
+        assert_eq!(
+            resolve_client_ip(ip("192.168.1.1"), &h, &trusted),
+            ip("203.0.113.9")
+        );
+        let h = headers(&[
+            ("forwarded", r#"for="[2001:db8::1]:4711";proto=https"#),
+            ("forwarded", "for=10.0.0.3"),
+            ("x-forwarded-for", "6.6.6.6"),
+        ]);
+        assert_eq!(
+            resolve_client_ip(ip("10.0.0.1"), &h, &trusted),
+            ip("2001:db8::1")
+        );
+        let h = headers(&[("forwarded", "for=unknown, for=10.0.0.3")]);
+        assert_eq!(
+            resolve_client_ip(ip("10.0.0.1"), &h, &trusted),
+            ip("10.0.0.3")
+        );
+    }
+
+    #[test]
+    fn falls_back_to_real_ip_and_peer() {
+        let trusted = parse_ip_nets(&["127.0.0.1".to_string()]);
+        let h = headers(&[("x-real-ip", "198.51.100.7")]);
+        assert_eq!(
+            resolve_client_ip(ip("127.0.0.1"), &h, &trusted),
+            ip("198.51.100.7")
+        );
+        assert_eq!(
+            resolve_client_ip(ip("::ffff:127.0.0.1"), &HeaderMap::new(), &trusted),
+            ip("127.0.0.1")
+        );
+    }
+}
@@ -0,0 +1,192 @@
+//! IP访问控制
+//!
+//! 全局规则作用于所有请求, 分组规则由RouterGroup按名称引用, 作用于该分组下的全部路由。
+//! 分组规则在鉴权之前按匹配路径查找, 被拒绝的请求不会进入token校验。
+//! 规则保存在内存中, 修改配置文件后调用`reload_ip_filter`即可生效, 无需重启。
+
+use std::{
+    collections::HashMap,
+    net::IpAddr,
+    sync::{Arc, RwLock},
+};
+
+use axum::{
+    extract::{MatchedPath, Request, State},
+    middleware::Next,
+    response::Response,
+};
+use commonx::{
+    config::{
+        APP_CONFIG,
+        config::{Config, IpFilterConfig, IpRuleConfig},
+    },
+    error::AppError,
+    web_info, web_warn,
+};
+use hyper::StatusCode;
+use ipnet::IpNet;
+use once_cell::sync::Lazy;
+
+use crate::{
+    middlewares::client_ip::{client_ip, parse_ip_nets},
+    resp::ApiResponse,
+};
+
+static IP_FILTER: Lazy<RwLock<IpFilter>> =
+    Lazy::new(|| RwLock::new(IpFilter::new(&APP_CONFIG.server.middlewares.ip_filter)));
+
+#[derive(Debug, Default)]
+struct IpRules {
+    allow: Vec<IpNet>,
+    deny: Vec<IpNet>,
+}
+
+impl IpRules {
+    fn new(config: &IpRuleConfig) -> Self {
+        Self {
+            allow: parse_ip_nets(&config.allow),
+            deny: parse_ip_nets(&config.deny),
+        }
+    }
+
+    /// 无法解析客户端IP时, 只有未配置allow才放行
+    fn permits(&self, ip: Option<IpAddr>) -> bool {
+        match ip {
+            Some(ip) => {
+                !self.deny.iter().any(|net| net.contains(&ip))
+                    && (self.allow.is_empty() || self.allow.iter().any(|net| net.contains(&ip)))
+            }
+            None => self.allow.is_empty(),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct IpFilter {
+    enable: bool,
+    global: IpRules,
+    groups: HashMap<String, IpRules>,
+}
+
+impl IpFilter {
+    fn new(config: &IpFilterConfig) -> Self {
+        Self {
+            enable: config.enable,
+            global: IpRules::new(&config.global),
+            groups: config
+                .groups
+                .iter()
+                .map(|(name, rules)| (name.clone(), IpRules::new(rules)))
+                .collect(),
+        }
+    }
+
+    /// 返回拒绝访问的规则名称, 放行时返回None
+    fn check<'a>(&self, groups: &'a [String], ip: Option<IpAddr>) -> Option<&'a str> {
+        if !self.enable {
+            return None;
+        }
+        groups.iter().map(String::as_str).find(|name| {
+            self.groups
+                .get(*name)
+                .is_some_and(|rules| !rules.permits(ip))
+        })
+    }
+}
+
+/// 重新读取配置文件中的IP访问控制规则
+pub fn reload_ip_filter() -> Result<(), AppError> {
+    let config = Config::reload()?;
+    let filter = IpFilter::new(&config.server.middlewares.ip_filter);
+    web_info!(
+        "重新加载IP访问控制规则, 启用:{} 分组:{:?}",
+        filter.enable,
+        filter.groups.keys().collect::<Vec<_>>()
+    );
+    *IP_FILTER
+        .write()
+        .map_err(|e| AppError::InternalError(format!("IP访问控制规则加锁失败: {}", e)))? = filter;
+    Ok(())
+}
+
+/// 全局IP访问控制
+pub async fn ip_filter_mid(req: Request, next: Next) -> Response {
+    let ip = client_ip(req.extensions(), req.headers());
+    let permitted = IP_FILTER
+        .read()
+        .map(|filter| !filter.enable || filter.global.permits(ip))
+        .unwrap_or(true);
+    if !permitted {
+        return reject(&req, ip, "global");
+    }
+    next.run(req).await
+}
+
+/// 路由使用的IP访问控制分组, 键为路由的完整匹配路径
+#[derive(Debug, Clone, Default)]
+pub struct IpFilterGroups {
+    pub routes: Arc<HashMap<String, Vec<String>>>,
+}
+
+/// 分组IP访问控制, 分组名称由RouterGroup::ip_filter指定, 子分组继承上级分组的规则
+pub async fn group_ip_filter_mid(
+    State(groups): State<IpFilterGroups>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let Some(groups) = req
+        .extensions()
+        .get::<MatchedPath>()
+        .and_then(|path| groups.routes.get(path.as_str()))
+    else {
+        return next.run(req).await;
+    };
+    let ip = client_ip(req.extensions(), req.headers());
+    let denied = IP_FILTER
+        .read()
+        .ok()
+        .and_then(|filter| filter.check(groups, ip).map(str::to_string));
+    if let Some(group) = denied {
+        return reject(&req, ip, &group);
+    }
+    next.run(req).await
+}
+
+fn reject(req: &Request, ip: Option<IpAddr>, rule: &str) -> Response {
+    web_warn!(
+        "IP访问被拒绝, 规则:{} ip:{} method:{} path:{}",
+        rule,
+        ip.map_or_else(|| "unknown IP".to_string(), |ip| ip.to_string()),
+        req.method(),
+        req.uri().path()
+    );
+    ApiResponse::error_response(StatusCode::FORBIDDEN, "当前IP无权访问".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deny_before_allow() {
+        let strings = |v: &[&str]| v.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        let filter = IpFilter::new(&IpFilterConfig {
+            enable: true,
+            global: IpRuleConfig::default(),
+            groups: HashMap::from([(
+                "admin".to_string(),
+                IpRuleConfig {
+                    allow: strings(&["10.0.0.0/8"]),
+                    deny: strings(&["10.0.0.9"]),
+                },
+            )]),
+        });
+        let groups = strings(&["admin", "missing"]);
+        let ip = |s: &str| Some(s.parse::<IpAddr>().unwrap());
+        assert_eq!(filter.check(&groups, ip("10.1.2.3")), None);
+        assert_eq!(filter.check(&groups, ip("10.0.0.9")), Some("admin"));
+        assert_eq!(filter.check(&groups, ip("8.8.8.8")), Some("admin"));
+        assert_eq!(filter.check(&groups, None), Some("admin"));
+        assert!(filter.global.permits(None));
+    }
+}
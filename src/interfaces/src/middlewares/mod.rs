@@ -1,29 +1,47 @@
 mod auth;
+pub mod client_ip;
+pub mod ip_filter;
 mod operater_log;
+pub mod rate_limit;
 pub mod request_log;
+mod tenant;
 
-use std::{any::Any, time::Duration};
+use std::{any::Any, str::FromStr, time::Duration};
 
 use axum::{
     Router,
     extract::{DefaultBodyLimit, Request},
-    http::HeaderName,
-    middleware,
+    http::{HeaderName, HeaderValue, Method},
+    middleware::{self, Next},
     response::{IntoResponse, Response},
 };
-use commonx::{config::APP_CONFIG, web_error, web_info};
+use commonx::{
+    config::{
+        APP_CONFIG,
+        config::{CorsConfig, Middlewares},
+    },
+    request_id::{REQUEST_ID_HEADER, with_request_id},
+    web_error, web_info, web_warn,
+};
 use hyper::StatusCode;
 use tower_http::{
     catch_panic::CatchPanicLayer,
     compression::{CompressionLayer, DefaultPredicate, Predicate, predicate::NotForContentType},
-    cors::CorsLayer,
+    cors::{AllowHeaders, AllowMethods, CorsLayer},
     limit::RequestBodyLimitLayer,
-    request_id::{MakeRequestUuid, SetRequestIdLayer},
+    request_id::{MakeRequestUuid, PropagateRequestIdLayer, SetRequestIdLayer},
     timeout::TimeoutLayer,
 };
+use tracing::Instrument;
 
 use crate::{
-    middlewares::{auth::check_permission_mid, operater_log::operate_log_fn_mid},
+    middlewares::{
+        auth::check_permission_mid,
+        ip_filter::{IpFilterGroups, group_ip_filter_mid},
+        operater_log::operate_log_fn_mid,
+        rate_limit::{IpRateLimits, ip_rate_limit_mid},
+        tenant::tenant_scope_mid,
+    },
     types::user_info::CtxUserInfo,
 };
 
@@ -34,56 +52,83 @@ pub struct ReqCtx {
     pub ip: String,
     pub ori_uri: String,
     pub path: String,
-    pub path_params: String,
     pub method: String,
-    // pub user_agent: String,
+    pub user_agent: String,
+    /// 查询参数
+    pub query: String,
+    /// 请求体, 只保留JSON及表单请求
+    pub body: String,
+    pub request_id: String,
 }
 
-pub fn set_no_auth_middleware(router: Router) -> Router {
+pub fn set_no_auth_middleware(
+    router: Router,
+    ip_filters: IpFilterGroups,
+    ip_limits: IpRateLimits,
+) -> Router {
+    // 登录等接口由处理函数提供用户信息
     router
+        .layer(middleware::from_fn(operate_log_fn_mid))
+        .layer(middleware::from_fn_with_state(ip_limits, ip_rate_limit_mid))
+        .layer(middleware::from_fn_with_state(
+            ip_filters,
+            group_ip_filter_mid,
+        ))
 }
 
-pub fn set_auth_middleware(router: Router) -> Router {
+pub fn set_auth_middleware(
+    router: Router,
+    ip_filters: IpFilterGroups,
+    ip_limits: IpRateLimits,
+) -> Router {
     router
         .layer(middleware::from_fn(operate_log_fn_mid))
         .layer(middleware::from_fn(check_permission_mid))
+        .layer(middleware::from_fn(tenant_scope_mid)) // 设置租户上下文
         // .layer(middleware::from_fn(req_info_fn_mid)) // 注入请求信息
         .layer(middleware::from_extractor::<CtxUserInfo>()) //从token中注入用户信息
+        .layer(middleware::from_fn_with_state(ip_limits, ip_rate_limit_mid)) // 鉴权前按IP限流
+        // 鉴权前按分组IP访问控制
+        .layer(middleware::from_fn_with_state(
+            ip_filters,
+            group_ip_filter_mid,
+        ))
 }
 
 pub fn set_common_middleware(mut router: Router) -> Router {
-    let server_config = &APP_CONFIG.server;
+    let middlewares = &APP_CONFIG.server.middlewares;
 
     // payload 限制
-    if let Some(limit) = server_config.middlewares.limit_payload.as_ref() {
-        if let Ok(size) = byte_unit::Byte::parse_str(&limit, true) {
-            // 1. 禁用默认请求体限制，改用自定义限制（10MB = 10 * 1024 * 1024 字节）
-            router = router
-                .layer(DefaultBodyLimit::disable())
-                .layer(RequestBodyLimitLayer::new(10 * 1024 * 1024));
-            web_info!("{MIDDLEWARE_NAME} 添加payload限制{:?}", size);
+    if let Some(limit) = middlewares.limit_payload.as_ref() {
+        match byte_unit::Byte::parse_str(limit, true) {
+            Ok(size) => {
+                // 禁用默认的2MB限制, 改用配置的大小
+                router = router
+                    .layer(DefaultBodyLimit::disable())
+                    .layer(RequestBodyLimitLayer::new(size.as_u64() as usize));
+                web_info!("{MIDDLEWARE_NAME} 添加payload限制{}", size);
+            }
+            Err(e) => web_warn!("{MIDDLEWARE_NAME} payload限制配置无效:{} {}", limit, e),
         }
     }
     // CORS配置
-    router = router.layer(
-        CorsLayer::new()
-            .allow_origin(tower_http::cors::Any)
-            .allow_methods(tower_http::cors::Any)
-            .allow_headers(tower_http::cors::Any),
-    );
+    if let Some(cors) = middlewares.cors.as_ref().filter(|c| c.enable) {
+        router = router.layer(cors_layer(cors));
+        web_info!("{MIDDLEWARE_NAME} 添加CORS中间件");
+    }
     // Panic处理
-    router = router.layer(CatchPanicLayer::custom(handle_panic));
+    if Middlewares::is_enabled(&middlewares.catch_panic) {
+        router = router.layer(CatchPanicLayer::custom(handle_panic));
+        web_info!("{MIDDLEWARE_NAME} 添加panic处理中间件");
+    }
     // 压缩
-    if let Some(compression) = server_config.middlewares.compression.clone() {
-        if compression.enable {
-            let predicate =
-                DefaultPredicate::new().and(NotForContentType::new("text/event-stream"));
-            router = router.layer(CompressionLayer::new().compress_when(predicate));
-            web_info!("{MIDDLEWARE_NAME} 添加压缩中间件");
-        }
+    if Middlewares::is_enabled(&middlewares.compression) {
+        let predicate = DefaultPredicate::new().and(NotForContentType::new("text/event-stream"));
+        router = router.layer(CompressionLayer::new().compress_when(predicate));
+        web_info!("{MIDDLEWARE_NAME} 添加压缩中间件");
     }
     // 超时
-    if let Some(time_request) = server_config.middlewares.timeout_request.as_ref() {
+    if let Some(time_request) = middlewares.timeout_request.as_ref() {
         if time_request.enable {
             router = router.layer(TimeoutLayer::with_status_code(
                 StatusCode::REQUEST_TIMEOUT,
@@ -92,27 +137,106 @@ pub fn set_common_middleware(mut router: Router) -> Router {
             web_info!("{MIDDLEWARE_NAME} 添加超时{}ms中间件", time_request.timeout);
         }
     }
-    // 需要设置一个请求头的键名，一般叫x-request-id
-    router = router.layer(SetRequestIdLayer::new(
-        HeaderName::from_static("x-request-id"),
-        MakeRequestUuid,
-    ));
+    // IP访问控制, 规则可重新加载, 始终挂载
+    router = router.layer(middleware::from_fn(ip_filter::ip_filter_mid));
+    // 请求ID上下文, 响应体及日志中携带请求ID
+    router = router.layer(middleware::from_fn(request_id_mid));
+    // 需要设置一个请求头的键名，一般叫x-request-id; 先生成再原样写回响应头
+    let request_id_header = HeaderName::from_static(REQUEST_ID_HEADER);
+    router = router
+        .layer(PropagateRequestIdLayer::new(request_id_header.clone()))
+        .layer(SetRequestIdLayer::new(request_id_header, MakeRequestUuid));
 
     router
 }
 
-pub fn parse_ip(req: &Request) -> String {
-    req.headers()
-        .get("x-forwarded-for")
-        .and_then(|h| h.to_str().ok())
-        .map(|ip| {
-            ip.split(',')
-                .next()
-                .unwrap_or("unknown IP")
-                .trim()
-                .to_string()
+/// 设置请求ID上下文, 并将请求ID附加到当前请求的日志span
+async fn request_id_mid(req: Request, next: Next) -> Response {
+    let request_id = req
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default()
+        .to_string();
+    let span = tracing::info_span!("request", request_id = %request_id);
+    with_request_id(request_id, next.run(req))
+        .instrument(span)
+        .await
+}
+
+/// 请求日志开关
+pub fn logger_enabled() -> bool {
+    Middlewares::is_enabled(&APP_CONFIG.server.middlewares.logger)
+}
+
+fn cors_layer(cors: &CorsConfig) -> CorsLayer {
+    let wildcard = |values: &[String]| values.is_empty() || values.iter().any(|v| v == "*");
+    // 允许任意来源携带cookie等同于关闭跨站防护, 必须明确列出允许的来源
+    let credentials = cors.allow_credentials && !wildcard(&cors.allow_origins);
+    if cors.allow_credentials && !credentials {
+        web_warn!(
+            "{MIDDLEWARE_NAME} CORS允许携带cookie时必须明确配置allow_origins, 已关闭allow_credentials"
+        );
+    }
+    let mut layer = CorsLayer::new().allow_credentials(credentials);
+
+    layer = if wildcard(&cors.allow_origins) {
+        layer.allow_origin(tower_http::cors::Any)
+    } else {
+        layer.allow_origin(parse_cors_values::<HeaderValue>(&cors.allow_origins))
+    };
+    // 允许携带cookie时浏览器不接受通配符, 改为回显请求中的值
+    layer = match (wildcard(&cors.allow_methods), credentials) {
+        (true, false) => layer.allow_methods(tower_http::cors::Any),
+        (true, true) => layer.allow_methods(AllowMethods::mirror_request()),
+        (false, _) => {
+            let methods: Vec<String> = cors
+                .allow_methods
+                .iter()
+                .map(|m| m.to_ascii_uppercase())
+                .collect();
+            layer.allow_methods(parse_cors_values::<Method>(&methods))
+        }
+    };
+    layer = match (wildcard(&cors.allow_headers), credentials) {
+        (true, false) => layer.allow_headers(tower_http::cors::Any),
+        (true, true) => layer.allow_headers(AllowHeaders::mirror_request()),
+        (false, _) => layer.allow_headers(parse_cors_values::<HeaderName>(&cors.allow_headers)),
+    };
+    if !cors.expose_headers.is_empty() {
+        layer = match (wildcard(&cors.expose_headers), credentials) {
+            (true, false) => layer.expose_headers(tower_http::cors::Any),
+            // 携带cookie时不支持通配, 不暴露额外响应头
+            (true, true) => layer,
+            (false, _) => {
+                layer.expose_headers(parse_cors_values::<HeaderName>(&cors.expose_headers))
+            }
+        };
+    }
+    if let Some(max_age) = cors.max_age {
+        layer = layer.max_age(Duration::from_secs(max_age));
+    }
+    layer
+}
+
+/// 忽略无法解析的配置项
+fn parse_cors_values<T: FromStr>(values: &[String]) -> Vec<T> {
+    values
+        .iter()
+        .filter_map(|v| {
+            let parsed = v.parse::<T>().ok();
+            if parsed.is_none() {
+                web_warn!("{MIDDLEWARE_NAME} 忽略无效的CORS配置项:{}", v);
+            }
+            parsed
         })
-        .unwrap_or_else(|| "unknown IP".to_string())
+        .collect()
+}
+
+/// 解析客户端IP, 请求日志、操作日志及限流统一使用
+pub fn parse_ip(req: &Request) -> String {
+    client_ip::client_ip(req.extensions(), req.headers())
+        .map_or_else(|| "unknown IP".to_string(), |ip| ip.to_string())
 }
 
 fn handle_panic(err: Box<dyn Any + Send + 'static>) -> Response {
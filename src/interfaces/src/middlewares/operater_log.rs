@@ -2,14 +2,23 @@ use std::time::Instant;
 
 use axum::{extract::Request, middleware::Next, response::IntoResponse};
 use chrono::Local;
+use commonx::{
+    redact::{redactor, truncate_chars},
+    web_error,
+};
 use hyper::StatusCode;
-use operaterLogDomain::{api::traits::OperaterLogDomainTrait, entity::OperaterLog};
+use operaterLogDomain::{
+    api::traits::OperaterLogDomainTrait,
+    entity::{OPER_STATUS_FAIL, OPER_STATUS_SUCCESS, OperaterLog},
+};
 
 use crate::{
-    common::OPERATOR_LOG_DOMAIN, middlewares::ReqCtx, resp::RespDataString,
+    common::OPERATOR_LOG_DOMAIN, middlewares::ReqCtx, resp::RespDataString, routes::OperLogOption,
     types::user_info::CtxUserInfo,
 };
 
+/// 只记录路由声明了操作日志选项的请求, 选项由路由写入响应扩展;
+/// 登录等白名单接口没有请求中的用户信息, 由处理函数将登录用户写入响应扩展
 pub async fn operate_log_fn_mid(
     req: Request,
     next: Next,
@@ -18,46 +27,111 @@ pub async fn operate_log_fn_mid(
         Some(ctx) => ctx.clone(),
         None => return Ok(next.run(req).await),
     };
-
-    let user_ctx = match req.extensions().get::<CtxUserInfo>() {
-        Some(ctx) => ctx.clone(),
-        None => return Ok(next.run(req).await),
-    };
+    let req_user = req.extensions().get::<CtxUserInfo>().cloned();
 
     let now = Instant::now();
     let res_end = next.run(req).await;
     let duration = now.elapsed();
+
+    let Some(option) = res_end.extensions().get::<OperLogOption>() else {
+        return Ok(res_end);
+    };
+    let Some(user_ctx) = req_user.or_else(|| res_end.extensions().get::<CtxUserInfo>().cloned())
+    else {
+        return Ok(res_end);
+    };
     let respdata = match res_end.extensions().get::<RespDataString>() {
         Some(x) => &x.0,
         None => &"".to_string(),
     };
-    oper_log_add(&req_ctx, &user_ctx, respdata, duration).await;
+    let outcome = RespOutcome::parse(res_end.status(), respdata);
+    oper_log_add(&req_ctx, &user_ctx, option, respdata, outcome, duration).await;
     Ok(res_end)
 }
+
+/// 响应结果, HTTP状态码或响应体中的业务码非2xx时视为失败
+pub struct RespOutcome {
+    pub status: i16,
+    pub http_status: i32,
+    pub biz_code: Option<i32>,
+    pub error_msg: Option<String>,
+}
+
+impl RespOutcome {
+    pub fn parse(http_status: StatusCode, respdata: &str) -> Self {
+        let body: Option<serde_json::Value> = serde_json::from_str(respdata).ok();
+        let biz_code = body
+            .as_ref()
+            .and_then(|b| b.get("code"))
+            .and_then(|c| c.as_i64())
+            .map(|c| c as i32);
+        let success = http_status.is_success() && biz_code.is_none_or(|c| (200..300).contains(&c));
+        let error_msg = (!success).then(|| {
+            body.as_ref()
+                .and_then(|b| b.get("message"))
+                .and_then(|m| m.as_str())
+                .map(|m| m.to_string())
+                .unwrap_or_else(|| http_status.to_string())
+        });
+        Self {
+            status: if success {
+                OPER_STATUS_SUCCESS
+            } else {
+                OPER_STATUS_FAIL
+            },
+            http_status: http_status.as_u16() as i32,
+            biz_code,
+            error_msg,
+        }
+    }
+}
 pub async fn oper_log_add(
     req_ctx: &ReqCtx,
     user_ctx: &CtxUserInfo,
+    option: &OperLogOption,
     respdata: &String,
+    outcome: RespOutcome,
     duration: std::time::Duration,
 ) {
-    let _ = OPERATOR_LOG_DOMAIN
+    let (oper_param, json_result) = if option.record_body {
+        // 登录等接口的响应中含令牌, 脱敏后再截断
+        (
+            req_ctx.body.clone(),
+            truncate_chars(&redactor().mask_body(respdata), 1024).to_string(),
+        )
+    } else {
+        (String::new(), String::new())
+    };
+    // 异步写入时只是入队, 写库失败由写入器重试并计数; 同步写入失败时记录错误, 不影响响应
+    if let Err(e) = OPERATOR_LOG_DOMAIN
         .create(OperaterLog {
             api_name: req_ctx.path.clone(),
+            title: option.title.clone(),
+            business_type: option.business_type,
             oper_ip: req_ctx.ip.clone(),
             oper_id: user_ctx.id,
             oper_name: user_ctx.username.clone(),
+            dept_id: user_ctx.dept_id,
+            // 登录接口没有租户上下文, 日志归属登录用户的租户
+            tenant_id: user_ctx.tenant_id,
             oper_url: req_ctx.ori_uri.clone(),
             oper_location: req_ctx.ori_uri.clone(),
             request_method: req_ctx.method.clone(),
-            oper_param: req_ctx.path_params.clone(),
-            json_result: if respdata.len() > 1024 {
-                respdata.chars().take(1024).collect::<String>()
-            } else {
-                respdata.clone()
-            },
+            oper_param,
+            oper_query: req_ctx.query.clone(),
+            json_result,
+            status: outcome.status,
+            http_status: outcome.http_status,
+            biz_code: outcome.biz_code,
+            error_msg: outcome.error_msg,
+            user_agent: req_ctx.user_agent.clone(),
+            request_id: req_ctx.request_id.clone(),
             cost_time: duration.as_millis() as i64,
             oper_time: Local::now(),
             ..Default::default()
         })
-        .await;
+        .await
+    {
+        web_error!(" -- 操作日志写入失败: {:?}", e);
+    }
 }
@@ -0,0 +1,198 @@
+//! 限流中间件
+//!
+//! 采用滑动窗口计数: 按窗口序号分别计数, 上一窗口的计数按未流逝的比例折算后与当前窗口计数相加作为估算值。
+//! 计数通过CacheManager存取, 使用redis时多实例共享限额, 使用内存缓存时单实例内有效。
+//! 按IP计数的规则在鉴权及租户上下文之前执行, 凭证无效的请求同样计入限额, 且计数不按租户区分;
+//! 按用户、路由计数的规则在鉴权之后执行。
+
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use axum::{
+    extract::{MatchedPath, Request, State},
+    http::{HeaderMap, HeaderValue, header::RETRY_AFTER},
+    middleware::Next,
+    response::Response,
+};
+use commonx::{
+    config::config::{RateLimitKey, RateLimitRule},
+    web_error,
+};
+use hyper::StatusCode;
+use infrastructurex::cache::CacheManager;
+
+use crate::{middlewares::parse_ip, resp::ApiResponse, types::user_info::CtxUserInfo};
+
+const RATE_LIMIT_KEY: &str = "rate_limit";
+
+/// 路由使用的限流规则
+#[derive(Debug, Clone)]
+pub struct RouteRateLimit {
+    pub rule: RateLimitRule,
+    /// 路由完整路径
+    pub route: String,
+    /// 是否为路由单独配置的规则, 单独配置的规则按路由分别计数
+    pub route_rule: bool,
+}
+
+impl RouteRateLimit {
+    fn cache_key(&self, req: &Request) -> String {
+        let subject = match self.rule.key_by {
+            RateLimitKey::Route => return format!("{RATE_LIMIT_KEY}:route:{}", self.route),
+            RateLimitKey::User => match req.extensions().get::<CtxUserInfo>() {
+                Some(user) => format!("user:{}", user.id),
+                None => format!("ip:{}", parse_ip(req)),
+            },
+            RateLimitKey::Ip => format!("ip:{}", parse_ip(req)),
+        };
+        if self.route_rule {
+            format!("{RATE_LIMIT_KEY}:{}:{}", self.route, subject)
+        } else {
+            format!("{RATE_LIMIT_KEY}:{}", subject)
+        }
+    }
+}
+
+/// 按IP计数的限流规则, 键为路由的完整匹配路径
+#[derive(Debug, Clone, Default)]
+pub struct IpRateLimits {
+    pub routes: Arc<HashMap<String, RouteRateLimit>>,
+}
+
+/// 限流判定结果
+#[derive(Debug, Clone, PartialEq)]
+struct RateLimitDecision {
+    allowed: bool,
+    limit: u64,
+    remaining: u64,
+    /// 距当前窗口结束的秒数
+    reset_after: u64,
+}
+
+pub async fn rate_limit_mid(
+    State(limiter): State<RouteRateLimit>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let key = limiter.cache_key(&req);
+    let decision = match check(&key, &limiter.rule).await {
+        Ok(decision) => decision,
+        Err(e) => {
+            // 缓存不可用时放行, 不影响正常请求
+            web_error!(" -- 限流计数失败: {:?}", e);
+            return next.run(req).await;
+        }
+    };
+    if !decision.allowed {
+        let mut res = ApiResponse::error_response(
+            StatusCode::TOO_MANY_REQUESTS,
+            "请求过于频繁, 请稍后再试".to_string(),
+        );
+        set_rate_limit_headers(res.headers_mut(), &limiter.rule, &decision);
+        res.headers_mut()
+            .insert(RETRY_AFTER, HeaderValue::from(decision.reset_after));
+        return res;
+    }
+    let mut res = next.run(req).await;
+    set_rate_limit_headers(res.headers_mut(), &limiter.rule, &decision);
+    res
+}
+
+/// 鉴权之前按匹配路径查找IP限流规则
+pub async fn ip_rate_limit_mid(
+    State(limits): State<IpRateLimits>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let limiter = req
+        .extensions()
+        .get::<MatchedPath>()
+        .and_then(|path| limits.routes.get(path.as_str()))
+        .cloned();
+    match limiter {
+        Some(limiter) => rate_limit_mid(State(limiter), req, next).await,
+        None => next.run(req).await,
+    }
+}
+
+async fn check(
+    key: &str,
+    rule: &RateLimitRule,
+) -> Result<RateLimitDecision, commonx::error::AppError> {
+    let window_ms = rule.window_seconds.max(1) * 1000;
+    let now_ms = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64;
+    let index = now_ms / window_ms;
+    let cache = CacheManager::instance();
+    // 当前窗口的计数在下一个窗口中仍需参与估算, 保留两个窗口
+    let ttl = (rule.window_seconds.max(1) * 2) as usize;
+    let current = cache.incr_ex(&format!("{key}:{index}"), ttl).await?.max(0) as u64;
+    let previous = cache
+        .get_string(&format!("{key}:{}", index.saturating_sub(1)))
+        .await
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(0);
+    Ok(sliding_window(
+        rule.limit,
+        window_ms,
+        now_ms % window_ms,
+        previous,
+        current,
+    ))
+}
+
+/// 按上一窗口计数的剩余权重与当前窗口计数估算窗口内的请求数, current已包含本次请求
+fn sliding_window(
+    limit: u64,
+    window_ms: u64,
+    elapsed_ms: u64,
+    previous: u64,
+    current: u64,
+) -> RateLimitDecision {
+    let left_ms = window_ms - elapsed_ms;
+    let estimated = (previous * left_ms).div_ceil(window_ms) + current;
+    RateLimitDecision {
+        allowed: estimated <= limit,
+        limit,
+        remaining: limit.saturating_sub(estimated),
+        reset_after: left_ms.div_ceil(1000).max(1),
+    }
+}
+
+fn set_rate_limit_headers(headers: &mut HeaderMap, rule: &RateLimitRule, d: &RateLimitDecision) {
+    headers.insert("ratelimit-limit", HeaderValue::from(d.limit));
+    headers.insert("ratelimit-remaining", HeaderValue::from(d.remaining));
+    headers.insert("ratelimit-reset", HeaderValue::from(d.reset_after));
+    if let Ok(policy) = HeaderValue::from_str(&format!("{};w={}", rule.limit, rule.window_seconds))
+    {
+        headers.insert("ratelimit-policy", policy);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sliding_window_weights_previous_window() {
+        // 窗口过去一半, 上一窗口10次折算为5次
+        let d = sliding_window(10, 60_000, 30_000, 10, 5);
+        assert!(d.allowed);
+        assert_eq!(d.remaining, 0);
+        assert_eq!(d.reset_after, 30);
+
+        let d = sliding_window(10, 60_000, 30_000, 10, 6);
+        assert!(!d.allowed);
+        assert_eq!(d.remaining, 0);
+
+        // 窗口刚开始时上一窗口几乎全部计入
+        assert!(!sliding_window(10, 60_000, 0, 10, 1).allowed);
+        assert_eq!(sliding_window(10, 60_000, 59_999, 10, 1).remaining, 8);
+    }
+}
@@ -1,8 +1,20 @@
-use axum::{body::Body, extract::Request, middleware::Next, response::IntoResponse};
-use commonx::web_info;
+use axum::{
+    body::Body, extract::Request, http::header::CONTENT_TYPE, middleware::Next,
+    response::IntoResponse,
+};
+use commonx::{
+    redact::{redactor, truncate_chars},
+    request_id::REQUEST_ID_HEADER,
+    web_debug, web_info,
+};
 use hyper::StatusCode;
 
-use crate::middlewares::{ReqCtx, parse_ip};
+use crate::middlewares::{ReqCtx, logger_enabled, parse_ip};
+
+/// 请求上下文中保留的请求体最大字符数
+const MAX_BODY_CHARS: usize = 2000;
+/// 请求日志中输出的请求体最大字符数
+const LOG_BODY_CHARS: usize = 500;
 
 pub async fn request_log_fn_mid(
     req: Request,
@@ -17,15 +29,22 @@ pub async fn request_log_fn_mid(
     // let path = uri.path();
     let query = uri.query().unwrap_or("");
 
-    // let user_agent = parts
-    //     .headers
-    //     .get("user-agent")
-    //     .map_or("", |h| h.to_str().unwrap_or(""));
+    let user_agent = parts
+        .headers
+        .get("user-agent")
+        .map_or("", |h| h.to_str().unwrap_or(""))
+        .to_string();
+
+    let content_type = parts
+        .headers
+        .get(CONTENT_TYPE)
+        .map_or("", |h| h.to_str().unwrap_or(""));
 
-    // let content_type = parts
-    //     .headers
-    //     .get(CONTENT_TYPE)
-    //     .map_or("", |h| h.to_str().unwrap_or(""));
+    let request_id = parts
+        .headers
+        .get(REQUEST_ID_HEADER)
+        .map_or("", |h| h.to_str().unwrap_or(""))
+        .to_string();
 
     // 读取请求体
     let body_bytes = axum::body::to_bytes(body, usize::MAX)
@@ -33,27 +52,45 @@ pub async fn request_log_fn_mid(
         .map_err(|e| (StatusCode::BAD_REQUEST, format!("读取请求体失败: {}", e)))?;
 
     let body_content = String::from_utf8_lossy(&body_bytes);
+    // 先脱敏再截断, 截断后的JSON无法按字段脱敏
+    let masked_body = redactor().mask_body(&body_content);
+    let masked_query = redactor().mask_text(query);
 
     // 记录日志
-    web_info!(
-        "ip:{} method:{} url:{} query:{} body:{}",
-        ip,
-        method,
-        uri,
-        query,
-        if body_content.len() > 500 {
-            format!("{}...(truncated)", &body_content[..500])
-        } else {
-            body_content.to_string()
-        }
-    );
+    if logger_enabled() {
+        let log_body = truncate_chars(&masked_body, LOG_BODY_CHARS);
+        web_info!(
+            "ip:{} method:{} url:{} query:{} body:{}{}",
+            ip,
+            method,
+            uri.path(),
+            masked_query,
+            log_body,
+            if log_body.len() < masked_body.len() {
+                "...(truncated)"
+            } else {
+                ""
+            }
+        );
+        web_debug!("headers:{}", redactor().format_headers(&parts.headers));
+    }
+    // 上传文件等二进制请求体不记录
+    let body = if content_type.starts_with("application/json")
+        || content_type.starts_with("application/x-www-form-urlencoded")
+    {
+        truncate_chars(&masked_body, MAX_BODY_CHARS).to_string()
+    } else {
+        String::new()
+    };
     let req_ctx = ReqCtx {
         ip: ip,
-        ori_uri: uri.to_string(),
+        ori_uri: redactor().mask_text(&uri.to_string()).to_string(),
         path: uri.path().to_string(),
-        path_params: uri.path().to_string(),
         method: method,
-        // user_agent: user_agent.to_string(),
+        user_agent,
+        query: masked_query.to_string(),
+        body,
+        request_id,
     };
 
     // 重新构建请求
@@ -0,0 +1,12 @@
+use axum::{extract::Request, middleware::Next, response::Response};
+use commonx::tenant::with_tenant;
+
+use crate::types::user_info::CtxUserInfo;
+
+/// 在当前用户所属租户的上下文中处理请求, 持久化层及缓存据此隔离数据
+pub async fn tenant_scope_mid(req: Request, next: Next) -> Response {
+    match req.extensions().get::<CtxUserInfo>() {
+        Some(user) => with_tenant(user.tenant_id, next.run(req)).await,
+        None => next.run(req).await,
+    }
+}
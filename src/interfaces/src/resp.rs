@@ -1,5 +1,5 @@
 use axum::response::{IntoResponse, Response};
-use commonx::{error::AppError, traits::IntoStatusTuple};
+use commonx::{error::AppError, request_id::current_request_id, traits::IntoStatusTuple};
 use hyper::StatusCode;
 use serde::{Deserialize, Serialize};
 
@@ -25,7 +25,7 @@ where
             code: code.as_u16(),
             data,
             message,
-            request_id: None,
+            request_id: current_request_id(),
         }
     }
     pub fn ok_with_data(data: T) -> Response {
@@ -94,15 +94,20 @@ where
 {
     fn into_response(self) -> Response {
         match serde_json::to_string(&self) {
-            Ok(json) => (
-                [
-                    ("Content-Type", "application/json;charset=UTF-8"),
-                    ("Access-Control-Allow-Origin", "*"),
-                    ("Cache-Control", "no-cache"),
-                ],
-                json,
-            )
-                .into_response(),
+            Ok(json) => {
+                // 响应体同时放入扩展, 供操作日志记录
+                let resp_data = RespDataString(json.clone());
+                let mut response = (
+                    [
+                        ("Content-Type", "application/json;charset=UTF-8"),
+                        ("Cache-Control", "no-cache"),
+                    ],
+                    json,
+                )
+                    .into_response();
+                response.extensions_mut().insert(resp_data);
+                response
+            }
             Err(_) => (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 [("Content-Type", "application/json;charset=UTF-8")],
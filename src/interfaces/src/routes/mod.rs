@@ -1,6 +1,9 @@
 mod router_group;
 mod sys;
-use axum::{Router, middleware::from_fn, response::IntoResponse};
+mod user;
+
+pub use router_group::OperLogOption;
+use axum::{Router, middleware::from_fn, response::IntoResponse, routing::get};
 use commonx::config::APP_CONFIG;
 use tower_http::{
     services::ServeDir,
@@ -8,44 +11,57 @@ use tower_http::{
 };
 
 use crate::{
-    API_PATH_PRE,
+    API_PATH_PRE, controller,
     middlewares::{
-        request_log::request_log_fn_mid, set_auth_middleware, set_common_middleware,
+        logger_enabled, request_log::request_log_fn_mid, set_auth_middleware, set_common_middleware,
         set_no_auth_middleware,
     },
     resp::ApiResponse,
-    routes::sys::{router_sys, router_sys_white},
+    routes::{
+        sys::{router_sys, router_sys_white},
+        user::router_user,
+    },
 };
 
 // static MODULE_NAME: &str = "[routes]";
 
 fn routes() -> Router {
-    router_sys().into()
+    let router_group = router_sys().merge(router_user());
+    let ip_filters = router_group.ip_filter_groups(API_PATH_PRE);
+    let ip_limits = router_group.ip_rate_limits(API_PATH_PRE);
+    set_auth_middleware(router_group.into(), ip_filters, ip_limits)
 }
 
 // 白名单路由
 fn white_routers() -> Router {
-    router_sys_white().into()
+    let router_group = router_sys_white();
+    let ip_filters = router_group.ip_filter_groups(API_PATH_PRE);
+    let ip_limits = router_group.ip_rate_limits(API_PATH_PRE);
+    set_no_auth_middleware(router_group.into(), ip_filters, ip_limits)
 }
 
 fn set_routes() -> Router {
     let server_config = &APP_CONFIG.server;
     let static_dir = ServeDir::new(&server_config.static_dir);
     // let webdir = ServeDir::new(serverconfig.web_dir);
-    Router::new()
+    let mut router = Router::new()
         .nest_service("/static", static_dir)
+        .route("/.well-known/jwks.json", get(controller::sys::jwks))
         // .nest_service("/", webdir)
-        .nest(API_PATH_PRE, set_no_auth_middleware(white_routers()))
-        .nest(API_PATH_PRE, set_auth_middleware(routes()))
-        .layer(from_fn(request_log_fn_mid))
+        .nest(API_PATH_PRE, white_routers())
+        .nest(API_PATH_PRE, routes())
+        .layer(from_fn(request_log_fn_mid));
+    if logger_enabled() {
         // 3. 请求跟踪日志（记录请求详情，便于排查问题）
-        .layer(
+        router = router.layer(
             TraceLayer::new_for_http()
                 .make_span_with(DefaultMakeSpan::new().include_headers(true)) // 记录请求头
                 .on_request(DefaultOnRequest::new().level(tracing::Level::INFO)) // 请求开始日志
                 .on_response(DefaultOnResponse::new().level(tracing::Level::INFO)) // 响应日志（含耗时）
                 .on_failure(DefaultOnFailure::new().level(tracing::Level::ERROR)), // 失败日志
-        )
+        );
+    }
+    router
         .with_state(()) // Axum 0.8+ 必需：明确状态（空状态用 ()）
         .fallback(handle_404)
 }
@@ -1,11 +1,28 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::Arc};
 
-use axum::{Router, routing::MethodRouter};
-use commonx::web_info;
+use axum::{
+    Router,
+    middleware::{from_fn_with_state, map_response},
+    response::Response,
+    routing::MethodRouter,
+};
+use commonx::{
+    config::{
+        APP_CONFIG,
+        config::{RateLimitKey, RateLimitRule},
+    },
+    web_info,
+};
 use hyper::Method;
+use operaterLogDomain::entity::BusinessType;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Default, Serialize, Deserialize, PartialEq)]
+use crate::middlewares::{
+    ip_filter::IpFilterGroups,
+    rate_limit::{IpRateLimits, RouteRateLimit, rate_limit_mid},
+};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
 pub enum WebPathMethod {
     #[default]
     Get,
@@ -57,7 +74,69 @@ impl From<Method> for WebPathMethod {
     }
 }
 
-#[derive(Debug, Default, Serialize, Deserialize)]
+/// 路由的操作日志选项, 由路由写入响应扩展, 操作日志中间件据此决定是否记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OperLogOption {
+    /// 操作名称
+    pub title: String,
+    pub business_type: BusinessType,
+    /// 是否记录请求体及响应体
+    pub record_body: bool,
+}
+
+/// 路由选项
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RouteOption {
+    pub api_name: Option<String>,
+    /// 为None时不记录操作日志
+    pub oper_log: Option<OperLogOption>,
+    /// 为None时使用全局限流配置
+    pub rate_limit: Option<RateLimitRule>,
+}
+
+impl RouteOption {
+    pub fn new(api_name: &str) -> Self {
+        Self {
+            api_name: Some(api_name.to_string()),
+            ..Default::default()
+        }
+    }
+
+    /// 记录操作日志, 默认记录请求体及响应体
+    pub fn oper_log(mut self, business_type: BusinessType) -> Self {
+        self.oper_log = Some(OperLogOption {
+            title: self.api_name.clone().unwrap_or_default(),
+            business_type,
+            record_body: true,
+        });
+        self
+    }
+
+    /// 操作日志不记录请求体及响应体
+    pub fn without_body(mut self) -> Self {
+        if let Some(oper_log) = self.oper_log.as_mut() {
+            oper_log.record_body = false;
+        }
+        self
+    }
+
+    /// 单独指定限流规则, 按路由分别计数
+    pub fn rate_limit(mut self, rule: RateLimitRule) -> Self {
+        self.rate_limit = Some(rule);
+        self
+    }
+}
+
+impl From<Option<&str>> for RouteOption {
+    fn from(api_name: Option<&str>) -> Self {
+        Self {
+            api_name: api_name.map(|s| s.to_string()),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct RouterGroup {
     pub final_path: String,
     pub method: WebPathMethod,
@@ -65,6 +144,10 @@ pub struct RouterGroup {
     pub method_router: Option<MethodRouter>,
     sub_paths: HashMap<String, RouterGroup>,
     pub api_name: Option<String>,
+    pub oper_log: Option<OperLogOption>,
+    pub rate_limit: Option<RateLimitRule>,
+    /// IP访问控制分组, 包含上级分组
+    pub ip_filters: Vec<String>,
 }
 
 impl RouterGroup {
@@ -75,6 +158,11 @@ impl RouterGroup {
         self.sub_paths.insert(path.to_string(), web_path);
         self
     }
+    /// 该分组下的路由使用配置中指定名称的IP访问控制规则
+    pub fn ip_filter(mut self, group: &str) -> Self {
+        self.ip_filters.push(group.to_string());
+        self
+    }
     #[allow(dead_code)]
     pub fn merge(mut self, web_path: RouterGroup) -> Self {
         for (sub_key, sub_path) in web_path.sub_paths {
@@ -87,14 +175,17 @@ impl RouterGroup {
         mut self,
         path: &str,
         method: WebPathMethod,
-        api_name: Option<&str>,
+        option: impl Into<RouteOption>,
         method_router: MethodRouter,
     ) -> Self {
+        let option = option.into();
         self.sub_paths.insert(
             String::from(path),
             RouterGroup {
                 method: method,
-                api_name: api_name.map(|s| s.to_string()),
+                api_name: option.api_name,
+                oper_log: option.oper_log,
+                rate_limit: option.rate_limit,
                 method_router: Some(method_router),
                 sub_paths: HashMap::new(),
                 ..Default::default()
@@ -105,6 +196,11 @@ impl RouterGroup {
 
     fn concat_sub_paths_final_paths(&mut self, parent_path: &str) {
         for (sub_key, sub_path) in self.sub_paths.iter_mut() {
+            for group in self.ip_filters.iter().rev() {
+                if !sub_path.ip_filters.contains(group) {
+                    sub_path.ip_filters.insert(0, group.clone());
+                }
+            }
             let f_path = format!("{}{}", parent_path, sub_key);
             sub_path.concat_sub_paths_final_paths(&f_path);
             sub_path.final_path = f_path;
@@ -131,6 +227,53 @@ impl RouterGroup {
         last_level_paths
     }
 
+    /// 路由单独配置的限流规则优先, 否则使用启用的全局规则
+    fn rate_limiter(&self) -> Option<RouteRateLimit> {
+        let global = &APP_CONFIG.server.middlewares.rate_limit;
+        let (rule, route_rule) = match self.rate_limit.clone() {
+            Some(rule) => (rule, true),
+            None if global.enable => (global.rule.clone(), false),
+            None => return None,
+        };
+        Some(RouteRateLimit {
+            rule,
+            route: self.final_path.clone(),
+            route_rule,
+        })
+    }
+
+    /// 按IP计数的限流规则, 键为加上前缀后的完整匹配路径, 由调用方添加在鉴权中间件之外
+    pub fn ip_rate_limits(&self, prefix: &str) -> IpRateLimits {
+        let router_group = self.clone().final_to_path();
+        let routes = router_group
+            .get_last_level_paths()
+            .into_iter()
+            .filter(|p| p.method_router.is_some())
+            .filter_map(|p| {
+                p.rate_limiter()
+                    .filter(|limiter| limiter.rule.key_by == RateLimitKey::Ip)
+                    .map(|limiter| (format!("{}{}", prefix, p.final_path), limiter))
+            })
+            .collect();
+        IpRateLimits {
+            routes: Arc::new(routes),
+        }
+    }
+
+    /// 各路由的IP访问控制分组, 键为加上前缀后的完整匹配路径, 由调用方添加在鉴权中间件之外
+    pub fn ip_filter_groups(&self, prefix: &str) -> IpFilterGroups {
+        let router_group = self.clone().final_to_path();
+        let routes = router_group
+            .get_last_level_paths()
+            .into_iter()
+            .filter(|p| p.method_router.is_some() && !p.ip_filters.is_empty())
+            .map(|p| (format!("{}{}", prefix, p.final_path), p.ip_filters.clone()))
+            .collect();
+        IpFilterGroups {
+            routes: Arc::new(routes),
+        }
+    }
+
     // pub fn print_all_paths(&self) {
     //     for sub_path_data in self.sub_paths.values() {
     //         if sub_path_data.is_last_level() {
@@ -148,7 +291,24 @@ impl Into<Router> for RouterGroup {
 
         let mut router = Router::new();
         for p in expand_path {
-            if let Some(method_router) = p.method_router.clone() {
+            if let Some(mut method_router) = p.method_router.clone() {
+                if let Some(oper_log) = p.oper_log.clone() {
+                    method_router = method_router.layer(map_response(move |mut res: Response| {
+                        let oper_log = oper_log.clone();
+                        async move {
+                            res.extensions_mut().insert(oper_log);
+                            res
+                        }
+                    }));
+                }
+                // IP访问控制分组及按IP计数的限流规则分别由ip_filter_groups、ip_rate_limits在鉴权之前执行
+                if let Some(limiter) = p
+                    .rate_limiter()
+                    .filter(|limiter| limiter.rule.key_by != RateLimitKey::Ip)
+                {
+                    method_router =
+                        method_router.layer(from_fn_with_state(limiter, rate_limit_mid));
+                }
                 router = router.route(&p.final_path, method_router);
                 web_info!("[路由]:[{}]{}", p.method, p.final_path);
             }
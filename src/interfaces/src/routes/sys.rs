@@ -2,25 +2,151 @@ use axum::{
     response::IntoResponse,
     routing::{get, post},
 };
-use commonx::web_info;
+use commonx::{
+    config::config::{RateLimitKey, RateLimitRule},
+    web_info,
+};
+use operaterLogDomain::entity::BusinessType;
 
 use crate::{
     controller,
     resp::ApiResponse,
-    routes::router_group::{RouterGroup, WebPathMethod},
+    routes::router_group::{RouteOption, RouterGroup, WebPathMethod},
 };
 
+// 登录接口按IP限流, 防止暴力破解
+fn login_rate_limit() -> RateLimitRule {
+    RateLimitRule::new(RateLimitKey::Ip, 10, 60)
+}
+
 // 系统路由
 pub fn router_sys() -> RouterGroup {
     RouterGroup::new()
         .nest(
             "/sys",
-            RouterGroup::new().nest("/cache", sys_cache()).route(
-                "/init_all",
-                WebPathMethod::Post,
-                Some("初始化数据库"),
-                post(controller::sys::init_all),
-            ),
+            RouterGroup::new()
+                .ip_filter("admin")
+                .nest("/cache", sys_cache())
+                .nest(
+                    "/user",
+                    RouterGroup::new()
+                        .route(
+                            "/change_password",
+                            WebPathMethod::Post,
+                            RouteOption::new("修改密码")
+                                .oper_log(BusinessType::Update)
+                                .without_body(),
+                            post(controller::user::change_password),
+                        )
+                        .route(
+                            "/api_keys/list",
+                            WebPathMethod::Get,
+                            Some("获取API Key列表"),
+                            get(controller::user::list_api_keys),
+                        )
+                        .route(
+                            "/api_keys/create",
+                            WebPathMethod::Post,
+                            RouteOption::new("创建API Key")
+                                .oper_log(BusinessType::Create)
+                                .without_body(),
+                            post(controller::user::create_api_key),
+                        )
+                        .route(
+                            "/api_keys/revoke",
+                            WebPathMethod::Post,
+                            RouteOption::new("吊销API Key").oper_log(BusinessType::Delete),
+                            post(controller::user::revoke_api_key),
+                        )
+                        .route(
+                            "/sessions/list",
+                            WebPathMethod::Get,
+                            Some("获取登录会话列表"),
+                            get(controller::user::list_sessions),
+                        )
+                        .route(
+                            "/sessions/revoke",
+                            WebPathMethod::Post,
+                            RouteOption::new("吊销登录会话").oper_log(BusinessType::Delete),
+                            post(controller::user::revoke_session),
+                        ),
+                )
+                .nest(
+                    "/session",
+                    RouterGroup::new()
+                        .route(
+                            "/list",
+                            WebPathMethod::Get,
+                            Some("获取所有用户的登录会话"),
+                            get(controller::user::list_all_sessions),
+                        )
+                        .route(
+                            "/revoke",
+                            WebPathMethod::Post,
+                            RouteOption::new("吊销用户登录会话").oper_log(BusinessType::Delete),
+                            post(controller::user::revoke_any_session),
+                        ),
+                )
+                .nest(
+                    "/dept",
+                    RouterGroup::new().route(
+                        "/tree",
+                        WebPathMethod::Get,
+                        Some("获取部门树"),
+                        get(controller::dept::tree),
+                    ),
+                )
+                .nest(
+                    "/loginlog",
+                    RouterGroup::new().route(
+                        "/list",
+                        WebPathMethod::Get,
+                        Some("获取登录日志列表"),
+                        get(controller::login_log::list),
+                    ),
+                )
+                .nest(
+                    "/operlog",
+                    RouterGroup::new()
+                        .route(
+                            "/list",
+                            WebPathMethod::Get,
+                            Some("获取操作日志列表"),
+                            get(controller::oper_log::list),
+                        )
+                        .route(
+                            "/detail",
+                            WebPathMethod::Get,
+                            Some("获取操作日志详情"),
+                            get(controller::oper_log::detail),
+                        )
+                        .route(
+                            "/export",
+                            WebPathMethod::Get,
+                            RouteOption::new("导出操作日志")
+                                .oper_log(BusinessType::Export)
+                                .without_body(),
+                            get(controller::oper_log::export),
+                        )
+                        .route(
+                            "/writer_stats",
+                            WebPathMethod::Get,
+                            Some("获取操作日志写入队列状态"),
+                            get(controller::oper_log::writer_stats),
+                        ),
+                )
+                .route(
+                    "/init_all",
+                    WebPathMethod::Post,
+                    RouteOption::new("初始化数据库").oper_log(BusinessType::Other),
+                    post(controller::sys::init_all),
+                )
+                .route(
+                    "/ip_filter/reload",
+                    WebPathMethod::Post,
+                    RouteOption::new("重新加载IP访问控制规则").oper_log(BusinessType::Update),
+                    post(controller::sys::reload_ip_filter),
+                ),
         )
         .nest(
             "/cornJob",
@@ -34,19 +160,19 @@ pub fn router_sys() -> RouterGroup {
                 .route(
                     "/create",
                     WebPathMethod::Post,
-                    Some("创建CornJob"),
+                    RouteOption::new("创建CornJob").oper_log(BusinessType::Create),
                     post(controller::corn_job::create),
                 )
                 .route(
                     "/delete",
                     WebPathMethod::Post,
-                    Some("删除CornJob"),
+                    RouteOption::new("删除CornJob").oper_log(BusinessType::Delete),
                     post(controller::corn_job::delete_by_id),
                 )
                 .route(
                     "/update",
                     WebPathMethod::Post,
-                    Some("更新CornJob"),
+                    RouteOption::new("更新CornJob").oper_log(BusinessType::Update),
                     post(controller::corn_job::update_by_id),
                 )
                 .route(
@@ -70,7 +196,7 @@ fn sys_cache() -> RouterGroup {
         .route(
             "/clear",
             WebPathMethod::Post,
-            Some("清空缓存"),
+            RouteOption::new("清空缓存").oper_log(BusinessType::Delete),
             post(|| async { "sys cache clear" }),
         )
 }
@@ -93,15 +219,74 @@ pub fn router_sys_white() -> RouterGroup {
                 .route(
                     "/login",
                     WebPathMethod::Post,
-                    Some("用户登录"),
+                    RouteOption::new("用户登录")
+                        .oper_log(BusinessType::Login)
+                        .without_body()
+                        .rate_limit(login_rate_limit()),
                     post(controller::user::login),
                 )
                 .route(
                     "/login_with_captcha",
                     WebPathMethod::Post,
-                    Some("用户登录（验证码）"),
+                    RouteOption::new("用户登录（验证码）")
+                        .oper_log(BusinessType::Login)
+                        .without_body()
+                        .rate_limit(login_rate_limit()),
                     post(controller::user::login_with_captcha),
                 )
+                .route(
+                    "/login_2fa",
+                    WebPathMethod::Post,
+                    RouteOption::new("用户登录（两步验证）")
+                        .oper_log(BusinessType::Login)
+                        .without_body()
+                        .rate_limit(login_rate_limit()),
+                    post(controller::user::login_2fa),
+                )
+                .route(
+                    "/oidc/authorize",
+                    WebPathMethod::Get,
+                    Some("单点登录授权地址"),
+                    get(controller::user::oidc_authorize),
+                )
+                .route(
+                    "/oidc/callback",
+                    WebPathMethod::Post,
+                    RouteOption::new("单点登录回调")
+                        .oper_log(BusinessType::Login)
+                        .without_body(),
+                    post(controller::user::oidc_callback),
+                )
+                .route(
+                    "/register",
+                    WebPathMethod::Post,
+                    Some("用户注册"),
+                    post(controller::user::register),
+                )
+                .route(
+                    "/verify_email",
+                    WebPathMethod::Get,
+                    Some("注册邮箱验证"),
+                    get(controller::user::verify_email),
+                )
+                .route(
+                    "/resend_verify_email",
+                    WebPathMethod::Post,
+                    RouteOption::new("重发注册验证邮件").rate_limit(login_rate_limit()),
+                    post(controller::user::resend_email_verify),
+                )
+                .route(
+                    "/forgot_password",
+                    WebPathMethod::Post,
+                    Some("找回密码"),
+                    post(controller::user::forgot_password),
+                )
+                .route(
+                    "/reset_password",
+                    WebPathMethod::Post,
+                    Some("重置密码"),
+                    post(controller::user::reset_password),
+                )
                 .route(
                     "/get_captcha",
                     WebPathMethod::Get,
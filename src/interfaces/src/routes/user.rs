@@ -0,0 +1,48 @@
+use axum::routing::post;
+use operaterLogDomain::entity::BusinessType;
+
+use crate::{
+    controller,
+    routes::router_group::{RouteOption, RouterGroup, WebPathMethod},
+};
+
+// 用户路由
+pub fn router_user() -> RouterGroup {
+    RouterGroup::new().nest(
+        "/user",
+        RouterGroup::new().nest(
+            "/totp",
+            RouterGroup::new()
+                .route(
+                    "/enroll",
+                    WebPathMethod::Post,
+                    RouteOption::new("绑定两步验证")
+                        .oper_log(BusinessType::Update)
+                        .without_body(),
+                    post(controller::user::totp_enroll),
+                )
+                .route(
+                    "/activate",
+                    WebPathMethod::Post,
+                    RouteOption::new("启用两步验证")
+                        .oper_log(BusinessType::Update)
+                        .without_body(),
+                    post(controller::user::totp_activate),
+                )
+                .route(
+                    "/disable",
+                    WebPathMethod::Post,
+                    RouteOption::new("关闭两步验证").oper_log(BusinessType::Update),
+                    post(controller::user::totp_disable),
+                )
+                .route(
+                    "/recovery_codes",
+                    WebPathMethod::Post,
+                    RouteOption::new("重新生成恢复码")
+                        .oper_log(BusinessType::Update)
+                        .without_body(),
+                    post(controller::user::totp_recovery_codes),
+                ),
+        ),
+    )
+}
@@ -7,4 +7,13 @@ pub struct Claims {
     pub role: i64,
     pub exp: i64,
     pub token_id: i64,
+    /// 所属租户, 旧token没有该字段时为默认租户
+    #[serde(default)]
+    pub tenant_id: i64,
+    /// 所属部门, 用于数据权限
+    #[serde(default)]
+    pub dept_id: Option<i64>,
+    /// 密码已过期, 只允许调用修改密码接口
+    #[serde(default)]
+    pub password_expired: bool,
 }
@@ -64,6 +64,7 @@ impl Into<ListJobQo> for ListReq {
         ListJobQo {
             page_req: self.page_req,
             class: self.class,
+            ..Default::default()
         }
     }
 }
@@ -0,0 +1,25 @@
+use queryx::dept::entity::DeptTreeVo;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct DeptTreeRes {
+    pub id: i64,
+    pub parent_id: i64,
+    pub name: String,
+    pub order_num: i32,
+    pub status: Option<String>,
+    pub children: Vec<DeptTreeRes>,
+}
+
+impl From<DeptTreeVo> for DeptTreeRes {
+    fn from(value: DeptTreeVo) -> Self {
+        Self {
+            id: value.dept.id,
+            parent_id: value.dept.parent_id,
+            name: value.dept.name,
+            order_num: value.dept.order_num,
+            status: value.dept.status,
+            children: value.children.into_iter().map(DeptTreeRes::from).collect(),
+        }
+    }
+}
@@ -0,0 +1,99 @@
+use chrono::{DateTime, Local};
+use queryx::{
+    entity::PageReq,
+    login_log::entity::{ListLoginLogQo, LoginLogPageVo, LoginLogVo},
+};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+/// 登录日志查询条件
+#[derive(Debug, Serialize, Deserialize, Clone, Validate, Default)]
+pub struct LoginLogListReq {
+    #[validate(range(min = 1, message = "页码必须大于0"))]
+    pub page: Option<u64>,
+    #[serde(rename = "pageSize")]
+    #[validate(range(min = 1, max = 100, message = "每页数量必须在1-100之间"))]
+    pub page_size: Option<u64>,
+    pub username: Option<String>,
+    #[serde(rename = "userId")]
+    pub user_id: Option<i64>,
+    pub ip: Option<String>,
+    /// 0成功 1失败
+    #[validate(range(min = 0, max = 1, message = "状态只能为0或1"))]
+    pub status: Option<i16>,
+    #[serde(rename = "beginTime")]
+    pub begin_time: Option<DateTime<Local>>,
+    #[serde(rename = "endTime")]
+    pub end_time: Option<DateTime<Local>>,
+}
+
+impl From<LoginLogListReq> for ListLoginLogQo {
+    fn from(value: LoginLogListReq) -> Self {
+        let default_page = PageReq::default();
+        Self {
+            page_req: PageReq {
+                page: value.page.or(default_page.page),
+                page_size: value.page_size.or(default_page.page_size),
+            },
+            username: value.username,
+            user_id: value.user_id,
+            ip: value.ip,
+            status: value.status,
+            begin_time: value.begin_time,
+            end_time: value.end_time,
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct LoginLogRes {
+    pub id: i64,
+    pub user_id: Option<i64>,
+    pub username: String,
+    pub login_type: String,
+    pub ip: String,
+    pub user_agent: String,
+    pub status: i16,
+    pub fail_reason: Option<String>,
+    pub captcha_used: bool,
+    pub two_factor_used: bool,
+    pub request_id: String,
+    pub dept_id: Option<i64>,
+    pub login_time: DateTime<Local>,
+}
+
+impl From<LoginLogVo> for LoginLogRes {
+    fn from(value: LoginLogVo) -> Self {
+        Self {
+            id: value.id,
+            user_id: value.user_id,
+            username: value.username,
+            login_type: value.login_type,
+            ip: value.ip,
+            user_agent: value.user_agent,
+            status: value.status,
+            fail_reason: value.fail_reason,
+            captcha_used: value.captcha_used,
+            two_factor_used: value.two_factor_used,
+            request_id: value.request_id,
+            dept_id: value.dept_id,
+            login_time: value.login_time,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct LoginLogListRes {
+    pub list: Vec<LoginLogRes>,
+    pub total: u64,
+}
+
+impl From<LoginLogPageVo> for LoginLogListRes {
+    fn from(value: LoginLogPageVo) -> Self {
+        Self {
+            list: value.list.into_iter().map(LoginLogRes::from).collect(),
+            total: value.total,
+        }
+    }
+}
@@ -3,6 +3,9 @@ use validator::Validate;
 
 pub mod auth_jwt;
 pub mod corn_job;
+pub mod dept;
+pub mod login_log;
+pub mod oper_log;
 pub mod user_info;
 
 #[derive(Debug, Serialize, Deserialize, Clone, Validate, Default)]
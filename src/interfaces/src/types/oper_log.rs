@@ -0,0 +1,133 @@
+use chrono::{DateTime, Local};
+use queryx::{
+    entity::PageReq,
+    oper_log::entity::{ListOperLogQo, OperLogPageVo, OperLogVo},
+};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+/// 操作日志查询条件, 导出时忽略分页参数
+#[derive(Debug, Serialize, Deserialize, Clone, Validate, Default)]
+pub struct OperLogListReq {
+    #[validate(range(min = 1, message = "页码必须大于0"))]
+    pub page: Option<u64>,
+    #[serde(rename = "pageSize")]
+    #[validate(range(min = 1, max = 100, message = "每页数量必须在1-100之间"))]
+    pub page_size: Option<u64>,
+    #[serde(rename = "operId")]
+    pub oper_id: Option<i64>,
+    #[serde(rename = "apiName")]
+    pub api_name: Option<String>,
+    pub title: Option<String>,
+    /// 0其他 1新增 2修改 3删除 4导出 5登录
+    #[serde(rename = "businessType")]
+    #[validate(range(min = 0, max = 5, message = "业务类型只能为0-5"))]
+    pub business_type: Option<i16>,
+    #[serde(rename = "requestMethod")]
+    pub request_method: Option<String>,
+    #[serde(rename = "beginTime")]
+    pub begin_time: Option<DateTime<Local>>,
+    #[serde(rename = "endTime")]
+    pub end_time: Option<DateTime<Local>>,
+    /// 0成功 1失败
+    #[validate(range(min = 0, max = 1, message = "状态只能为0或1"))]
+    pub status: Option<i16>,
+    #[serde(rename = "minCostTime")]
+    #[validate(range(min = 0, message = "耗时不能小于0"))]
+    pub min_cost_time: Option<i64>,
+    #[serde(rename = "requestId")]
+    pub request_id: Option<String>,
+}
+
+impl From<OperLogListReq> for ListOperLogQo {
+    fn from(value: OperLogListReq) -> Self {
+        let default_page = PageReq::default();
+        Self {
+            page_req: PageReq {
+                page: value.page.or(default_page.page),
+                page_size: value.page_size.or(default_page.page_size),
+            },
+            oper_id: value.oper_id,
+            api_name: value.api_name,
+            title: value.title,
+            business_type: value.business_type,
+            request_method: value.request_method,
+            begin_time: value.begin_time,
+            end_time: value.end_time,
+            status: value.status,
+            min_cost_time: value.min_cost_time,
+            request_id: value.request_id,
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct OperLogRes {
+    pub id: i64,
+    pub oper_id: i64,
+    pub oper_name: String,
+    pub dept_id: Option<i64>,
+    pub api_name: String,
+    pub title: String,
+    pub business_type: i16,
+    pub request_method: String,
+    pub oper_url: String,
+    pub oper_ip: String,
+    pub oper_location: String,
+    pub oper_param: String,
+    pub oper_query: String,
+    pub json_result: String,
+    pub status: i16,
+    pub http_status: i32,
+    pub biz_code: Option<i32>,
+    pub error_msg: Option<String>,
+    pub user_agent: String,
+    pub request_id: String,
+    pub oper_time: DateTime<Local>,
+    pub cost_time: i64,
+}
+
+impl From<OperLogVo> for OperLogRes {
+    fn from(value: OperLogVo) -> Self {
+        Self {
+            id: value.id,
+            oper_id: value.oper_id,
+            oper_name: value.oper_name,
+            dept_id: value.dept_id,
+            api_name: value.api_name,
+            title: value.title,
+            business_type: value.business_type,
+            request_method: value.request_method,
+            oper_url: value.oper_url,
+            oper_ip: value.oper_ip,
+            oper_location: value.oper_location,
+            oper_param: value.oper_param,
+            oper_query: value.oper_query,
+            json_result: value.json_result,
+            status: value.status,
+            http_status: value.http_status,
+            biz_code: value.biz_code,
+            error_msg: value.error_msg,
+            user_agent: value.user_agent,
+            request_id: value.request_id,
+            oper_time: value.oper_time,
+            cost_time: value.cost_time,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct OperLogListRes {
+    pub list: Vec<OperLogRes>,
+    pub total: u64,
+}
+
+impl From<OperLogPageVo> for OperLogListRes {
+    fn from(value: OperLogPageVo) -> Self {
+        Self {
+            list: value.list.into_iter().map(OperLogRes::from).collect(),
+            total: value.total,
+        }
+    }
+}
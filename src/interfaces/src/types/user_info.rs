@@ -1,41 +1,40 @@
-use axum::{RequestPartsExt, extract::FromRequestParts, http::request::Parts};
+use std::collections::HashMap;
+
+use axum::{
+    RequestPartsExt,
+    extract::{FromRequestParts, Query},
+    http::request::Parts,
+};
 use axum_extra::TypedHeader;
-use commonx::config::APP_CONFIG;
+use commonx::config::{APP_CONFIG, config::JWTLocation};
 use commonx::error::AppError;
-use headers::{Authorization, authorization::Bearer};
-use jsonwebtoken::{DecodingKey, EncodingKey, Validation, decode, errors::ErrorKind};
-use once_cell::sync::Lazy;
+use commonx::tenant::with_tenant;
+use headers::{Authorization, Cookie, authorization::Bearer};
+use jsonwebtoken::{decode, errors::ErrorKind};
 use serde::{Deserialize, Serialize};
-use userDomain::api::dto::user_info::UserInfoDto;
-use validator::Validate;
-
-use crate::{resp::ApiResponse, types::auth_jwt::Claims};
-
-pub static KEYS: Lazy<Keys> = Lazy::new(|| {
-    let secret = &APP_CONFIG.auth.jwt.secret;
-    Keys::new(secret.as_bytes())
-});
+use userDomain::{api::dto::user_info::UserInfoDto, entity::session::UserSession};
+use validator::{Validate, ValidationError};
 
-pub struct Keys {
-    pub encoding: EncodingKey,
-    pub decoding: DecodingKey,
-}
-
-impl Keys {
-    fn new(secret: &[u8]) -> Self {
-        Self {
-            encoding: EncodingKey::from_secret(secret),
-            decoding: DecodingKey::from_secret(secret),
-        }
-    }
-}
+use crate::{
+    common::jwt_keys::KEYS,
+    controller::{USER_CONTROLLER, user::UserControllerTrait},
+    resp::ApiResponse,
+    types::auth_jwt::Claims,
+};
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct CtxUserInfo {
     pub username: String,
     pub id: i64,
     pub role: i64,
+    pub tenant_id: i64,
+    pub dept_id: Option<i64>,
     pub token: String,
+    /// 登录会话ID, 通过API Key认证时为0
+    pub token_id: i64,
+    pub password_expired: bool,
+    /// 通过API Key认证时的权限范围, JWT认证时为None
+    pub api_key_scopes: Option<Vec<String>>,
 }
 
 impl<S> FromRequestParts<S> for CtxUserInfo
@@ -49,36 +48,57 @@ where
         _state: &S,
     ) -> impl Future<Output = Result<Self, Self::Rejection>> + Send {
         async move {
-            let token_v = get_bear_token(parts).await?;
-            let token_data =
-                match decode::<Claims>(&token_v, &KEYS.decoding, &Validation::default()) {
-                    Ok(token) => token,
-                    Err(err) => match err.kind() {
-                        ErrorKind::InvalidToken => {
-                            return Err(
-                                AppError::AuthError("token错误,请重新登录".to_string()).into()
-                            );
-                        }
-                        ErrorKind::ExpiredSignature => {
-                            return Err(
-                                AppError::AuthError("token过期,请重新登录".to_string()).into()
-                            );
-                        }
-                        _ => {
-                            tracing::info!("AuthError:{:?}", err);
-                            return Err(
-                                AppError::AuthError("token错误,请重新登录".to_string()).into()
-                            );
-                        }
-                    },
+            if let Some(key) = get_api_key(parts) {
+                let principal = USER_CONTROLLER.authenticate_api_key(key).await?;
+                let user = CtxUserInfo {
+                    username: principal.user.username,
+                    id: principal.user.id,
+                    role: principal.user.role_id,
+                    tenant_id: principal.user.tenant_id,
+                    dept_id: principal.user.dept_id,
+                    token: String::new(),
+                    token_id: 0,
+                    password_expired: principal.user.password_expired,
+                    api_key_scopes: Some(principal.scopes),
                 };
+                parts.extensions.insert(user.clone());
+                return Ok(user);
+            }
+            let token_v = get_token(parts).await?;
+            let decoding_key = KEYS.decoding(&token_v)?;
+            let token_data = match decode::<Claims>(&token_v, decoding_key, &KEYS.validation()) {
+                Ok(token) => token,
+                Err(err) => match err.kind() {
+                    ErrorKind::InvalidToken => {
+                        return Err(AppError::AuthError("token错误,请重新登录".to_string()).into());
+                    }
+                    ErrorKind::ExpiredSignature => {
+                        return Err(AppError::AuthError("token过期,请重新登录".to_string()).into());
+                    }
+                    _ => {
+                        tracing::info!("AuthError:{:?}", err);
+                        return Err(AppError::AuthError("token错误,请重新登录".to_string()).into());
+                    }
+                },
+            };
             let claims: Claims = token_data.claims;
             tracing::info!(" userinfo.id:{:?}", claims.id);
+            // 会话数据按租户隔离, 需在token所属租户的上下文中校验
+            with_tenant(
+                claims.tenant_id,
+                USER_CONTROLLER.check_session(claims.token_id),
+            )
+            .await?;
             let user = CtxUserInfo {
                 username: claims.username,
                 id: claims.id,
                 role: claims.role,
+                tenant_id: claims.tenant_id,
+                dept_id: claims.dept_id,
                 token: token_v,
+                token_id: claims.token_id,
+                password_expired: claims.password_expired,
+                api_key_scopes: None,
             };
             parts.extensions.insert(user.clone());
             Ok(user)
@@ -86,6 +106,49 @@ where
     }
 }
 
+/// 启用API Key时, 从配置的请求头中获取
+fn get_api_key(parts: &Parts) -> Option<String> {
+    let config = &APP_CONFIG.auth.api_key;
+    if !config.enable {
+        return None;
+    }
+    parts
+        .headers
+        .get(config.header.as_str())
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+/// 按配置的位置顺序获取token, 取第一个存在的值
+pub async fn get_token(parts: &mut Parts) -> Result<String, AppError> {
+    for location in APP_CONFIG.auth.jwt.locations() {
+        let token = match location {
+            JWTLocation::Bearer => get_bear_token(parts).await.ok(),
+            JWTLocation::Query { name } => get_query_token(parts, &name),
+            JWTLocation::Cookie { name } => get_cookie_token(parts, &name).await,
+        };
+        if let Some(token) = token.filter(|t| !t.is_empty()) {
+            return Ok(token);
+        }
+    }
+    Err(AppError::AuthError("token错误,请重新登录".to_string()))
+}
+
+fn get_query_token(parts: &Parts, name: &str) -> Option<String> {
+    Query::<HashMap<String, String>>::try_from_uri(&parts.uri)
+        .ok()
+        .and_then(|Query(mut params)| params.remove(name))
+}
+
+async fn get_cookie_token(parts: &mut Parts, name: &str) -> Option<String> {
+    parts
+        .extract::<TypedHeader<Cookie>>()
+        .await
+        .ok()
+        .and_then(|TypedHeader(cookie)| cookie.get(name).map(|v| v.to_string()))
+}
+
 pub async fn get_bear_token(parts: &mut Parts) -> Result<String, AppError> {
     let TypedHeader(Authorization(bearer)) = parts
         .extract::<TypedHeader<Authorization<Bearer>>>()
@@ -108,11 +171,26 @@ pub struct ClientInfoReq {
     pub height: Option<u32>,
 }
 
+// 验证码长度与生成时使用同一配置
+fn validate_captcha(captcha: &str) -> Result<(), ValidationError> {
+    let (min, max) = APP_CONFIG.auth.captcha.answer_length();
+    let len = captcha.chars().count();
+    if len < min || len > max {
+        let message = if min == max {
+            format!("验证码长度必须为{}位", min)
+        } else {
+            format!("验证码长度必须在{}-{}之间", min, max)
+        };
+        return Err(ValidationError::new("captcha").with_message(message.into()));
+    }
+    Ok(())
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, Validate, Default)]
 pub struct LoginReq {
     #[validate(length(min = 4, max = 20, message = "用户名长度必须在4-20之间"))]
     pub username: String,
-    #[validate(length(min = 6, max = 20, message = "密码长度必须在6-20之间"))]
+    #[validate(length(min = 1, max = 128, message = "密码长度必须在1-128之间"))]
     pub password: String,
     #[serde(rename = "clientId")]
     pub client_id: Option<String>,
@@ -122,11 +200,11 @@ pub struct LoginReq {
 pub struct LoginWithCaptchaReq {
     #[validate(length(min = 4, max = 20, message = "用户名长度必须在4-20之间"))]
     pub username: String,
-    #[validate(length(min = 6, max = 20, message = "密码长度必须在6-20之间"))]
+    #[validate(length(min = 1, max = 128, message = "密码长度必须在1-128之间"))]
     pub password: String,
     #[serde(rename = "clientId")]
     pub client_id: String,
-    #[validate(length(min = 6, max = 6, message = "验证码长度必须为6位"))]
+    #[validate(custom(function = "validate_captcha"))]
     pub captcha: String,
 }
 
@@ -135,3 +213,138 @@ pub struct LoginResp {
     pub token: String,
     pub user: UserInfoDto,
 }
+
+#[derive(Debug, Serialize, Deserialize, Clone, Validate, Default)]
+pub struct Login2faReq {
+    #[serde(rename = "challengeToken")]
+    pub challenge_token: String,
+    #[validate(length(min = 6, max = 20, message = "动态码长度必须在6-20之间"))]
+    pub code: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Validate, Default)]
+pub struct TotpCodeReq {
+    #[validate(length(min = 6, max = 20, message = "动态码长度必须在6-20之间"))]
+    pub code: String,
+}
+
+/// 启用两步验证的账号, 登录时返回挑战token
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TwoFactorChallengeResp {
+    #[serde(rename = "challengeToken")]
+    pub challenge_token: String,
+    #[serde(rename = "expiresIn")]
+    pub expires_in: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum LoginResult {
+    Token(Box<LoginResp>),
+    TwoFactor(TwoFactorChallengeResp),
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Validate, Default)]
+pub struct OidcAuthorizeReq {
+    #[validate(length(min = 1, max = 64, message = "认证方式不能为空"))]
+    pub provider: String,
+}
+
+/// 前端跳转到该地址完成外部认证
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OidcAuthorizeResp {
+    pub url: String,
+    /// 写入Cookie, 不在响应体中返回
+    #[serde(skip)]
+    pub state: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Validate, Default)]
+pub struct OidcCallbackReq {
+    #[validate(length(min = 1, max = 64, message = "认证方式不能为空"))]
+    pub provider: String,
+    #[validate(length(min = 1, max = 2048, message = "授权码不能为空"))]
+    pub code: String,
+    #[validate(length(min = 1, max = 128, message = "state不能为空"))]
+    pub state: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Validate, Default)]
+pub struct ChangePasswordReq {
+    #[serde(rename = "oldPassword")]
+    #[validate(length(min = 1, max = 128, message = "原密码长度必须在1-128之间"))]
+    pub old_password: String,
+    #[serde(rename = "newPassword")]
+    #[validate(length(min = 1, max = 128, message = "新密码长度必须在1-128之间"))]
+    pub new_password: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Validate, Default)]
+pub struct RegisterReq {
+    #[validate(length(min = 4, max = 20, message = "用户名长度必须在4-20之间"))]
+    pub username: String,
+    #[validate(length(min = 1, max = 128, message = "密码长度必须在1-128之间"))]
+    pub password: String,
+    #[validate(email(message = "邮箱格式不正确"))]
+    pub email: String,
+    #[validate(length(max = 50, message = "姓名长度不能超过50"))]
+    pub name: Option<String>,
+    #[serde(rename = "clientId")]
+    pub client_id: String,
+    #[validate(custom(function = "validate_captcha"))]
+    pub captcha: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Validate, Default)]
+pub struct VerifyEmailReq {
+    #[validate(length(min = 1, max = 128, message = "验证token不能为空"))]
+    pub token: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Validate, Default)]
+pub struct ResendEmailVerifyReq {
+    #[validate(email(message = "邮箱格式不正确"))]
+    pub email: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Validate, Default)]
+pub struct ForgotPasswordReq {
+    #[validate(length(min = 4, max = 20, message = "用户名长度必须在4-20之间"))]
+    pub username: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Validate, Default)]
+pub struct ResetPasswordReq {
+    #[validate(length(min = 1, max = 128, message = "重置token不能为空"))]
+    pub token: String,
+    #[serde(rename = "newPassword")]
+    #[validate(length(min = 1, max = 128, message = "新密码长度必须在1-128之间"))]
+    pub new_password: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Validate, Default)]
+pub struct CreateApiKeyReq {
+    #[validate(length(min = 1, max = 64, message = "名称长度必须在1-64之间"))]
+    pub name: String,
+    #[validate(length(min = 1, max = 20, message = "权限范围数量必须在1-20之间"))]
+    pub scopes: Vec<String>,
+    #[serde(rename = "expiresInDays")]
+    pub expires_in_days: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Validate, Default)]
+pub struct SessionListReq {
+    #[validate(range(min = 1, message = "页码必须大于0"))]
+    pub page: Option<u64>,
+    #[serde(rename = "pageSize")]
+    #[validate(range(min = 1, max = 100, message = "每页数量必须在1-100之间"))]
+    pub page_size: Option<u64>,
+}
+
+/// 会话列表项, current 表示是否为当前请求使用的会话
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SessionRes {
+    #[serde(flatten)]
+    pub session: UserSession,
+    pub current: bool,
+}
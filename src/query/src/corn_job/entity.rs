@@ -1,11 +1,12 @@
 use chrono::{DateTime, Local};
 
-use crate::entity::PageReq;
+use crate::entity::{DataScope, PageReq};
 
 #[derive(Clone, Debug)]
 pub struct ListJobQo {
     pub page_req: PageReq,
     pub class: Option<String>,
+    pub data_scope: DataScope,
 }
 
 impl Default for ListJobQo {
@@ -13,6 +14,7 @@ impl Default for ListJobQo {
         Self {
             page_req: PageReq::default(),
             class: None,
+            data_scope: DataScope::All,
         }
     }
 }
@@ -26,6 +28,8 @@ pub struct JobVo {
     pub queue: Option<String>,
     pub args: Option<String>,
     pub retry: Option<bool>,
+    pub dept_id: Option<i64>,
+    pub create_by: i64,
     pub created_at: DateTime<Local>,
     pub updated_at: DateTime<Local>,
 }
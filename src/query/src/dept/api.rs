@@ -0,0 +1,10 @@
+use async_trait::async_trait;
+use commonx::error::AppError;
+
+use crate::dept::entity::DeptVo;
+
+#[async_trait]
+pub trait DeptQueryTrait {
+    /// 获取当前租户的全部部门
+    async fn list(&self) -> Result<Vec<DeptVo>, AppError>;
+}
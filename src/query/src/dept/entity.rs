@@ -0,0 +1,26 @@
+use chrono::{DateTime, Local};
+
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct DeptVo {
+    pub id: i64,
+    /// 上级部门, 0表示顶级部门
+    pub parent_id: i64,
+    pub name: String,
+    pub order_num: i32,
+    pub status: Option<String>,
+    pub created_at: DateTime<Local>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct DeptTreeVo {
+    pub dept: DeptVo,
+    pub children: Vec<DeptTreeVo>,
+}
+
+/// 解析数据权限所需的调用者信息
+#[derive(Clone, Debug, Default)]
+pub struct ScopeSubject {
+    pub user_id: i64,
+    pub role_id: i64,
+    pub dept_id: Option<i64>,
+}
@@ -0,0 +1,5 @@
+pub mod api;
+pub mod entity;
+pub mod services;
+
+pub const MODEL_DEPT_QUERY: &str = "dept_query";
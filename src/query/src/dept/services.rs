@@ -0,0 +1,136 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use commonx::{
+    config::{APP_CONFIG, config::DataScopeKind},
+    error::AppError,
+};
+use tracing::info;
+
+use crate::{
+    dept::{
+        MODEL_DEPT_QUERY,
+        api::DeptQueryTrait,
+        entity::{DeptTreeVo, DeptVo, ScopeSubject},
+    },
+    entity::DataScope,
+};
+
+pub struct DeptQueryImpl {
+    pub dept_repo: Box<dyn DeptQueryTrait + Sync + Send>,
+}
+
+#[async_trait]
+impl DeptQueryTrait for DeptQueryImpl {
+    async fn list(&self) -> Result<Vec<DeptVo>, AppError> {
+        self.dept_repo.list().await
+    }
+}
+
+impl DeptQueryImpl {
+    pub fn new(dept_repo: Box<dyn DeptQueryTrait + Sync + Send>) -> Self {
+        Self { dept_repo }
+    }
+
+    /// 部门树
+    pub async fn tree(&self) -> Result<Vec<DeptTreeVo>, AppError> {
+        Ok(build_tree(self.dept_repo.list().await?))
+    }
+
+    /// 按调用者角色配置的数据范围解析出过滤条件, 未分配部门时只能看到本人的数据
+    pub async fn data_scope(&self, subject: &ScopeSubject) -> Result<DataScope, AppError> {
+        let kind = APP_CONFIG.auth.data_scope.scope_of(subject.role_id);
+        let scope = match (kind, subject.dept_id) {
+            (DataScopeKind::All, _) => DataScope::All,
+            (DataScopeKind::Own, _) | (_, None) => DataScope::Own(subject.user_id),
+            (DataScopeKind::Dept, Some(dept_id)) => DataScope::Depts(vec![dept_id]),
+            (DataScopeKind::DeptAndChildren, Some(dept_id)) => {
+                DataScope::Depts(dept_and_children(&self.dept_repo.list().await?, dept_id))
+            }
+        };
+        info!(target: MODEL_DEPT_QUERY, "数据权限: user_id:{} role_id:{} -> {:?}", subject.user_id, subject.role_id, scope);
+        Ok(scope)
+    }
+}
+
+/// 按上级部门组装部门树, 同级按排序号排列; 上级部门不存在的视为顶级部门
+pub fn build_tree(mut depts: Vec<DeptVo>) -> Vec<DeptTreeVo> {
+    depts.sort_by_key(|d| (d.order_num, d.id));
+    let ids: Vec<i64> = depts.iter().map(|d| d.id).collect();
+    let mut children: HashMap<i64, Vec<DeptVo>> = HashMap::new();
+    let mut roots = vec![];
+    for dept in depts {
+        if dept.parent_id != 0 && ids.contains(&dept.parent_id) {
+            children.entry(dept.parent_id).or_default().push(dept);
+        } else {
+            roots.push(dept);
+        }
+    }
+    fn attach(dept: DeptVo, children: &mut HashMap<i64, Vec<DeptVo>>) -> DeptTreeVo {
+        let subs = children.remove(&dept.id).unwrap_or_default();
+        DeptTreeVo {
+            dept,
+            children: subs.into_iter().map(|d| attach(d, children)).collect(),
+        }
+    }
+    roots
+        .into_iter()
+        .map(|d| attach(d, &mut children))
+        .collect()
+}
+
+/// 部门及其所有下级部门的ID
+pub fn dept_and_children(depts: &[DeptVo], dept_id: i64) -> Vec<i64> {
+    let mut result = vec![dept_id];
+    let mut i = 0;
+    while i < result.len() {
+        let parent = result[i];
+        for dept in depts {
+            // 防止数据异常形成环时死循环
+            if dept.parent_id == parent && !result.contains(&dept.id) {
+                result.push(dept.id);
+            }
+        }
+        i += 1;
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dept(id: i64, parent_id: i64, order_num: i32) -> DeptVo {
+        DeptVo {
+            id,
+            parent_id,
+            order_num,
+            name: format!("dept-{}", id),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn dept_tree_and_children() {
+        let depts = vec![
+            dept(1, 0, 1),
+            dept(2, 1, 2),
+            dept(3, 1, 1),
+            dept(4, 3, 1),
+            dept(5, 0, 2),
+            // 上级部门不存在
+            dept(6, 99, 1),
+        ];
+        let mut ids = dept_and_children(&depts, 1);
+        ids.sort();
+        assert_eq!(ids, vec![1, 2, 3, 4]);
+        assert_eq!(dept_and_children(&depts, 5), vec![5]);
+
+        let tree = build_tree(depts);
+        let roots: Vec<i64> = tree.iter().map(|n| n.dept.id).collect();
+        assert_eq!(roots, vec![1, 6, 5]);
+        let children: Vec<i64> = tree[0].children.iter().map(|n| n.dept.id).collect();
+        assert_eq!(children, vec![3, 2]);
+        assert_eq!(tree[0].children[0].children[0].dept.id, 4);
+    }
+}
@@ -14,3 +14,26 @@ impl Default for PageReq {
         }
     }
 }
+
+/// 数据权限过滤条件, 由调用者的角色及所属部门解析得到
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum DataScope {
+    /// 不过滤
+    #[default]
+    All,
+    /// 属于这些部门的数据
+    Depts(Vec<i64>),
+    /// 该用户创建的数据
+    Own(i64),
+}
+
+impl DataScope {
+    /// 单条数据是否在权限范围内, 用于按ID查询等无法在SQL中过滤的场景
+    pub fn allows(&self, dept_id: Option<i64>, owner_id: i64) -> bool {
+        match self {
+            DataScope::All => true,
+            DataScope::Depts(dept_ids) => dept_id.is_some_and(|d| dept_ids.contains(&d)),
+            DataScope::Own(user_id) => *user_id == owner_id,
+        }
+    }
+}
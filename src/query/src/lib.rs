@@ -1,2 +1,5 @@
 pub mod corn_job;
+pub mod dept;
 pub mod entity;
+pub mod login_log;
+pub mod oper_log;
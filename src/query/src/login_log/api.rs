@@ -0,0 +1,10 @@
+use async_trait::async_trait;
+use commonx::error::AppError;
+
+use crate::login_log::entity::{ListLoginLogQo, LoginLogPageVo};
+
+#[async_trait]
+pub trait LoginLogQueryTrait {
+    /// 分页查询, 同时返回符合条件的总数
+    async fn list(&self, query: ListLoginLogQo) -> Result<LoginLogPageVo, AppError>;
+}
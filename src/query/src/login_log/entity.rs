@@ -0,0 +1,41 @@
+use chrono::{DateTime, Local};
+
+use crate::entity::{DataScope, PageReq};
+
+#[derive(Clone, Debug, Default)]
+pub struct ListLoginLogQo {
+    pub page_req: PageReq,
+    /// 用户名, 模糊匹配
+    pub username: Option<String>,
+    pub user_id: Option<i64>,
+    pub ip: Option<String>,
+    /// 登录结果: 0成功 1失败
+    pub status: Option<i16>,
+    /// 登录时间范围
+    pub begin_time: Option<DateTime<Local>>,
+    pub end_time: Option<DateTime<Local>>,
+    pub data_scope: DataScope,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct LoginLogVo {
+    pub id: i64,
+    pub user_id: Option<i64>,
+    pub username: String,
+    pub login_type: String,
+    pub ip: String,
+    pub user_agent: String,
+    pub status: i16,
+    pub fail_reason: Option<String>,
+    pub captcha_used: bool,
+    pub two_factor_used: bool,
+    pub request_id: String,
+    pub dept_id: Option<i64>,
+    pub login_time: DateTime<Local>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct LoginLogPageVo {
+    pub list: Vec<LoginLogVo>,
+    pub total: u64,
+}
@@ -0,0 +1,5 @@
+pub mod api;
+pub mod entity;
+pub mod services;
+
+pub const MODEL_LOGIN_LOG_QUERY: &str = "login_log_query";
@@ -0,0 +1,27 @@
+use async_trait::async_trait;
+use commonx::error::AppError;
+use tracing::info;
+
+use crate::login_log::{
+    MODEL_LOGIN_LOG_QUERY,
+    api::LoginLogQueryTrait,
+    entity::{ListLoginLogQo, LoginLogPageVo},
+};
+
+pub struct LoginLogQueryImpl {
+    pub login_log_repo: Box<dyn LoginLogQueryTrait + Sync + Send>,
+}
+
+#[async_trait]
+impl LoginLogQueryTrait for LoginLogQueryImpl {
+    async fn list(&self, query: ListLoginLogQo) -> Result<LoginLogPageVo, AppError> {
+        info!(target: MODEL_LOGIN_LOG_QUERY, "Listing login logs: {:?}", query);
+        self.login_log_repo.list(query).await
+    }
+}
+
+impl LoginLogQueryImpl {
+    pub fn new(login_log_repo: Box<dyn LoginLogQueryTrait + Sync + Send>) -> Self {
+        Self { login_log_repo }
+    }
+}
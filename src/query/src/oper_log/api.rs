@@ -0,0 +1,11 @@
+use async_trait::async_trait;
+use commonx::error::AppError;
+
+use crate::oper_log::entity::{ListOperLogQo, OperLogPageVo, OperLogVo};
+
+#[async_trait]
+pub trait OperLogQueryTrait {
+    async fn get_by_id(&self, id: i64) -> Result<Option<OperLogVo>, AppError>;
+    /// 分页查询, 同时返回符合条件的总数
+    async fn list(&self, query: ListOperLogQo) -> Result<OperLogPageVo, AppError>;
+}
@@ -0,0 +1,59 @@
+use chrono::{DateTime, Local};
+
+use crate::entity::{DataScope, PageReq};
+
+#[derive(Clone, Debug, Default)]
+pub struct ListOperLogQo {
+    pub page_req: PageReq,
+    /// 操作人
+    pub oper_id: Option<i64>,
+    /// 接口名称, 模糊匹配
+    pub api_name: Option<String>,
+    /// 操作名称, 模糊匹配
+    pub title: Option<String>,
+    /// 业务类型: 0其他 1新增 2修改 3删除 4导出 5登录
+    pub business_type: Option<i16>,
+    pub request_method: Option<String>,
+    /// 操作时间范围
+    pub begin_time: Option<DateTime<Local>>,
+    pub end_time: Option<DateTime<Local>>,
+    /// 操作状态: 0成功 1失败
+    pub status: Option<i16>,
+    /// 最小耗时(毫秒)
+    pub min_cost_time: Option<i64>,
+    /// 按请求ID精确查找
+    pub request_id: Option<String>,
+    pub data_scope: DataScope,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct OperLogVo {
+    pub id: i64,
+    pub oper_id: i64,
+    pub oper_name: String,
+    pub dept_id: Option<i64>,
+    pub api_name: String,
+    pub title: String,
+    pub business_type: i16,
+    pub request_method: String,
+    pub oper_url: String,
+    pub oper_ip: String,
+    pub oper_location: String,
+    pub oper_param: String,
+    pub oper_query: String,
+    pub json_result: String,
+    pub status: i16,
+    pub http_status: i32,
+    pub biz_code: Option<i32>,
+    pub error_msg: Option<String>,
+    pub user_agent: String,
+    pub request_id: String,
+    pub oper_time: DateTime<Local>,
+    pub cost_time: i64,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct OperLogPageVo {
+    pub list: Vec<OperLogVo>,
+    pub total: u64,
+}
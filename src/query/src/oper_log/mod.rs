@@ -0,0 +1,5 @@
+pub mod api;
+pub mod entity;
+pub mod services;
+
+pub const MODEL_OPER_LOG_QUERY: &str = "oper_log_query";
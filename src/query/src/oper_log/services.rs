@@ -0,0 +1,129 @@
+use async_trait::async_trait;
+use commonx::{config::APP_CONFIG, error::AppError};
+use tracing::info;
+
+use crate::{
+    entity::PageReq,
+    oper_log::{
+        MODEL_OPER_LOG_QUERY,
+        api::OperLogQueryTrait,
+        entity::{ListOperLogQo, OperLogPageVo, OperLogVo},
+    },
+};
+
+pub struct OperLogQueryImpl {
+    pub oper_log_repo: Box<dyn OperLogQueryTrait + Sync + Send>,
+}
+
+#[async_trait]
+impl OperLogQueryTrait for OperLogQueryImpl {
+    async fn get_by_id(&self, id: i64) -> Result<Option<OperLogVo>, AppError> {
+        info!(target: MODEL_OPER_LOG_QUERY, "Finding oper log with id: {}", id);
+        self.oper_log_repo.get_by_id(id).await
+    }
+
+    async fn list(&self, query: ListOperLogQo) -> Result<OperLogPageVo, AppError> {
+        info!(target: MODEL_OPER_LOG_QUERY, "Listing oper logs: {:?}", query);
+        self.oper_log_repo.list(query).await
+    }
+}
+
+impl OperLogQueryImpl {
+    pub fn new(oper_log_repo: Box<dyn OperLogQueryTrait + Sync + Send>) -> Self {
+        Self { oper_log_repo }
+    }
+
+    /// 按条件导出为CSV, 最多导出配置的行数
+    pub async fn export_csv(&self, mut query: ListOperLogQo) -> Result<String, AppError> {
+        query.page_req = PageReq {
+            page: Some(1),
+            page_size: Some(APP_CONFIG.oper_log.export_max_rows),
+        };
+        let page = self.oper_log_repo.list(query).await?;
+        info!(target: MODEL_OPER_LOG_QUERY, "Exporting {} of {} oper logs", page.list.len(), page.total);
+        Ok(to_csv(&page.list, true))
+    }
+}
+
+const CSV_HEADER: &str = "id,oper_id,oper_name,dept_id,api_name,title,business_type,request_method,oper_url,oper_ip,oper_location,oper_param,oper_query,json_result,status,http_status,biz_code,error_msg,user_agent,request_id,oper_time,cost_time";
+
+/// 转为CSV文本, 归档追加写入时可不输出表头
+pub fn to_csv(logs: &[OperLogVo], with_header: bool) -> String {
+    let mut out = String::new();
+    if with_header {
+        out.push_str(CSV_HEADER);
+        out.push('\n');
+    }
+    for log in logs {
+        let fields = [
+            log.id.to_string(),
+            log.oper_id.to_string(),
+            csv_field(&log.oper_name),
+            log.dept_id.map(|d| d.to_string()).unwrap_or_default(),
+            csv_field(&log.api_name),
+            csv_field(&log.title),
+            log.business_type.to_string(),
+            csv_field(&log.request_method),
+            csv_field(&log.oper_url),
+            csv_field(&log.oper_ip),
+            csv_field(&log.oper_location),
+            csv_field(&log.oper_param),
+            csv_field(&log.oper_query),
+            csv_field(&log.json_result),
+            log.status.to_string(),
+            log.http_status.to_string(),
+            log.biz_code.map(|c| c.to_string()).unwrap_or_default(),
+            csv_field(log.error_msg.as_deref().unwrap_or_default()),
+            csv_field(&log.user_agent),
+            csv_field(&log.request_id),
+            log.oper_time.format("%Y-%m-%d %H:%M:%S").to_string(),
+            log.cost_time.to_string(),
+        ];
+        out.push_str(&fields.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+/// 含逗号、引号、换行的字段用双引号包裹; 以公式字符开头的加单引号前缀, 防止在表格软件中被当作公式执行
+fn csv_field(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@']) {
+        format!("'{}", value)
+    } else {
+        value.to_string()
+    };
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_escapes_fields() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("=SUM(A1)"), "'=SUM(A1)");
+
+        let log = OperLogVo {
+            id: 1,
+            oper_name: "admin".to_string(),
+            json_result: "{\"code\":0,\"msg\":\"ok\"}".to_string(),
+            ..Default::default()
+        };
+        let csv = to_csv(&[log], true);
+        let mut lines = csv.lines();
+        assert_eq!(lines.next(), Some(CSV_HEADER));
+        assert!(
+            lines
+                .next()
+                .unwrap()
+                .contains("\"{\"\"code\"\":0,\"\"msg\"\":\"\"ok\"\"}\"")
+        );
+    }
+}